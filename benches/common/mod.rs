@@ -0,0 +1,15 @@
+// Inputs shared by the benchmarks
+
+// `len` random ACGT bases, from a fixed seed so that every run measures the
+// same sequence
+pub fn random_bases(len: usize) -> Vec<u8> {
+    let mut seed = 0x9E3779B97F4A7C15_u64;
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            b"ACGT"[(seed % 4) as usize]
+        })
+        .collect()
+}
@@ -0,0 +1,130 @@
+/* mod declaration */
+mod common;
+
+/* std use */
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/* crates use */
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/* project use */
+use common::random_bases;
+use kmers::naive_impl::seq_vector::SeqVector;
+use kmers::naive_impl::Kmer;
+
+// Decoding k-mers and sequence ranges to ASCII through `String`s versus into
+// a reused buffer. The allocations made by each way are counted once and
+// printed before its timings.
+
+const N_RECORDS: usize = 10_000;
+const K: usize = 31;
+const RANGE_LEN: usize = 150;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn count_allocations<F: FnMut()>(name: &str, mut f: F) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    let n = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!("{}: {} allocations for {} records", name, n, N_RECORDS);
+}
+
+pub fn kmers(c: &mut Criterion) {
+    let sv = SeqVector::from(random_bases(N_RECORDS + K).as_slice());
+    let kmers: Vec<Kmer> = sv.iter_kmers(K).take(N_RECORDS).collect();
+    let mut g = c.benchmark_group("decode_kmers");
+
+    let mut to_string = || {
+        let mut total = 0;
+        for km in &kmers {
+            total += String::from(km.clone()).len();
+        }
+        black_box(total)
+    };
+    count_allocations("kmer_string_from", || {
+        to_string();
+    });
+    g.bench_function("string_from", |b| b.iter(&mut to_string));
+
+    let mut buf = [0u8; 32];
+    let mut write_into = || {
+        let mut total = 0;
+        for km in &kmers {
+            total += km.write_into(&mut buf).unwrap();
+        }
+        black_box(total)
+    };
+    count_allocations("kmer_write_into", || {
+        write_into();
+    });
+    g.bench_function("write_into", |b| b.iter(&mut write_into));
+}
+
+pub fn ranges(c: &mut Criterion) {
+    let sv = SeqVector::from(random_bases(N_RECORDS + RANGE_LEN).as_slice());
+    let mut g = c.benchmark_group("decode_ranges");
+
+    let mut to_string = || {
+        let mut total = 0;
+        for start in 0..N_RECORDS {
+            total += String::from(&sv.slice(start, start + RANGE_LEN)).len();
+        }
+        black_box(total)
+    };
+    count_allocations("range_string_from", || {
+        to_string();
+    });
+    g.bench_function("string_from", |b| b.iter(&mut to_string));
+
+    let mut buf = vec![0u8; RANGE_LEN];
+    let mut decode_range_into = || {
+        let mut total = 0;
+        for start in 0..N_RECORDS {
+            total += sv
+                .decode_range_into(start, start + RANGE_LEN, &mut buf)
+                .unwrap();
+        }
+        black_box(total)
+    };
+    count_allocations("range_decode_range_into", || {
+        decode_range_into();
+    });
+    g.bench_function("decode_range_into", |b| b.iter(&mut decode_range_into));
+
+    // the output `String` is reused, only the `Display` path is measured
+    let mut out = String::with_capacity(RANGE_LEN);
+    let mut display = || {
+        let mut total = 0;
+        for start in 0..N_RECORDS {
+            out.clear();
+            write!(out, "{}", sv.slice(start, start + RANGE_LEN)).unwrap();
+            total += out.len();
+        }
+        black_box(total)
+    };
+    count_allocations("range_display", || {
+        display();
+    });
+    g.bench_function("display", |b| b.iter(&mut display));
+}
+
+criterion_group!(benches, kmers, ranges);
+criterion_main!(benches);
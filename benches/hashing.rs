@@ -0,0 +1,127 @@
+/* mod declaration */
+mod common;
+
+/* std use */
+use std::collections::hash_map::RandomState;
+
+/* crates use */
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/* project use */
+use common::random_bases;
+use kmers::naive_impl::hash::{
+    hash_one, CanonicalHashIterator, NtHashIterator, NtHashState, SeededState,
+};
+use kmers::naive_impl::seq_vector::minimizers::CanonicalMinimizerIter;
+use kmers::naive_impl::seq_vector::SeqVector;
+use kmers::naive_impl::CanonicalKmerIterator;
+
+// Hashing every k-mer (and minimizers) of a 1 Mbp sequence with
+// std's RandomState and SeededState versus ntHash, rolled or recomputed per
+// k-mer.
+
+const LEN: usize = 1 << 20;
+const K: usize = 31;
+const W: usize = 19;
+
+pub fn kmer_hashes(c: &mut Criterion) {
+    let sv = SeqVector::from(random_bases(LEN).as_slice());
+    let mut g = c.benchmark_group("kmer_hashes");
+    g.sample_size(10);
+
+    g.bench_function("random_state", |b| {
+        let state = RandomState::new();
+        b.iter(|| {
+            sv.iter_kmers(K)
+                .map(|km| hash_one(&state, km))
+                .fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.bench_function("seeded", |b| {
+        let state = SeededState(7);
+        b.iter(|| {
+            sv.iter_kmers(K)
+                .map(|km| hash_one(&state, km))
+                .fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.bench_function("nthash", |b| {
+        let state = NtHashState::new(K);
+        b.iter(|| {
+            sv.iter_kmers(K)
+                .map(|km| hash_one(&state, km))
+                .fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.bench_function("nthash_rolling", |b| {
+        b.iter(|| NtHashIterator::new(&sv.as_slice(), K).fold(0, |acc, h| acc ^ h));
+    });
+
+    g.finish();
+}
+
+pub fn minimizers(c: &mut Criterion) {
+    let sv = SeqVector::from(random_bases(LEN).as_slice());
+    let mut g = c.benchmark_group("canonical_minimizers");
+    g.sample_size(10);
+
+    g.bench_function("random_state", |b| {
+        b.iter(|| {
+            black_box(
+                sv.iter_canonical_minimizers(K, W, RandomState::new())
+                    .count(),
+            )
+        });
+    });
+
+    g.bench_function("nthash", |b| {
+        b.iter(|| black_box(CanonicalMinimizerIter::with_nthash(sv.as_slice(), K, W).count()));
+    });
+
+    g.finish();
+}
+
+pub fn canonical_hashes(c: &mut Criterion) {
+    let seq = random_bases(LEN);
+    let sv = SeqVector::from(seq.as_slice());
+    let state = RandomState::new();
+    let mut g = c.benchmark_group("canonical_hashes");
+    g.sample_size(10);
+
+    g.bench_function("seq_vector_kmers", |b| {
+        b.iter(|| {
+            sv.iter_canonical_kmers(K)
+                .map(|km| hash_one(&state, km.get_canonical_word()))
+                .fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.bench_function("seq_vector_rolling", |b| {
+        b.iter(|| {
+            sv.iter_canonical_hashes(K, state.clone())
+                .fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.bench_function("u8_slice_kmers", |b| {
+        b.iter(|| {
+            CanonicalKmerIterator::from_u8_slice(&seq, K as u8)
+                .map(|ckp| hash_one(&state, ckp.km.get_canonical_word()))
+                .fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.bench_function("u8_slice_rolling", |b| {
+        b.iter(|| {
+            CanonicalHashIterator::from_u8_slice(&seq, K, state.clone()).fold(0, |acc, h| acc ^ h)
+        });
+    });
+
+    g.finish();
+}
+
+criterion_group!(benches, kmer_hashes, minimizers, canonical_hashes);
+criterion_main!(benches);
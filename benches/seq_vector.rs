@@ -0,0 +1,253 @@
+/* mod declaration */
+mod common;
+
+/* std use */
+
+/* crates use */
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/* project use */
+use common::random_bases;
+use kmers::naive_impl;
+use kmers::naive_impl::seq_vector::SeqVector;
+use kmers::naive_impl::KmerVec;
+
+// Run with and without `--features simd` to compare the packing paths;
+// `scalar` always encodes each word through `Kmer::from`.
+
+pub fn scalar_words(b: &[u8]) -> u64 {
+    b.chunks(32)
+        .map(|x| naive_impl::Kmer::from(x).into_u64())
+        .fold(0, |acc, w| acc ^ w)
+}
+
+pub fn encode(c: &mut Criterion) {
+    let mut g = c.benchmark_group("seq_vector_encode");
+
+    for i in 8..20 {
+        let bytes = random_bases(1 << i);
+
+        g.bench_with_input(BenchmarkId::new("scalar", 1 << i), &bytes, |b, s| {
+            b.iter(|| black_box(scalar_words(s)));
+        });
+
+        g.bench_with_input(BenchmarkId::new("from", 1 << i), &bytes, |b, s| {
+            b.iter(|| black_box(SeqVector::from(s.as_slice())));
+        });
+
+        g.bench_with_input(BenchmarkId::new("push_chars", 1 << i), &bytes, |b, s| {
+            b.iter(|| {
+                let mut sv = SeqVector::with_capacity(s.len());
+                sv.push_chars(s);
+                black_box(sv)
+            });
+        });
+    }
+}
+
+pub fn canonical_kmers(c: &mut Criterion) {
+    let mut g = c.benchmark_group("seq_vector_canonical_kmers");
+    let k = 31;
+
+    for i in [12, 16, 20] {
+        let sv = SeqVector::from(random_bases(1 << i).as_slice());
+
+        g.bench_with_input(BenchmarkId::new("from_u64", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                (0..=sv.len() - k)
+                    .map(|p| {
+                        naive_impl::CanonicalKmer::from_u64(sv.get_kmer_u64(p, k), k as u8)
+                            .get_canonical_word()
+                    })
+                    .fold(0, |acc, w| acc ^ w)
+            });
+        });
+
+        g.bench_with_input(BenchmarkId::new("rolling", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                sv.iter_canonical_kmers(k)
+                    .map(|km| km.get_canonical_word())
+                    .fold(0, |acc, w| acc ^ w)
+            });
+        });
+    }
+}
+
+pub fn minimizers(c: &mut Criterion) {
+    let mut g = c.benchmark_group("minimizers_k31_w15");
+    let (k, w) = (31, 15);
+    let state = naive_impl::hash::LexHasherState::new(w);
+
+    for i in [12, 16] {
+        let sv = SeqVector::from(random_bases(1 << i).as_slice());
+
+        // every l-mer is hashed once per k-mer containing it
+        g.bench_with_input(BenchmarkId::new("per_kmer", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                (0..=sv.len() - k)
+                    .map(|p| {
+                        naive_impl::Kmer::minimizer_word(sv.get_kmer_u64(p, k), k, w, &state).0
+                    })
+                    .fold(0, |acc, w| acc ^ w)
+            });
+        });
+
+        // both strands of every l-mer, once per k-mer containing it
+        g.bench_with_input(
+            BenchmarkId::new("canonical_per_kmer", 1 << i),
+            &sv,
+            |b, sv| {
+                b.iter(|| {
+                    (0..=sv.len() - k)
+                        .map(|p| {
+                            let word = sv.get_kmer_u64(p, k);
+                            naive_impl::Kmer::canonical_minimizer_word(word, k, w, &state).0
+                        })
+                        .fold(0, |acc, w| acc ^ w)
+                });
+            },
+        );
+
+        // every l-mer is hashed once
+        g.bench_with_input(BenchmarkId::new("queue", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                let lmers = (0..=sv.len() - w).map(|p| sv.get_kmer_u64(p, w));
+                naive_impl::minimizer_queue::minimizers_of_word_seq(lmers, k, w, &state)
+                    .map(|(mm, _)| mm)
+                    .fold(0, |acc, w| acc ^ w)
+            });
+        });
+    }
+}
+
+pub fn kmer_words(c: &mut Criterion) {
+    let mut g = c.benchmark_group("seq_vector_kmer_words_k31");
+    let k = 31;
+
+    for i in [16, 20] {
+        let sv = SeqVector::from(random_bases(1 << i).as_slice());
+        g.throughput(Throughput::Elements((sv.len() - k + 1) as u64));
+
+        g.bench_with_input(BenchmarkId::new("iter_kmers", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                let words: Vec<u64> = sv.iter_kmers(k).map(|km| km.into_u64()).collect();
+                black_box(words)
+            });
+        });
+
+        g.bench_with_input(BenchmarkId::new("fill_kmer_words", 1 << i), &sv, |b, sv| {
+            let mut words = Vec::new();
+            b.iter(|| {
+                words.clear();
+                sv.fill_kmer_words(k, &mut words);
+                black_box(words.len())
+            });
+        });
+    }
+}
+
+pub fn canonicalize(c: &mut Criterion) {
+    let mut g = c.benchmark_group("kmer_vec_canonicalize_k31");
+    let k = 31;
+
+    for i in [16, 20] {
+        let sv = SeqVector::from(random_bases(1 << i).as_slice());
+        let kmers: Vec<naive_impl::Kmer> = sv.iter_kmers(k).collect();
+        let mut kv = KmerVec::with_capacity(k as u8, kmers.len());
+        for km in &kmers {
+            kv.push(km);
+        }
+        g.throughput(Throughput::Elements(kmers.len() as u64));
+
+        g.bench_with_input(
+            BenchmarkId::new("vec_of_kmers", 1 << i),
+            &kmers,
+            |b, kmers| {
+                b.iter(|| {
+                    let canonical: Vec<naive_impl::Kmer> =
+                        kmers.iter().map(|km| km.to_canonical()).collect();
+                    black_box(canonical)
+                });
+            },
+        );
+
+        g.bench_with_input(BenchmarkId::new("kmer_vec", 1 << i), &kv, |b, kv| {
+            b.iter(|| {
+                let mut kv = kv.clone();
+                kv.canonicalize_in_place();
+                black_box(kv)
+            });
+        });
+    }
+}
+
+pub fn reverse_complement(c: &mut Criterion) {
+    let mut g = c.benchmark_group("seq_vector_reverse_complement");
+
+    for i in [12, 16, 20, 24] {
+        let sv = SeqVector::from(random_bases(1 << i).as_slice());
+        g.throughput(Throughput::Bytes(1 << i));
+
+        // one base at a time, through `Kmer`
+        g.bench_with_input(BenchmarkId::new("per_base", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                let mut rc = SeqVector::with_capacity(sv.len());
+                for p in (0..sv.len()).rev() {
+                    let base = naive_impl::Kmer::from_u64(sv.get_base(p), 1);
+                    rc.push_chars(&base.to_reverse_complement().to_bytes());
+                }
+                black_box(rc)
+            });
+        });
+
+        g.bench_with_input(BenchmarkId::new("words", 1 << i), &sv, |b, sv| {
+            b.iter(|| black_box(sv.reverse_complement()));
+        });
+
+        g.bench_with_input(BenchmarkId::new("in_place", 1 << i), &sv, |b, sv| {
+            let mut sv = sv.clone();
+            b.iter(|| {
+                let len = sv.len();
+                sv.reverse_complement_range(0, len);
+                black_box(sv.get_base(0))
+            });
+        });
+    }
+}
+
+pub fn base_counts(c: &mut Criterion) {
+    let mut g = c.benchmark_group("seq_vector_base_counts");
+
+    for i in [12, 16, 20, 24] {
+        let sv = SeqVector::from(random_bases(1 << i).as_slice());
+        // not aligned on words
+        let (start, end) = (5, sv.len() - 3);
+        g.throughput(Throughput::Elements((end - start) as u64));
+
+        g.bench_with_input(BenchmarkId::new("per_base", 1 << i), &sv, |b, sv| {
+            b.iter(|| {
+                let mut counts = [0u64; 4];
+                for p in start..end {
+                    counts[sv.get_base(p) as usize] += 1;
+                }
+                black_box(counts)
+            });
+        });
+
+        g.bench_with_input(BenchmarkId::new("words", 1 << i), &sv, |b, sv| {
+            b.iter(|| black_box(sv.count_bases_in_range(start, end)));
+        });
+    }
+}
+
+criterion_group!(
+    benches,
+    encode,
+    canonical_kmers,
+    minimizers,
+    kmer_words,
+    canonicalize,
+    reverse_complement,
+    base_counts
+);
+criterion_main!(benches);
@@ -7,7 +7,7 @@ use random_string::generate;
 /* project use */
 use kmers::naive_impl;
 
-use kmers::encoding::Encoding as _;
+use kmers::encoding::{Encoding as _, NucleotideEncoding as _};
 
 const K: usize = 31;
 
@@ -33,6 +33,14 @@ pub fn compute_xor10(b: &[u8]) -> u64 {
         .sum()
 }
 
+pub fn compute_xor10_rolling(b: &[u8]) -> u64 {
+    let enc = kmers::encoding::xor10::Xor10;
+
+    kmers::kmer::Kmer::<u64, K, { kmers::kmer::word_for_k::<u64, K>() }>::iter_sequence(b, &enc)
+        .map(|k| k.get(0))
+        .sum()
+}
+
 pub fn rc_naive(b: &[u8]) -> u64 {
     b.windows(K)
         .map(|x| {
@@ -55,6 +63,24 @@ pub fn rc_xor10(b: &[u8]) -> u64 {
         .sum::<u64>()
 }
 
+pub fn rc_kmer128(b: &[u8]) -> u64 {
+    b.windows(K)
+        .map(|x| {
+            let k = naive_impl::Kmer128::from(x);
+            k.to_reverse_complement().into_u128() as u64
+        })
+        .sum::<u64>()
+}
+
+pub fn rc_kmer64(b: &[u8]) -> u64 {
+    b.windows(K)
+        .map(|x| {
+            let k = naive_impl::Kmer::from(x);
+            k.to_reverse_complement().into_u64()
+        })
+        .sum::<u64>()
+}
+
 pub fn construct(c: &mut Criterion) {
     let charset = "ACGT";
 
@@ -75,6 +101,14 @@ pub fn construct(c: &mut Criterion) {
                 b.iter(|| black_box(compute_xor10(s)));
             },
         );
+
+        g.bench_with_input(
+            BenchmarkId::new("kme.rs::xor10_rolling", 1 << i),
+            &bytes,
+            |b, &s| {
+                b.iter(|| black_box(compute_xor10_rolling(s)));
+            },
+        );
     }
 }
 
@@ -101,9 +135,30 @@ pub fn reverse_complement(c: &mut Criterion) {
     }
 }
 
+// the u128 backed k-mer against the u64 one, at a k both can hold
+pub fn kmer128(c: &mut Criterion) {
+    let charset = "ACGT";
+
+    let mut g = c.benchmark_group("kmer128");
+
+    for i in 8..16 {
+        let input = generate(1 << i, charset);
+        let bytes = input.as_bytes();
+
+        g.bench_with_input(BenchmarkId::new("u64", 1 << i), &bytes, |b, &s| {
+            b.iter(|| black_box(rc_kmer64(s)));
+        });
+
+        g.bench_with_input(BenchmarkId::new("u128", 1 << i), &bytes, |b, &s| {
+            b.iter(|| black_box(rc_kmer128(s)));
+        });
+    }
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     construct(c);
     reverse_complement(c);
+    kmer128(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
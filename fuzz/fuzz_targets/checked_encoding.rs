@@ -0,0 +1,28 @@
+//! Arbitrary bytes through the checked constructors and the k-mer iterator: none of them should panic,
+//! and whatever they accept must decode back to the input.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use kmers::naive_impl::seq_vector::SeqVector;
+use kmers::naive_impl::{CanonicalKmerIterator, Kmer};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(km) = Kmer::from_bytes_checked(data) {
+        assert_eq!(km.to_bytes(), data.to_ascii_uppercase());
+    }
+
+    if let Ok(sv) = SeqVector::from_bytes_checked(data) {
+        assert_eq!(sv.to_string().into_bytes(), data.to_ascii_uppercase());
+    }
+
+    // the first byte picks k, the rest is the sequence
+    if let Some((&k, seq)) = data.split_first() {
+        let k = k % 32 + 1;
+        for ckp in CanonicalKmerIterator::from_u8_slice(seq, k) {
+            let pos = ckp.pos as usize;
+            let window = &seq[pos..pos + k as usize];
+            assert_eq!(ckp.km.get_fw_mer().to_bytes(), window.to_ascii_uppercase());
+        }
+    }
+});
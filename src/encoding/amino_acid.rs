@@ -0,0 +1,194 @@
+//! Encoding of protein sequences on 5 bits per residue
+//! The 20 standard amino acids are encoded in alphabetical order of their one letter code, A -> 00000, C -> 00001, ..., Y -> 10011.
+//! Any other symbol, like X or the stop codon *, can't be encoded.
+
+/* crate use */
+use bit_field::BitArray as _;
+
+/* project use */
+use super::EncodeError;
+
+/// One letter codes of the 20 standard amino acids, in encoding order
+const RESIDUES: &[u8; 20] = b"ACDEFGHIKLMNPQRSTVWY";
+
+/// Marker of bytes that aren't a standard amino acid in RESIDUE2BITS
+const INVALID: u8 = 0xFF;
+
+/// Build the lookup table from ASCII to 5 bits code, for upper and lower case
+const fn residue2bits_table() -> [u8; 256] {
+    let mut table = [INVALID; 256];
+
+    let mut code = 0;
+    while code < RESIDUES.len() {
+        table[RESIDUES[code] as usize] = code as u8;
+        table[RESIDUES[code].to_ascii_lowercase() as usize] = code as u8;
+        code += 1;
+    }
+
+    table
+}
+
+/// Lookup table usefull to convert ASCII in internal encoding
+const RESIDUE2BITS: [u8; 256] = residue2bits_table();
+
+#[derive(Clone, Copy, Debug)]
+pub struct AminoAcid;
+
+impl AminoAcid {
+    /// Number of bits used to store a residue
+    pub const BITS: usize = 5;
+
+    /// Convert residue in encoding corresponding 5 bits, None if it isn't a standard amino acid
+    #[inline]
+    pub(crate) fn residue2bits(&self, residue: u8) -> Option<u8> {
+        match RESIDUE2BITS[residue as usize] {
+            INVALID => None,
+            code => Some(code),
+        }
+    }
+
+    /// Convert residue encode on 5 bits in ASCII, X for codes without residue
+    #[inline]
+    pub(crate) fn bits2residue<P>(&self, bits: P) -> u8
+    where
+        P: crate::utils::Data,
+    {
+        *RESIDUES.get(bits.to_u8() as usize).unwrap_or(&b'X')
+    }
+
+    /// Like Encoding::encode but return an error on the first residue that can't be encoded, or if seq doesn't fit in the array, instead of panicking
+    pub fn encode_checked<P, const B: usize>(&self, seq: &[u8]) -> Result<[P; B], EncodeError>
+    where
+        P: crate::utils::Data,
+    {
+        if seq.len() * Self::BITS > B * P::BIT_LENGTH {
+            return Err(EncodeError::TooLong { len: seq.len() });
+        }
+
+        let mut array: [P; B] = unsafe { [std::mem::zeroed(); B] };
+
+        for (idx, residue) in seq.iter().enumerate() {
+            let code = self
+                .residue2bits(*residue)
+                .ok_or(EncodeError::InvalidSymbol {
+                    pos: idx,
+                    byte: *residue,
+                })?;
+            array.set_bits(idx * Self::BITS..(idx + 1) * Self::BITS, P::from(code));
+        }
+
+        Ok(array)
+    }
+}
+
+impl<P, const B: usize> super::Encoding<P, B> for AminoAcid
+where
+    P: crate::utils::Data,
+{
+    const BITS: usize = AminoAcid::BITS;
+
+    /// Panic if seq contains anything else than the 20 standard amino acids
+    fn encode(&self, seq: &[u8]) -> [P; B] {
+        match self.encode_checked(seq) {
+            Ok(array) => array,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    fn decode(&self, array: [P; B]) -> Vec<u8> {
+        let mut seq = Vec::with_capacity(B * P::BIT_LENGTH / Self::BITS);
+
+        for idx in 0..array.len() * P::BIT_LENGTH / Self::BITS {
+            let value = array.get_bits(idx * Self::BITS..(idx + 1) * Self::BITS);
+
+            seq.push(self.bits2residue(value));
+        }
+
+        seq
+    }
+
+    /// Panic if nuc isn't one of the 20 standard amino acids
+    fn encode_base(&self, nuc: u8) -> P {
+        match self.residue2bits(nuc) {
+            Some(code) => P::from(code),
+            None => panic!("{}", EncodeError::InvalidSymbol { pos: 0, byte: nuc }),
+        }
+    }
+
+    fn decode_base(&self, bits: P) -> u8 {
+        self.bits2residue(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* Project use */
+    use crate::encoding::Encoding as _;
+    use crate::kmer::words_for_k_bits;
+
+    const PROTEIN: &[u8] = b"MKTAYIAKQRQISFVKSHFSRQLEERLGLIEVQAPILSRVGDGTQDNLSGAEKAVQVKVKALPDAQFEVVHSLAKWKRQTLGQHDFSAGEGLYTHMKALRPDEDRLSPLHSVYVDQWDWERVMGDGERQFSTLKSTVEAIWAGIKATEAAVSEEFGLAPFLPDQIHFVHSQELLSRYPDLDAKGRERAIAKDLGAVFLVGIGGKLSDGHRHDVRAPDYDDWVAIRERLTRLNFIIQEPYT";
+
+    #[test]
+    fn one_residue_encoding() {
+        for (code, residue) in RESIDUES.iter().enumerate() {
+            assert_eq!(AminoAcid.residue2bits(*residue), Some(code as u8));
+            assert_eq!(
+                AminoAcid.residue2bits(residue.to_ascii_lowercase()),
+                Some(code as u8)
+            );
+            assert_eq!(AminoAcid.bits2residue(code as u8), *residue);
+        }
+
+        for residue in [b'X', b'x', b'*', b'B', b'J', b'O', b'U', b'Z', b'-'] {
+            assert_eq!(AminoAcid.residue2bits(residue), None);
+        }
+    }
+
+    /// Longest sequence encoded in round trip tests
+    const N: usize = 60;
+
+    fn check_round_trip<P, const B: usize>()
+    where
+        P: crate::utils::Data,
+    {
+        for len in [0, 1, 5, 12, 13, 25, 26, 51, N] {
+            let seq = &PROTEIN[len..2 * len];
+            let array: [P; B] = AminoAcid.encode(seq);
+
+            let decoded = AminoAcid.decode(array);
+            assert_eq!(decoded[..len], *seq);
+            // the padding is decoded as the residue of code 0
+            assert!(decoded[len..N].iter().all(|r| *r == b'A'));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        check_round_trip::<u8, { words_for_k_bits::<u8>(N, 5) }>();
+        check_round_trip::<u64, { words_for_k_bits::<u64>(N, 5) }>();
+        check_round_trip::<u128, { words_for_k_bits::<u128>(N, 5) }>();
+    }
+
+    #[test]
+    fn invalid_residue() {
+        let res: Result<[u64; 2], _> = AminoAcid.encode_checked(b"MKTAYXAK");
+        assert_eq!(res, Err(EncodeError::InvalidSymbol { pos: 5, byte: b'X' }));
+
+        // 2 words of 64 bits hold 25 residues
+        let res: Result<[u64; 2], _> = AminoAcid.encode_checked(&PROTEIN[..26]);
+        assert_eq!(res, Err(EncodeError::TooLong { len: 26 }));
+        let res: Result<[u64; 2], _> = AminoAcid.encode_checked(&PROTEIN[..25]);
+        assert!(res.is_ok());
+
+        let res: Result<[u64; 2], _> = AminoAcid.encode_checked(b"mktay");
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot encode 'X' (0x58) at position 2")]
+    fn encode_x_panics() {
+        let _: [u64; 1] = AminoAcid.encode(b"ACX");
+    }
+}
@@ -1,23 +1,269 @@
 //! Mod define encoding trait and type implementing this trait
 
+/* crate use */
+use bit_field::BitArray as _;
+
 /* project use */
 
 /* mod declaration */
+pub mod amino_acid;
 pub mod naive;
 pub mod xor10;
 
 /* public use */
+pub use amino_acid::AminoAcid;
 pub use naive::Naive;
 pub use xor10::Xor10;
 
-/// Trait use by Kmer struct, to convert DNA encode on 8 bits to 2 bits encoding, inverte this operation and perform a reverse complement on the 2 bits encoding
+/// Error returned by the checked encoding functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The symbol `byte`, at position `pos` of the input, has no code in the encoding
+    InvalidSymbol { pos: usize, byte: u8 },
+    /// The `len` symbols of the input don't fit in the array
+    TooLong { len: usize },
+}
+
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidSymbol { pos, byte } => write!(
+                f,
+                "cannot encode {:?} (0x{:02x}) at position {}",
+                *byte as char, byte, pos
+            ),
+            Self::TooLong { len } => write!(f, "sequence of length {} is too long", len),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Trait use by Kmer struct, to convert a sequence encode on 8 bits per symbol to BITS bits per symbol and inverte this operation
 pub trait Encoding<P, const B: usize> {
-    /// Convert a DNA sequence, encode with 8 bits per nucleotide in a DNA sequence encode on 2 bits per nucleotide
+    /// Number of bits used to store a symbol, 2 for nucleotides
+    const BITS: usize = 2;
+
+    /// Convert a sequence, encode with 8 bits per symbol in a sequence encode on BITS bits per symbol
     fn encode(&self, seq: &[u8]) -> [P; B];
 
-    /// Convert a DNA sequence, encode on 2 bits per nucleotide in a DNA sequence on 8 bits per nucleotide
+    /// Convert a sequence, encode on BITS bits per symbol in a sequence on 8 bits per symbol
     fn decode(&self, array: [P; B]) -> Vec<u8>;
 
-    /// Perform a reverse complement on a DNA sequence encode on 2 bits per nucleotide
+    /// Convert one symbol, encode with 8 bits, in its BITS bits code
+    fn encode_base(&self, nuc: u8) -> P
+    where
+        P: crate::utils::Data,
+    {
+        self.encode(&[nuc]).get_bits(0..Self::BITS)
+    }
+
+    /// Convert the BITS bits code of one symbol in 8 bits
+    fn decode_base(&self, bits: P) -> u8
+    where
+        P: crate::utils::Data,
+    {
+        let mut array = [P::from(0); B];
+        array.set_bits(0..Self::BITS, bits);
+        self.decode(array)[0]
+    }
+}
+
+/// Trait of DNA encodings, on 2 bits per nucleotide, where reverse complement is defined
+pub trait NucleotideEncoding<P, const B: usize>: Encoding<P, B> {
+    /// Perform a reverse complement on the K first nucleotides of a DNA sequence encode on 2 bits per nucleotide
     fn rev_comp<const K: usize>(&self, array: [P; B]) -> [P; B];
+
+    /// Complement the K first nucleotides without reversing them, the bits after them are unchanged
+    fn comp<const K: usize>(&self, mut array: [P; B]) -> [P; B]
+    where
+        P: crate::utils::Data,
+    {
+        for idx in 0..K {
+            let i = idx * 2;
+            let comp_i = self.complement_base(array.get_bits(i..i + 2));
+            array.set_bits(i..i + 2, comp_i);
+        }
+
+        array
+    }
+
+    /// Get the complement of a nucleotide encode in 2 bits
+    fn complement_base(&self, bits: P) -> P
+    where
+        P: crate::utils::Data,
+    {
+        let mut array = [P::from(0); B];
+        array.set_bits(0..2, bits);
+        self.rev_comp::<1>(array).get_bits(0..2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::kmer::word_for_k;
+    use crate::test_utils::rc as true_rev_comp;
+
+    const SEQ: &[u8] = b"TAAGGATTCTAATCATAAGGATTCTAATCATAAGGATTCTAATCATAAGGATTCTAATCAGGGGG";
+
+    fn check_rev_comp<E, P, const K: usize, const B: usize>(encoder: &E)
+    where
+        E: NucleotideEncoding<P, B>,
+    {
+        let array: [P; B] = encoder.encode(&SEQ[..K]);
+        let rc = encoder.decode(encoder.rev_comp::<K>(array));
+
+        assert_eq!(rc[..K], true_rev_comp(&SEQ[..K]));
+    }
+
+    macro_rules! check_all_k {
+        ($encoder:expr, $($ty:ty), *) => (
+            $(
+                check_rev_comp::<_, $ty, 1, { word_for_k::<$ty, 1>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 3, { word_for_k::<$ty, 3>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 4, { word_for_k::<$ty, 4>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 15, { word_for_k::<$ty, 15>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 31, { word_for_k::<$ty, 31>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 32, { word_for_k::<$ty, 32>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 45, { word_for_k::<$ty, 45>() }>(&$encoder);
+                check_rev_comp::<_, $ty, 65, { word_for_k::<$ty, 65>() }>(&$encoder);
+            )*
+        )
+    }
+
+    #[test]
+    fn rev_comp_respects_k() {
+        check_all_k!(Naive::ACGT, u8, u16, u32, u64, u128);
+        check_all_k!(Naive::GTCA, u8, u16, u32, u64, u128);
+        check_all_k!(Xor10, u8, u16, u32, u64, u128);
+    }
+
+    /// Any sequence of K bases, built from random bytes
+    fn random_kmer<const K: usize>(bytes: &[u8]) -> [u8; K] {
+        let mut kmer = [b'A'; K];
+        for (nuc, byte) in kmer.iter_mut().zip(bytes) {
+            *nuc = b"ACGT"[(byte & 0b11) as usize];
+        }
+        kmer
+    }
+
+    fn rev_comp_matches<E, P, const K: usize, const B: usize>(encoder: &E, seq: &[u8]) -> bool
+    where
+        E: NucleotideEncoding<P, B>,
+    {
+        let rc = encoder.decode(encoder.rev_comp::<K>(encoder.encode(seq)));
+        rc[..K] == true_rev_comp(seq)
+    }
+
+    macro_rules! rev_comp_property {
+        ($name:ident, $k:expr, $($ty:ty), *) => (
+            #[quickcheck]
+            fn $name(bytes: Vec<u8>) -> bool {
+                let seq = random_kmer::<$k>(&bytes);
+                true $(
+                    && rev_comp_matches::<_, $ty, $k, { word_for_k::<$ty, $k>() }>(&Naive::ACGT, &seq)
+                    && rev_comp_matches::<_, $ty, $k, { word_for_k::<$ty, $k>() }>(&Xor10, &seq)
+                )*
+            }
+        )
+    }
+
+    rev_comp_property!(rev_comp_random_1, 1, u8, u64);
+    rev_comp_property!(rev_comp_random_15, 15, u8, u16, u32, u64, u128);
+    rev_comp_property!(rev_comp_random_32, 32, u8, u16, u32, u64, u128);
+    rev_comp_property!(rev_comp_random_45, 45, u8, u16, u32, u64, u128);
+
+    fn true_comp(seq: &[u8]) -> Vec<u8> {
+        let mut comp = true_rev_comp(seq);
+        comp.reverse();
+        comp
+    }
+
+    fn check_comp<E, P, const K: usize, const B: usize>(encoder: &E)
+    where
+        E: NucleotideEncoding<P, B>,
+        P: crate::utils::Data,
+    {
+        let array: [P; B] = encoder.encode(SEQ);
+        let comp = encoder.decode(encoder.comp::<K>(array));
+        let len = SEQ.len().min(comp.len());
+
+        assert_eq!(comp[..K], true_comp(&SEQ[..K]));
+        // the bases after K are not complemented
+        assert_eq!(comp[K..len], SEQ[K..len]);
+    }
+
+    macro_rules! check_comp_all_k {
+        ($encoder:expr, $($ty:ty), *) => (
+            $(
+                check_comp::<_, $ty, 1, { word_for_k::<$ty, 65>() }>(&$encoder);
+                check_comp::<_, $ty, 4, { word_for_k::<$ty, 65>() }>(&$encoder);
+                check_comp::<_, $ty, 15, { word_for_k::<$ty, 65>() }>(&$encoder);
+                check_comp::<_, $ty, 32, { word_for_k::<$ty, 65>() }>(&$encoder);
+                check_comp::<_, $ty, 45, { word_for_k::<$ty, 65>() }>(&$encoder);
+                check_comp::<_, $ty, 65, { word_for_k::<$ty, 65>() }>(&$encoder);
+            )*
+        )
+    }
+
+    #[test]
+    fn comp_respects_k() {
+        check_comp_all_k!(Naive::ACGT, u8, u16, u32, u64, u128);
+        check_comp_all_k!(Naive::GTCA, u8, u16, u32, u64, u128);
+        check_comp_all_k!(Naive::AGTC, u8, u64);
+        check_comp_all_k!(Xor10, u8, u16, u32, u64, u128);
+        check_comp_all_k!(RequiredOnly(Naive::GTCA), u8, u64);
+    }
+
+    /// An encoding relying on the default of every provided method
+    struct RequiredOnly(Naive);
+
+    impl<P, const B: usize> Encoding<P, B> for RequiredOnly
+    where
+        P: crate::utils::Data + num::PrimInt,
+    {
+        fn encode(&self, seq: &[u8]) -> [P; B] {
+            self.0.encode(seq)
+        }
+
+        fn decode(&self, array: [P; B]) -> Vec<u8> {
+            self.0.decode(array)
+        }
+    }
+
+    impl<P, const B: usize> NucleotideEncoding<P, B> for RequiredOnly
+    where
+        P: crate::utils::Data + num::PrimInt,
+    {
+        fn rev_comp<const K: usize>(&self, array: [P; B]) -> [P; B] {
+            self.0.rev_comp::<K>(array)
+        }
+    }
+
+    fn check_bases<E>(encoder: &E)
+    where
+        E: NucleotideEncoding<u64, 1>,
+    {
+        for (nuc, comp) in [(b'A', b'T'), (b'C', b'G'), (b'G', b'C'), (b'T', b'A')] {
+            let bits = encoder.encode_base(nuc);
+            assert_eq!(bits, encoder.encode(&[nuc])[0]);
+            assert_eq!(encoder.decode_base(bits), nuc);
+            assert_eq!(encoder.decode_base(encoder.complement_base(bits)), comp);
+        }
+    }
+
+    #[test]
+    fn base_accessors() {
+        check_bases(&Naive::ACGT);
+        check_bases(&Naive::TGCA);
+        check_bases(&Naive::CTAG);
+        check_bases(&Xor10);
+        check_bases(&RequiredOnly(Naive::TGCA));
+
+        let encoder = AminoAcid;
+        let bits = Encoding::<u16, 1>::encode_base(&encoder, b'W');
+        assert_eq!(Encoding::<u16, 1>::decode_base(&encoder, bits), b'W');
+    }
 }
@@ -135,23 +135,44 @@ where
         seq
     }
 
+    fn encode_base(&self, nuc: u8) -> P {
+        self.nuc2bits(nuc)
+    }
+
+    fn decode_base(&self, bits: P) -> u8 {
+        self.bits2nuc(bits)
+    }
+}
+
+impl<P, const B: usize> super::NucleotideEncoding<P, B> for Naive
+where
+    P: crate::utils::Data,
+{
     fn rev_comp<const K: usize>(&self, mut array: [P; B]) -> [P; B] {
-        let mut i = 0;
-        let mut j = K * 2 - 2;
+        for idx in 0..K / 2 {
+            let i = idx * 2;
+            let j = (K - 1 - idx) * 2;
 
-        while i <= j {
             let comp_i = self.complement(array.get_bits(i..i + 2));
             let comp_j = self.complement(array.get_bits(j..j + 2));
 
             array.set_bits(i..i + 2, comp_j);
             array.set_bits(j..j + 2, comp_i);
+        }
 
-            i += 2;
-            j -= 2;
+        // the middle base of odd k-mers is only complemented
+        if K % 2 == 1 {
+            let m = K / 2 * 2;
+            let comp_m = self.complement(array.get_bits(m..m + 2));
+            array.set_bits(m..m + 2, comp_m);
         }
 
         array
     }
+
+    fn complement_base(&self, bits: P) -> P {
+        self.complement(bits)
+    }
 }
 
 #[cfg(test)]
@@ -162,7 +183,7 @@ mod tests {
 
     use crate::kmer;
 
-    use crate::encoding::Encoding as _;
+    use crate::encoding::{Encoding as _, NucleotideEncoding as _};
 
     #[test]
     fn one_base_all_encoding() {
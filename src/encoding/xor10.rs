@@ -47,7 +47,7 @@ impl<P> From<usize> {
 
 impl<P, const B: usize> super::Encoding<P, B> for Xor10
 where
-    P: crate::utils::Data + num::PrimInt + std::convert::From<u64>,
+    P: crate::utils::Data + num::PrimInt,
 {
     fn encode(&self, seq: &[u8]) -> [P; B] {
         let mut array: [P; B] = unsafe { [std::mem::zeroed(); B] };
@@ -71,8 +71,21 @@ where
         seq
     }
 
+    fn encode_base(&self, nuc: u8) -> P {
+        self.nuc2bits(nuc)
+    }
+
+    fn decode_base(&self, bits: P) -> u8 {
+        self.bits2nuc(bits)
+    }
+}
+
+impl<P, const B: usize> super::NucleotideEncoding<P, B> for Xor10
+where
+    P: crate::utils::Data + num::PrimInt,
+{
     fn rev_comp<const K: usize>(&self, mut array: [P; B]) -> [P; B] {
-        if B == 1 {
+        if B == 1 && size_of::<P>() <= size_of::<u64>() {
             let mut kmer = array[0].to_u64().unwrap();
             // Thank to needtail people ! :)
             kmer = (kmer >> 2 & 0x3333_3333_3333_3333) | (kmer & 0x3333_3333_3333_3333) << 2;
@@ -81,30 +94,41 @@ where
             kmer = (kmer >> 16 & 0x0000_FFFF_0000_FFFF) | (kmer & 0x0000_FFFF_0000_FFFF) << 16;
             kmer = (kmer >> 32 & 0x0000_0000_FFFF_FFFF) | (kmer & 0x0000_0000_FFFF_FFFF) << 32;
 
-            array[0] = ((8 * size_of::<P>()) as u64 - kmer * 2u64).into();
+            // shift away the padding, then complement the K bases (x ^ 0b10)
+            kmer >>= 64 - K * 2;
+            kmer ^= 0xAAAA_AAAA_AAAA_AAAA >> (64 - K * 2);
+
+            array[0] = num::cast(kmer).unwrap();
             array
         } else {
             // This could probably be improve natir/cocktail have a nicer implementation for u64
-            let mut i = 0;
-            let mut j = K * 2 - 2;
+            for idx in 0..K / 2 {
+                let i = idx * 2;
+                let j = (K - 1 - idx) * 2;
 
-            while i <= j {
                 let comp_i = self.complement(array.get_bits(i..i + 2));
                 let comp_j = self.complement(array.get_bits(j..j + 2));
 
                 array.set_bits(i..i + 2, comp_j);
                 array.set_bits(j..j + 2, comp_i);
+            }
 
-                i += 2;
-                j -= 2;
+            // the middle base of odd k-mers is only complemented
+            if K % 2 == 1 {
+                let m = K / 2 * 2;
+                let comp_m = self.complement(array.get_bits(m..m + 2));
+                array.set_bits(m..m + 2, comp_m);
             }
 
             array
         }
     }
+
+    fn complement_base(&self, bits: P) -> P {
+        self.complement(bits)
+    }
 }
 
-/*
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,7 +136,7 @@ mod tests {
     /* Project use */
     use crate::kmer;
 
-    use crate::encoding::Encoding as _;
+    use crate::encoding::{Encoding as _, NucleotideEncoding as _};
 
     #[test]
     fn one_base_encoding() {
@@ -301,4 +325,3 @@ mod tests {
         assert_eq!(Xor10.decode(Xor10.rev_comp::<65>(array)), b"CCCCCTGATTAGAATCCTTATGATTAGAATCCTTATGATTAGAATCCTTATGATTAGAATCCTTAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
     }
 }
-*/
@@ -0,0 +1,316 @@
+//! Minimal FASTA/FASTQ reading, to build `SeqVector`s and k-mer streams from files
+//! FASTA records may be wrapped over several lines, FASTQ records must hold their sequence on one line.
+//! Only the sequence and the name (the header up to the first whitespace) of records are kept.
+
+/* std use */
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/* project use */
+use crate::naive_impl::prelude::{encode_binary_u8, is_valid_nuc};
+use crate::naive_impl::seq_vector::SeqVector;
+use crate::naive_impl::{CanonicalKmer, CanonicalKmerIterator};
+
+/// A FASTA or FASTQ record
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub name: String,
+    pub seq: Vec<u8>,
+}
+
+/// Iterator over the records of a FASTA or FASTQ stream, the format is detected on each header
+pub struct Records<R> {
+    reader: R,
+    /// header of the next record, already read while looking for the end of a FASTA record
+    next_header: Option<Vec<u8>>,
+    line: Vec<u8>,
+    done: bool,
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Remove the trailing end of line, `\n` or `\r\n`
+fn trim_eol(line: &mut Vec<u8>) {
+    while line.last().map_or(false, |&c| c == b'\n' || c == b'\r') {
+        line.pop();
+    }
+}
+
+impl<R: BufRead> Records<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            next_header: None,
+            line: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Read the next line in `self.line`, false at the end of the stream
+    fn read_line(&mut self) -> io::Result<bool> {
+        self.line.clear();
+        let n = self.reader.read_until(b'\n', &mut self.line)?;
+        trim_eol(&mut self.line);
+        Ok(n > 0)
+    }
+
+    fn next_record(&mut self) -> io::Result<Option<Record>> {
+        let header = match self.next_header.take() {
+            Some(header) => header,
+            None => loop {
+                if !self.read_line()? {
+                    return Ok(None);
+                }
+                if !self.line.is_empty() {
+                    break std::mem::take(&mut self.line);
+                }
+            },
+        };
+
+        let name = header[1..]
+            .split(|c| c.is_ascii_whitespace())
+            .next()
+            .unwrap_or(&[]);
+        let name = String::from_utf8_lossy(name).into_owned();
+
+        match header[0] {
+            b'>' => self.fasta_seq(name),
+            b'@' => self.fastq_seq(name),
+            c => Err(invalid_data(format!(
+                "expected a record header starting with '>' or '@', found {:?}",
+                c as char
+            ))),
+        }
+        .map(Some)
+    }
+
+    fn fasta_seq(&mut self, name: String) -> io::Result<Record> {
+        let mut seq = Vec::new();
+        while self.read_line()? {
+            if matches!(self.line.first(), Some(b'>') | Some(b'@')) {
+                self.next_header = Some(std::mem::take(&mut self.line));
+                break;
+            }
+            seq.extend_from_slice(&self.line);
+        }
+        Ok(Record { name, seq })
+    }
+
+    fn fastq_seq(&mut self, name: String) -> io::Result<Record> {
+        let truncated = || invalid_data(format!("truncated FASTQ record {}", name));
+
+        if !self.read_line()? {
+            return Err(truncated());
+        }
+        let seq = std::mem::take(&mut self.line);
+
+        if !self.read_line()? || self.line.first() != Some(&b'+') {
+            return Err(truncated());
+        }
+        if !self.read_line()? || self.line.len() != seq.len() {
+            return Err(invalid_data(format!(
+                "quality and sequence lengths differ in FASTQ record {}",
+                name
+            )));
+        }
+        Ok(Record { name, seq })
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    /// Stop after the first error
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = self.next_record().transpose();
+        self.done = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+impl<R: BufRead> std::iter::FusedIterator for Records<R> {}
+
+/// Records of the FASTA or FASTQ file at `path`
+pub fn read_records<P: AsRef<Path>>(path: P) -> io::Result<Records<BufReader<File>>> {
+    Ok(Records::new(BufReader::new(File::open(path)?)))
+}
+
+/// Maximal runs of `ACGTacgt` in `seq`, with their offset in `seq`
+pub fn segments(seq: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let is_nuc = |c: &u8| is_valid_nuc(encode_binary_u8(*c));
+        let start = pos + seq[pos..].iter().position(is_nuc)?;
+        let len = seq[start..]
+            .iter()
+            .position(|c| !is_nuc(c))
+            .unwrap_or(seq.len() - start);
+        pos = start + len;
+        Some((start, &seq[start..pos]))
+    })
+}
+
+/// For each record of the file at `path`, and each of its `segments`, the name of the record,
+/// the offset of the segment in the record and the segment itself
+pub fn records_to_seqvectors<P: AsRef<Path>>(
+    path: P,
+) -> io::Result<impl Iterator<Item = io::Result<(String, usize, SeqVector)>>> {
+    Ok(read_records(path)?.flat_map(|record| match record {
+        Ok(record) => segments(&record.seq)
+            .map(|(offset, seg)| Ok((record.name.clone(), offset, SeqVector::from(seg))))
+            .collect(),
+        Err(e) => vec![Err(e)],
+    }))
+}
+
+/// Canonical k-mers of the file at `path`, with the index of their record and their position in it,
+/// k-mers spanning a non `ACGTacgt` character are skipped
+pub fn canonical_kmers_of_file<P: AsRef<Path>>(
+    path: P,
+    k: u8,
+) -> io::Result<impl Iterator<Item = io::Result<(usize, usize, CanonicalKmer)>>> {
+    Ok(read_records(path)?
+        .enumerate()
+        .flat_map(move |(idx, record)| {
+            let (kmers, err) = match record {
+                Ok(record) => (Some(CanonicalKmerIterator::from_vec(record.seq, k)), None),
+                Err(e) => (None, Some(Err(e))),
+            };
+            kmers
+                .into_iter()
+                .flatten()
+                .map(move |ckp| Ok((idx, ckp.pos as usize, ckp.km)))
+                .chain(err)
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FASTA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/segments.fa");
+    const FASTQ: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/reads.fq");
+
+    #[test]
+    fn parse_fasta() {
+        let records: Vec<Record> = read_records(FASTA).unwrap().map(|r| r.unwrap()).collect();
+        let names: Vec<&str> = records.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["chr1", "chr2", "empty", "chrN"]);
+
+        // lines are joined, case is kept
+        assert_eq!(
+            records[0].seq,
+            b"ACGTACGTTTGACCATGACANNNNNNNNGGATCCAGTTacgtacgggtcaAAAAT".to_vec()
+        );
+        assert_eq!(records[1].seq, b"TTGCAGGTCAGGNTTCA".to_vec());
+        assert!(records[2].seq.is_empty());
+        assert_eq!(records[3].seq, b"NNNN".to_vec());
+    }
+
+    #[test]
+    fn parse_fastq() {
+        let records: Vec<Record> = read_records(FASTQ).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    name: "read1".to_string(),
+                    seq: b"ACGTTGCANGGT".to_vec()
+                },
+                Record {
+                    name: "read2".to_string(),
+                    seq: b"ttgacca".to_vec()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut records = Records::new(&b"@r1\nACGT\n+\nIII\n>r2\nACGT\n"[..]);
+        assert_eq!(
+            records.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(records.next().is_none());
+
+        let mut records = Records::new(&b"ACGT\n"[..]);
+        assert!(records.next().unwrap().is_err());
+
+        let mut records = Records::new(&b"\r\n>r1 description\r\nAC\r\nGT\r\n@r2\n"[..]);
+        assert_eq!(
+            records.next().unwrap().unwrap(),
+            Record {
+                name: "r1".to_string(),
+                seq: b"ACGT".to_vec()
+            }
+        );
+        assert!(records.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn split_segments() {
+        let segs: Vec<(usize, &[u8])> = segments(b"NNACGTnRacgTTN").collect();
+        assert_eq!(segs, vec![(2, &b"ACGT"[..]), (8, &b"acgTT"[..])]);
+        assert_eq!(segments(b"").count(), 0);
+        assert_eq!(segments(b"NNN").count(), 0);
+        assert_eq!(segments(b"ACG").collect::<Vec<_>>(), vec![(0, &b"ACG"[..])]);
+    }
+
+    #[test]
+    fn seqvector_segments() {
+        let segs: Vec<(String, usize, String)> = records_to_seqvectors(FASTA)
+            .unwrap()
+            .map(|r| {
+                let (name, offset, sv) = r.unwrap();
+                (name, offset, sv.to_string())
+            })
+            .collect();
+        assert_eq!(
+            segs,
+            vec![
+                ("chr1".to_string(), 0, "ACGTACGTTTGACCATGACA".to_string()),
+                (
+                    "chr1".to_string(),
+                    28,
+                    "GGATCCAGTTACGTACGGGTCAAAAAT".to_string()
+                ),
+                ("chr2".to_string(), 0, "TTGCAGGTCAGG".to_string()),
+                ("chr2".to_string(), 13, "TTCA".to_string()),
+            ]
+        );
+
+        assert!(records_to_seqvectors("/does/not/exist.fa").is_err());
+    }
+
+    #[test]
+    fn kmers_of_file() {
+        let k = 11;
+        let kmers: Vec<(usize, usize, CanonicalKmer)> = canonical_kmers_of_file(FASTA, k)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+
+        let records: Vec<Record> = read_records(FASTA).unwrap().map(|r| r.unwrap()).collect();
+        let mut expected = Vec::new();
+        for (idx, record) in records.iter().enumerate() {
+            for (offset, seg) in segments(&record.seq) {
+                for (i, w) in seg.windows(k as usize).enumerate() {
+                    expected.push((idx, offset + i, CanonicalKmer::from(w)));
+                }
+            }
+        }
+        assert_eq!(kmers, expected);
+        assert_eq!(kmers.first().unwrap().0, 0);
+        assert_eq!(kmers.last().unwrap().0, 1);
+
+        let n = canonical_kmers_of_file(FASTQ, 5).unwrap().count();
+        assert_eq!(n, 4 + 3);
+    }
+}
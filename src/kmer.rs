@@ -2,26 +2,43 @@
 
 /* crate use */
 use bit_field::BitArray;
+use std::cmp::Ordering;
 use std::u32;
 
 /* project use */
 use crate::encoding;
+use crate::naive_impl;
 
-/// Struct to store and use kmer
-#[derive(Debug)]
-pub struct Kmer<P, const K: usize, const B: usize> {
+/// Struct to store and use kmer, of K symbols on BITS bits each, 2 for nucleotides
+#[derive(Debug, Clone, Copy)]
+pub struct Kmer<P, const K: usize, const B: usize, const BITS: usize = 2> {
     array: [P; B],
 }
 
-impl<P, const K: usize, const B: usize> Kmer<P, K, B>
+impl<P, const K: usize, const B: usize, const BITS: usize> Kmer<P, K, B, BITS>
 where
     P: Copy + bit_field::BitField,
 {
+    /// panic if encoder doesn't use BITS bits per symbol
+    fn check_encoder<E>(_encoder: &E)
+    where
+        E: encoding::Encoding<P, B>,
+    {
+        assert_eq!(
+            E::BITS,
+            BITS,
+            "encoder uses {} bits per symbol, the k-mer {}",
+            E::BITS,
+            BITS
+        );
+    }
+
     /// construct a new empty k-mer fill with zero by default
     pub fn new<E>(sequence: &[u8], encoder: &E) -> Self
     where
         E: encoding::Encoding<P, B>,
     {
+        Self::check_encoder(encoder);
         Self {
             array: encoder.encode(sequence),
         }
@@ -32,6 +49,17 @@ where
         Self { array: data }
     }
 
+    /// decode the K symbols of the k-mer with encoder, padding symbols of the last word are dropped
+    pub fn decode<E>(&self, encoder: &E) -> Vec<u8>
+    where
+        E: encoding::Encoding<P, B>,
+    {
+        Self::check_encoder(encoder);
+        let mut seq = encoder.decode(self.array);
+        seq.truncate(K);
+        seq
+    }
+
     /// returns the value of k for this k-mer
     pub fn k(&self) -> usize {
         K
@@ -39,20 +67,328 @@ where
 
     /// returns the number of bytes used for the storage of this k-mer
     pub fn num_bytes(&self) -> usize {
-        std::mem::size_of::<P>() * word_for_k::<P, K>()
+        std::mem::size_of::<P>() * B
     }
 
-    /// get the niest nucleotide
+    /// get the niest symbol
     pub fn get(&self, index: usize) -> P {
-        self.array.get_bits(index * 2..=index * 2 + 1)
+        self.array.get_bits(index * BITS..(index + 1) * BITS)
     }
 
+    /// get the len first symbols, len * BITS must fit in a P
     pub fn get_prefix(&self, len: usize) -> P {
-        self.array.get_bits(0..=(len * 2))
+        self.array.get_bits(0..len * BITS)
+    }
+
+    /// get the len last symbols, len * BITS must fit in a P
+    pub fn get_suffix(&self, len: usize) -> P {
+        self.array.get_bits((K - len) * BITS..K * BITS)
+    }
+
+    /// get the L symbols from start as a k-mer stored on the same number of words, the range may cross words
+    pub fn get_range<const L: usize>(&self, start: usize) -> Kmer<P, L, B, BITS> {
+        assert!(
+            start + L <= K,
+            "range {}..{} out of a {}-mer",
+            start,
+            start + L,
+            K
+        );
+
+        let bits = std::mem::size_of::<P>() * 8;
+        let mut sub = Kmer::<P, L, B, BITS>::default();
+
+        // copy a word worth of bits at a time
+        let (from, len) = (start * BITS, L * BITS);
+        for offset in (0..len).step_by(bits) {
+            let end = (offset + bits).min(len);
+            let word = self.array.get_bits(from + offset..from + end);
+            sub.array.set_bits(offset..end, word);
+        }
+
+        sub
     }
 }
 
-impl<P, const K: usize, const B: usize> std::default::Default for Kmer<P, K, B>
+impl<P, const K: usize, const B: usize, const BITS: usize> Kmer<P, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+{
+    /// BITS bits code of a symbol with encoder
+    fn nuc2bits<E>(nuc: u8, encoder: &E) -> P
+    where
+        E: encoding::Encoding<P, B>,
+    {
+        encoder.encode_base(nuc)
+    }
+
+    /// add a nucleotide at the end of the k-mer and remove the first one, like naive_impl::Kmer::append_base
+    pub fn append_base<E>(&mut self, nuc: u8, encoder: &E)
+    where
+        E: encoding::Encoding<P, B>,
+    {
+        Self::check_encoder(encoder);
+        let bits = std::mem::size_of::<P>() * 8;
+        let width = BITS;
+
+        // shift the whole array one symbol toward the first nucleotide
+        for i in 0..B {
+            let carry = if i + 1 < B {
+                self.array[i + 1] << (bits - width)
+            } else {
+                P::zero()
+            };
+            self.array[i] = (self.array[i] >> width) | carry;
+        }
+
+        self.array
+            .set_bits((K - 1) * width..K * width, Self::nuc2bits(nuc, encoder));
+    }
+
+    /// add a nucleotide at the begin of the k-mer and remove the last one, like naive_impl::Kmer::prepend_base
+    pub fn prepend_base<E>(&mut self, nuc: u8, encoder: &E)
+    where
+        E: encoding::Encoding<P, B>,
+    {
+        Self::check_encoder(encoder);
+        let bits = std::mem::size_of::<P>() * 8;
+        let width = BITS;
+
+        // shift the whole array one symbol toward the last nucleotide
+        for i in (0..B).rev() {
+            let carry = if i > 0 {
+                self.array[i - 1] >> (bits - width)
+            } else {
+                P::zero()
+            };
+            self.array[i] = (self.array[i] << width) | carry;
+        }
+
+        // mask the nucleotide pushed out of the k-mer
+        if K * width < B * bits {
+            let end = (K * width + width).min(B * bits);
+            self.array.set_bits(K * width..end, P::zero());
+        }
+        self.array.set_bits(0..width, Self::nuc2bits(nuc, encoder));
+    }
+
+    /// iterate over all k-mers of seq, each k-mer is compute from the previous one by append_base
+    pub fn iter_sequence<'a, E>(seq: &'a [u8], encoder: &'a E) -> KmerIterator<'a, P, E, K, B, BITS>
+    where
+        E: encoding::Encoding<P, B>,
+    {
+        KmerIterator::new(seq, encoder)
+    }
+}
+
+/// Iterator over the k-mers of a sequence, each k-mer is compute from the previous one by append_base
+pub struct KmerIterator<'a, P, E, const K: usize, const B: usize, const BITS: usize = 2> {
+    kmer: Option<Kmer<P, K, B, BITS>>,
+    rest: std::slice::Iter<'a, u8>,
+    encoder: &'a E,
+}
+
+impl<'a, P, E, const K: usize, const B: usize, const BITS: usize> KmerIterator<'a, P, E, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+    E: encoding::Encoding<P, B>,
+{
+    /// iterate over the k-mers of seq, there is none if seq is shorter than K
+    pub fn new(seq: &'a [u8], encoder: &'a E) -> Self {
+        let kmer = (seq.len() >= K).then(|| Kmer::new(&seq[..K], encoder));
+        let rest = seq.get(K..).unwrap_or_default().iter();
+
+        Self {
+            kmer,
+            rest,
+            encoder,
+        }
+    }
+}
+
+impl<'a, P, E, const K: usize, const B: usize, const BITS: usize> Iterator
+    for KmerIterator<'a, P, E, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+    E: encoding::Encoding<P, B>,
+{
+    type Item = Kmer<P, K, B, BITS>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let curr = self.kmer?;
+        self.kmer = self.rest.next().map(|nuc| {
+            let mut next = curr;
+            next.append_base(*nuc, self.encoder);
+            next
+        });
+        Some(curr)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.kmer.map_or(0, |_| self.rest.len() + 1);
+        (len, Some(len))
+    }
+}
+
+impl<'a, P, E, const K: usize, const B: usize, const BITS: usize> ExactSizeIterator
+    for KmerIterator<'a, P, E, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+    E: encoding::Encoding<P, B>,
+{
+}
+
+impl<P, const K: usize, const B: usize, const BITS: usize> Kmer<P, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+{
+    /// the storage array with all bits after the K symbols set to zero
+    fn masked(&self) -> [P; B] {
+        let bits = std::mem::size_of::<P>() * 8;
+        let mut array = self.array;
+
+        for (i, word) in array.iter_mut().enumerate() {
+            let used = (K * BITS).saturating_sub(i * bits);
+            if used == 0 {
+                *word = P::zero();
+            } else if used < bits {
+                *word = *word & !(P::max_value() << used);
+            }
+        }
+
+        array
+    }
+}
+
+impl<P, const K: usize, const B: usize> Kmer<P, K, B>
+where
+    P: crate::utils::Data + num::PrimInt,
+{
+    /// compare the k-mer to its reverse complement with encoder, Less or Equal means the k-mer is canonical
+    pub fn canonical_cmp<E>(&self, encoder: &E) -> Ordering
+    where
+        E: encoding::NucleotideEncoding<P, B>,
+    {
+        self.cmp(&self.to_reverse_complement(encoder))
+    }
+
+    /// reverse complement of the K nucleotides with encoder
+    pub fn to_reverse_complement<E>(&self, encoder: &E) -> Self
+    where
+        E: encoding::NucleotideEncoding<P, B>,
+    {
+        Self::with_data(encoder.rev_comp::<K>(self.masked()))
+    }
+
+    /// true if the k-mer is lexicographically smaller or equal to its reverse complement, under encoder nucleotide ordering
+    pub fn is_canonical<E>(&self, encoder: &E) -> bool
+    where
+        E: encoding::NucleotideEncoding<P, B>,
+    {
+        self.canonical_cmp(encoder).is_le()
+    }
+
+    /// the smallest of the k-mer and its reverse complement
+    pub fn to_canonical<E>(&self, encoder: &E) -> Self
+    where
+        E: encoding::NucleotideEncoding<P, B>,
+    {
+        let rc = self.to_reverse_complement(encoder);
+        if *self <= rc {
+            *self
+        } else {
+            rc
+        }
+    }
+}
+
+/// Two k-mers are equal if their K symbols are equal, whatever the padding bits
+impl<P, const K: usize, const B: usize, const BITS: usize> PartialEq for Kmer<P, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.masked() == other.masked()
+    }
+}
+
+impl<P, const K: usize, const B: usize, const BITS: usize> Eq for Kmer<P, K, B, BITS> where
+    P: crate::utils::Data + num::PrimInt
+{
+}
+
+/// K-mers are ordered lexicographically by the BITS bits code of their symbols
+impl<P, const K: usize, const B: usize, const BITS: usize> Ord for Kmer<P, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        (0..K)
+            .map(|i| self.get(i).cmp(&other.get(i)))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<P, const K: usize, const B: usize, const BITS: usize> PartialOrd for Kmer<P, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P, const K: usize, const B: usize, const BITS: usize> std::hash::Hash for Kmer<P, K, B, BITS>
+where
+    P: crate::utils::Data + num::PrimInt + std::hash::Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.masked().hash(state);
+    }
+}
+
+impl<const K: usize, const B: usize> Kmer<u64, K, B> {
+    /// Evaluated, so checked at compile time, by conversions that need K to fit in a naive_impl::Kmer
+    const FITS_NAIVE: () = assert!(K <= 32, "naive_impl::Kmer can not store more than 32 bases");
+
+    /// convert to a naive_impl::Kmer, re-mapping the 2 bits codes of encoder to the A, C, G, T ordering, return None if K is larger than 32
+    pub fn to_naive<E>(&self, encoder: &E) -> Option<naive_impl::Kmer>
+    where
+        E: encoding::NucleotideEncoding<u64, B>,
+    {
+        if K > 32 {
+            return None;
+        }
+
+        // translation table from the encoder codes to the naive_impl codes
+        let mut table = [0; 4];
+        for (naive_code, nuc) in b"ACGT".iter().enumerate() {
+            let code = encoder.encode(&[*nuc])[0] & 0b11;
+            table[code as usize] = naive_code as u64;
+        }
+
+        let data = (0..K).fold(0, |data, i| data | table[self.get(i) as usize] << (2 * i));
+
+        Some(naive_impl::Kmer::from_u64(data, K as u8))
+    }
+}
+
+/// A k-mer encoded with encoding::Naive::ACGT has the same representation as a naive_impl::Kmer
+///
+/// **Warning**: the word is copied as it is, whatever the encoder the k-mer was built with. For any other encoder, like encoding::Xor10 or another encoding::Naive ordering, this silently gives a different k-mer, use Kmer::to_naive with the encoder instead.
+///
+/// Fails to compile if K is larger than 32.
+impl<const K: usize> From<&Kmer<u64, K, 1>> for naive_impl::Kmer {
+    fn from(kmer: &Kmer<u64, K, 1>) -> Self {
+        let () = Kmer::<u64, K, 1>::FITS_NAIVE;
+
+        naive_impl::Kmer::from_u64(kmer.array[0], K as u8)
+    }
+}
+
+impl<P, const K: usize, const B: usize, const BITS: usize> std::default::Default
+    for Kmer<P, K, B, BITS>
 where
     P: Copy + bit_field::BitField,
 {
@@ -65,9 +401,16 @@ where
 
 /// compute the number of words required to store a kmer of length k
 pub const fn word_for_k<P, const K: usize>() -> usize {
-    (std::mem::size_of::<P>() * 8 / 2 + K - 1) / (std::mem::size_of::<P>() * 8 / 2)
+    words_for_k_bits::<P>(K, 2)
+}
+
+/// compute the number of words required to store k symbols of bits bits
+pub const fn words_for_k_bits<P>(k: usize, bits: usize) -> usize {
+    (k * bits + std::mem::size_of::<P>() * 8 - 1) / (std::mem::size_of::<P>() * 8)
 }
 
+/// decode the len_in first nucleotides of mer, with the A, C, G, T codes 0 to 3 of encoding::Naive::ACGT and naive_impl
+#[deprecated(note = "use `naive_impl::word_to_string`, or `Kmer::decode` with an `Encoding`")]
 pub fn bitmer_to_bytes(mer: u64, len_in: usize) -> Vec<u8> {
     let mut new_kmer = mer;
     let len = len_in as u32;
@@ -94,6 +437,10 @@ pub fn bitmer_to_bytes(mer: u64, len_in: usize) -> Vec<u8> {
 mod tests {
     use super::*;
 
+    use crate::encoding::Encoding as _;
+    use crate::naive_impl::word_to_string;
+    use crate::test_utils::{random_bases, rc};
+
     #[test]
     fn choose_number_of_word() {
         assert_eq!(word_for_k::<u8, 1>(), 1);
@@ -191,14 +538,494 @@ mod tests {
         let pref: u64 = kmer.get_prefix(4);
         assert_eq!(pref, 0b01001110);
 
-        let s = bitmer_to_bytes(pref, 4);
-        assert_eq!(b"GTAC".to_vec(), s);
+        assert_eq!(naive_impl::word_to_string(pref, 4), "GTAC");
+    }
+
+    fn check_prefix_suffix<P, const K: usize, const B: usize>(seq: &[u8])
+    where
+        P: crate::utils::Data + num::PrimInt,
+    {
+        let kmer = Kmer::<P, K, B>::new(seq, &encoding::Naive::ACGT);
+        // word_to_string decodes at most 32 bases
+        let max_len = (std::mem::size_of::<P>() * 4).min(K).min(32);
+
+        for len in 1..=max_len {
+            let prefix = kmer.get_prefix(len).to_u64().unwrap();
+            let prefix = naive_impl::word_to_string(prefix, len as u8);
+            assert_eq!(prefix.as_bytes(), &seq[..len]);
+
+            let suffix = kmer.get_suffix(len).to_u64().unwrap();
+            let suffix = naive_impl::word_to_string(suffix, len as u8);
+            assert_eq!(suffix.as_bytes(), &seq[K - len..]);
+        }
+    }
+
+    fn check_ranges<P, const K: usize, const B: usize, const L: usize>(seq: &[u8])
+    where
+        P: crate::utils::Data + num::PrimInt,
+    {
+        let encoder = encoding::Naive::ACGT;
+        let kmer = Kmer::<P, K, B>::new(seq, &encoder);
+
+        for start in 0..=K - L {
+            let sub: Kmer<P, L, B> = kmer.get_range(start);
+            assert_eq!(sub, Kmer::<P, L, B>::new(&seq[start..start + L], &encoder));
+            assert_eq!(encoder.decode(sub.array)[..L], seq[start..start + L]);
+        }
+    }
+
+    #[test]
+    fn prefix_suffix_range() {
+        let mut seed = 0x2545F4914F6CDD1D;
+        let seq = random_bases(45, &mut seed, b"ACGT");
+
+        // a 4 bases prefix fills the first byte exactly
+        let kmer = Kmer::<u8, 15, { word_for_k::<u8, 15>() }>::new(
+            b"GTACCATTGACCAGT",
+            &encoding::Naive::ACGT,
+        );
+        assert_eq!(kmer.get_prefix(4), 0b01001110);
+        assert_eq!(kmer.get_suffix(4), 0b11100001);
+        assert_eq!(word_to_string(kmer.get_suffix(3) as u64, 3), "AGT");
+
+        check_prefix_suffix::<u8, 15, { word_for_k::<u8, 15>() }>(&seq[..15]);
+        check_prefix_suffix::<u16, 15, { word_for_k::<u16, 15>() }>(&seq[..15]);
+        check_prefix_suffix::<u32, 15, { word_for_k::<u32, 15>() }>(&seq[..15]);
+        check_prefix_suffix::<u64, 45, { word_for_k::<u64, 45>() }>(&seq);
+        check_prefix_suffix::<u128, 45, { word_for_k::<u128, 45>() }>(&seq);
+
+        check_ranges::<u8, 15, { word_for_k::<u8, 15>() }, 1>(&seq[..15]);
+        check_ranges::<u8, 15, { word_for_k::<u8, 15>() }, 4>(&seq[..15]);
+        check_ranges::<u8, 15, { word_for_k::<u8, 15>() }, 9>(&seq[..15]);
+        check_ranges::<u8, 15, { word_for_k::<u8, 15>() }, 15>(&seq[..15]);
+        check_ranges::<u64, 45, { word_for_k::<u64, 45>() }, 31>(&seq);
+        check_ranges::<u64, 45, { word_for_k::<u64, 45>() }, 33>(&seq);
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_out_of_kmer() {
+        let kmer = Kmer::<u8, 15, { word_for_k::<u8, 15>() }>::default();
+        let _: Kmer<u8, 4, 4> = kmer.get_range(12);
     }
 
     #[test]
+    #[allow(deprecated)]
     fn kmer_to_bytes() {
         let pref = 0b01001110;
         let s = bitmer_to_bytes(pref, 4);
         assert_eq!(b"GTAC".to_vec(), s);
     }
+
+    const NAIVES: [encoding::Naive; 24] = [
+        encoding::Naive::ACTG,
+        encoding::Naive::ACGT,
+        encoding::Naive::ATCG,
+        encoding::Naive::ATGC,
+        encoding::Naive::AGCT,
+        encoding::Naive::AGTC,
+        encoding::Naive::CATG,
+        encoding::Naive::CAGT,
+        encoding::Naive::CTAG,
+        encoding::Naive::CTGA,
+        encoding::Naive::CGAT,
+        encoding::Naive::CGTA,
+        encoding::Naive::TACG,
+        encoding::Naive::TAGC,
+        encoding::Naive::TCAG,
+        encoding::Naive::TCGA,
+        encoding::Naive::TGAC,
+        encoding::Naive::TGCA,
+        encoding::Naive::GACT,
+        encoding::Naive::GATC,
+        encoding::Naive::GCAT,
+        encoding::Naive::GCTA,
+        encoding::Naive::GTAC,
+        encoding::Naive::GTCA,
+    ];
+
+    #[test]
+    fn to_naive() {
+        let mut seed = 0x9E3779B97F4A7C15;
+
+        for _ in 0..16 {
+            let seq = random_bases(31, &mut seed, b"ACGT");
+            let expected = String::from_utf8(seq.to_ascii_lowercase()).unwrap();
+
+            for encoder in NAIVES {
+                let kmer = Kmer::<u64, 31, { word_for_k::<u64, 31>() }>::new(&seq, &encoder);
+                let naive = kmer.to_naive(&encoder).unwrap();
+                assert_eq!(naive.to_string(), expected);
+            }
+
+            let kmer = Kmer::<u64, 31, 1>::new(&seq, &encoding::Xor10);
+            let naive = kmer.to_naive(&encoding::Xor10).unwrap();
+            assert_eq!(naive.to_string(), expected);
+
+            // shorter k-mers on a multi word array
+            let kmer = Kmer::<u64, 7, 2>::new(&seq[..7], &encoding::Naive::GTCA);
+            let naive = kmer.to_naive(&encoding::Naive::GTCA).unwrap();
+            assert_eq!(naive.to_string(), expected[..7]);
+        }
+
+        let kmer = Kmer::<u64, 33, 2>::new(&[b'A'; 33], &encoding::Naive::ACGT);
+        assert_eq!(kmer.to_naive(&encoding::Naive::ACGT), None);
+    }
+
+    #[test]
+    fn from_acgt_kmer() {
+        let mut seed = 0x2545F4914F6CDD1D;
+
+        for _ in 0..16 {
+            let seq = random_bases(21, &mut seed, b"ACGT");
+            let kmer = Kmer::<u64, 21, 1>::new(&seq, &encoding::Naive::ACGT);
+            let naive = naive_impl::Kmer::from(&kmer);
+
+            assert_eq!(naive, naive_impl::Kmer::from(seq.as_slice()));
+            assert_eq!(Some(naive), kmer.to_naive(&encoding::Naive::ACGT));
+        }
+    }
+
+    fn check_rolling<E, P, const K: usize, const B: usize>(seq: &[u8], encoder: &E)
+    where
+        E: encoding::Encoding<P, B>,
+        P: crate::utils::Data + num::PrimInt,
+    {
+        let rolled: Vec<Kmer<P, K, B>> = Kmer::iter_sequence(seq, encoder).collect();
+        let encoded: Vec<Kmer<P, K, B>> = seq
+            .windows(K)
+            .map(|window| Kmer::new(window, encoder))
+            .collect();
+        assert_eq!(rolled, encoded);
+
+        // and backward, from the last k-mer
+        let mut kmer = Kmer::<P, K, B>::new(&seq[seq.len() - K..], encoder);
+        for i in (0..seq.len() - K).rev() {
+            kmer.prepend_base(seq[i], encoder);
+            assert_eq!(kmer, encoded[i]);
+        }
+    }
+
+    macro_rules! check_rolling_words {
+        ($encoder:expr, $seq:expr, $(($ty:ty, $k:expr)), *) => (
+            $(
+                check_rolling::<_, $ty, $k, { word_for_k::<$ty, $k>() }>($seq, &$encoder);
+            )*
+        )
+    }
+
+    #[test]
+    fn rolling_kmers() {
+        let mut seed = 0x9E3779B97F4A7C15;
+        let seq = random_bases(200, &mut seed, b"ACGT");
+
+        // k-mers on one, two and three words
+        check_rolling_words!(
+            encoding::Naive::ACGT,
+            &seq,
+            (u8, 3),
+            (u8, 7),
+            (u8, 11),
+            (u32, 15),
+            (u32, 31),
+            (u32, 47),
+            (u64, 31),
+            (u64, 63),
+            (u64, 95)
+        );
+        check_rolling_words!(
+            encoding::Naive::TGCA,
+            &seq,
+            (u8, 4),
+            (u8, 8),
+            (u8, 12),
+            (u32, 16),
+            (u32, 32),
+            (u32, 48),
+            (u64, 32),
+            (u64, 64),
+            (u64, 96)
+        );
+        check_rolling_words!(encoding::Xor10, &seq, (u8, 5), (u32, 17), (u64, 33));
+
+        // sequences shorter than k have no k-mer
+        let mut kmers = Kmer::<u64, 31, 1>::iter_sequence(&seq[..30], &encoding::Naive::ACGT);
+        assert!(kmers.next().is_none());
+
+        let kmers = Kmer::<u64, 31, 1>::iter_sequence(&seq, &encoding::Naive::ACGT);
+        assert_eq!(kmers.len(), 170);
+    }
+
+    macro_rules! rolling_properties {
+        ($(($name:ident, $ty:ty, $k:expr)), *) => (
+            $(
+                #[quickcheck]
+                fn $name(codes: Vec<u8>) -> bool {
+                    // at least one k-mer
+                    let seq: Vec<u8> = codes
+                        .iter()
+                        .chain(std::iter::repeat(&0).take($k))
+                        .map(|c| b"ACGT"[*c as usize % 4])
+                        .collect();
+
+                    check_rolling::<_, $ty, $k, { word_for_k::<$ty, $k>() }>(&seq, &encoding::Naive::ACGT);
+                    check_rolling::<_, $ty, $k, { word_for_k::<$ty, $k>() }>(&seq, &encoding::Xor10);
+                    true
+                }
+            )*
+        )
+    }
+
+    // K * 2 on a word boundary or not
+    rolling_properties!(
+        (rolling_u8_k5, u8, 5),
+        (rolling_u8_k8, u8, 8),
+        (rolling_u16_k13, u16, 13),
+        (rolling_u32_k33, u32, 33),
+        (rolling_u64_k32, u64, 32),
+        (rolling_u64_k45, u64, 45),
+        (rolling_u128_k64, u128, 64),
+        (rolling_u128_k97, u128, 97)
+    );
+
+    type ProteinKmer<P, const K: usize, const B: usize> =
+        Kmer<P, K, B, { encoding::AminoAcid::BITS }>;
+
+    fn check_protein_kmers<P, const K: usize, const B: usize>(seq: &[u8])
+    where
+        P: crate::utils::Data + num::PrimInt,
+    {
+        let encoder = encoding::AminoAcid;
+        let rolled: Vec<ProteinKmer<P, K, B>> = Kmer::iter_sequence(seq, &encoder).collect();
+        assert_eq!(rolled.len(), seq.len() - K + 1);
+
+        for (kmer, window) in rolled.iter().zip(seq.windows(K)) {
+            assert_eq!(*kmer, ProteinKmer::<P, K, B>::new(window, &encoder));
+            assert_eq!(kmer.decode(&encoder), *window);
+        }
+
+        // and backward, from the last k-mer
+        let mut kmer = rolled[rolled.len() - 1];
+        for i in (0..seq.len() - K).rev() {
+            kmer.prepend_base(seq[i], &encoder);
+            assert_eq!(kmer, rolled[i]);
+            assert_eq!(kmer.decode(&encoder), seq[i..i + K]);
+        }
+    }
+
+    #[test]
+    fn protein_kmers() {
+        let seq = b"MKTAYIAKQRQISFVKSHFSRQLEERLGLIEVQAPILSRVGDGTQDNLSGAEKAVQVKVKALPDAQ";
+
+        let kmer = ProteinKmer::<u64, 5, 1>::new(b"MKTAY", &encoding::AminoAcid);
+        assert_eq!(kmer.get(0), 10); // M
+        assert_eq!(kmer.get(4), 19); // Y
+        assert_eq!(kmer.get_prefix(2), 10 | 8 << 5); // MK
+        assert_eq!(kmer.get_suffix(2), 19 << 5); // AY
+
+        // comparisons use the 5 bits of each residue, on 2 bits per symbol
+        // only the first 4 bits, those of M, would be compared
+        let mk = ProteinKmer::<u64, 2, 1>::new(b"MK", &encoding::AminoAcid);
+        let mw = ProteinKmer::<u64, 2, 1>::new(b"MW", &encoding::AminoAcid);
+        assert_ne!(mk, mw);
+        assert_eq!(mk.cmp(&mw), Ordering::Less);
+        assert_ne!(hash_of(&mk), hash_of(&mw));
+        assert_eq!(kmer.get_range::<2>(0), mk);
+
+        check_protein_kmers::<u64, 7, { words_for_k_bits::<u64>(7, 5) }>(seq);
+        check_protein_kmers::<u64, 12, { words_for_k_bits::<u64>(12, 5) }>(seq);
+        check_protein_kmers::<u64, 13, { words_for_k_bits::<u64>(13, 5) }>(seq);
+        check_protein_kmers::<u64, 31, { words_for_k_bits::<u64>(31, 5) }>(seq);
+        check_protein_kmers::<u128, 25, { words_for_k_bits::<u128>(25, 5) }>(seq);
+        check_protein_kmers::<u128, 26, { words_for_k_bits::<u128>(26, 5) }>(seq);
+        check_protein_kmers::<u8, 9, { words_for_k_bits::<u8>(9, 5) }>(seq);
+    }
+
+    #[test]
+    #[should_panic(expected = "bits per symbol")]
+    fn encoder_of_other_width() {
+        Kmer::<u64, 5, 1>::new(b"MKTAY", &encoding::AminoAcid);
+    }
+
+    #[test]
+    fn choose_number_of_word_bits() {
+        assert_eq!(words_for_k_bits::<u64>(12, 5), 1);
+        assert_eq!(words_for_k_bits::<u64>(13, 5), 2);
+        assert_eq!(words_for_k_bits::<u128>(25, 5), 1);
+        assert_eq!(words_for_k_bits::<u128>(26, 5), 2);
+        assert_eq!(words_for_k_bits::<u8>(8, 5), 5);
+        assert_eq!(words_for_k_bits::<u64>(32, 2), word_for_k::<u64, 32>());
+        assert_eq!(words_for_k_bits::<u64>(33, 2), word_for_k::<u64, 33>());
+    }
+
+    fn hash_of<T: std::hash::Hash>(x: &T) -> u64 {
+        use std::hash::Hasher;
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_ignores_padding() {
+        let encoder = encoding::Naive::ACGT;
+        let kmer = Kmer::<u64, 5, 1>::new(b"GATTA", &encoder);
+        let junk = Kmer::<u64, 5, 1>::with_data([kmer.array[0] | 0xDEAD << 10]);
+        assert_ne!(kmer.array, junk.array);
+        assert_eq!(kmer, junk);
+        assert_eq!(kmer.cmp(&junk), Ordering::Equal);
+
+        assert_eq!(hash_of(&kmer), hash_of(&junk));
+
+        // and across words
+        let kmer = Kmer::<u8, 6, 2>::new(b"GATTAC", &encoder);
+        let junk = Kmer::<u8, 6, 2>::with_data([kmer.array[0], kmer.array[1] | 0b1111_0000]);
+        assert_eq!(kmer, junk);
+
+        let mut counts = std::collections::HashMap::new();
+        *counts.entry(kmer).or_insert(0) += 1;
+        *counts.entry(junk).or_insert(0) += 1;
+        assert_eq!(counts[&kmer], 2);
+    }
+
+    fn seq_from_codes<const K: usize>(codes: &[u8]) -> Vec<u8> {
+        (0..K)
+            .map(|i| b"ACGT"[*codes.get(i).unwrap_or(&0) as usize % 4])
+            .collect()
+    }
+
+    #[quickcheck]
+    fn ord_matches_strings(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let encoder = encoding::Naive::ACGT;
+        let (a, b) = (seq_from_codes::<23>(&a), seq_from_codes::<23>(&b));
+        let (ka, kb) = (
+            Kmer::<u16, 23, 3>::new(&a, &encoder),
+            Kmer::<u16, 23, 3>::new(&b, &encoder),
+        );
+
+        ka.cmp(&kb) == a.cmp(&b) && (ka == kb) == (a == b)
+    }
+
+    #[test]
+    fn canonical_cmp() {
+        let encoder = encoding::Naive::ACGT;
+        let kmer = Kmer::<u64, 5, 1>::new(b"TAATC", &encoder);
+        assert_eq!(kmer.canonical_cmp(&encoder), Ordering::Greater); // GATTA
+
+        let kmer = Kmer::<u64, 5, 1>::new(b"AATTC", &encoder);
+        assert_eq!(kmer.canonical_cmp(&encoder), Ordering::Less); // GAATT
+
+        let kmer = Kmer::<u64, 4, 1>::new(b"ACGT", &encoder);
+        assert_eq!(kmer.canonical_cmp(&encoder), Ordering::Equal);
+    }
+
+    macro_rules! rc_properties {
+        ($(($name:ident, $ty:ty, $k:expr)), *) => (
+            $(
+                #[quickcheck]
+                fn $name(codes: Vec<u8>) -> bool {
+                    let seq = seq_from_codes::<$k>(&codes);
+                    let mut ok = true;
+
+                    for encoder in [encoding::Naive::ACGT, encoding::Naive::GTCA] {
+                        let kmer = Kmer::<$ty, $k, { word_for_k::<$ty, $k>() }>::new(&seq, &encoder);
+                        let rc = kmer.to_reverse_complement(&encoder);
+                        let canonical = kmer.to_canonical(&encoder);
+
+                        ok &= rc.to_reverse_complement(&encoder) == kmer;
+                        ok &= canonical.is_canonical(&encoder);
+                        ok &= canonical.to_canonical(&encoder) == canonical;
+                        ok &= rc.to_canonical(&encoder) == canonical;
+                    }
+
+                    ok
+                }
+            )*
+        )
+    }
+
+    rc_properties!(
+        (rc_u64_k15, u64, 15),
+        (rc_u64_k31, u64, 31),
+        (rc_u64_k45, u64, 45),
+        (rc_u64_k65, u64, 65),
+        (rc_u128_k15, u128, 15),
+        (rc_u128_k31, u128, 31),
+        (rc_u128_k45, u128, 45),
+        (rc_u128_k65, u128, 65)
+    );
+
+    macro_rules! decode_properties {
+        ($(($name:ident, $ty:ty, $k:expr)), *) => (
+            $(
+                #[quickcheck]
+                fn $name(codes: Vec<u8>) -> bool {
+                    // Naive::ACGT orders k-mers as their strings
+                    let encoder = encoding::Naive::ACGT;
+                    let seq = seq_from_codes::<$k>(&codes);
+                    let rc_seq = rc(&seq);
+
+                    let kmer = Kmer::<$ty, $k, { word_for_k::<$ty, $k>() }>::new(&seq, &encoder);
+                    let rc = kmer.to_reverse_complement(&encoder);
+                    let canonical = kmer.to_canonical(&encoder);
+                    let from_rc = Kmer::<$ty, $k, { word_for_k::<$ty, $k>() }>::new(&rc_seq, &encoder);
+
+                    kmer.decode(&encoder) == seq
+                        && rc.decode(&encoder) == rc_seq
+                        && canonical.decode(&encoder) == seq.clone().min(rc_seq.clone())
+                        && rc == from_rc
+                        && hash_of(&rc) == hash_of(&from_rc)
+                        && kmer.is_canonical(&encoder) == (seq <= rc_seq)
+                }
+            )*
+        )
+    }
+
+    // one symbol, a full word, and two or three words
+    decode_properties!(
+        (decode_u8_k1, u8, 1),
+        (decode_u8_k4, u8, 4),
+        (decode_u8_k9, u8, 9),
+        (decode_u16_k1, u16, 1),
+        (decode_u16_k8, u16, 8),
+        (decode_u16_k17, u16, 17),
+        (decode_u32_k1, u32, 1),
+        (decode_u32_k16, u32, 16),
+        (decode_u32_k33, u32, 33),
+        (decode_u64_k1, u64, 1),
+        (decode_u64_k32, u64, 32),
+        (decode_u64_k65, u64, 65),
+        (decode_u128_k1, u128, 1),
+        (decode_u128_k64, u128, 64),
+        (decode_u128_k129, u128, 129)
+    );
+
+    #[test]
+    fn decode_xor10() {
+        let seq = b"GATTACAGATTACAGATTACA";
+        let kmer = Kmer::<u16, 21, 3>::new(seq, &encoding::Xor10);
+        assert_eq!(kmer.decode(&encoding::Xor10), seq);
+        assert_eq!(
+            kmer.to_reverse_complement(&encoding::Xor10)
+                .decode(&encoding::Xor10),
+            rc(seq)
+        );
+    }
+
+    #[test]
+    fn reverse_complement() {
+        let encoder = encoding::Naive::ACGT;
+        let kmer =
+            Kmer::<u64, 45, 2>::new(b"TAAGGATTCTAATCATAAGGATTCTAATCATAAGGATTCTAATCA", &encoder);
+        let rc = kmer.to_reverse_complement(&encoder);
+
+        assert_eq!(
+            encoder.decode(rc.array)[..45],
+            b"TGATTAGAATCCTTATGATTAGAATCCTTATGATTAGAATCCTTA"[..]
+        );
+        // TAAG... < TGAT...
+        assert!(kmer.is_canonical(&encoder));
+        assert!(!rc.is_canonical(&encoder));
+        assert_eq!(rc.to_canonical(&encoder), kmer);
+    }
 }
@@ -1,9 +1,14 @@
 /* mod declaration */
 pub mod encoding;
+#[cfg(feature = "io")]
+pub mod io;
 pub mod kmer;
 pub mod naive_impl;
 pub mod utils;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 #[cfg(test)]
 extern crate quickcheck;
 
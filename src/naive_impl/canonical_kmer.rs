@@ -2,7 +2,12 @@ use super::prelude::*;
 use super::Kmer;
 
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
 use std::convert::From;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use super::CanonicalKmerIterator;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
 pub enum MatchType {
@@ -71,6 +76,7 @@ impl CanonicalKmer {
     #[inline]
     pub fn append_base_u8(&mut self, c: u8) -> Base {
         let b = encode_binary_u8(c);
+        debug_assert!(b <= T, "cannot encode {:?} into 2 bit encoding", c as char);
         let cb = complement_base(b);
         let r = self.fw.append_base(b);
         self.rc.prepend_base(cb);
@@ -80,6 +86,7 @@ impl CanonicalKmer {
     #[inline]
     pub fn prepend_base_u8(&mut self, c: u8) -> Base {
         let b = encode_binary_u8(c);
+        debug_assert!(b <= T, "cannot encode {:?} into 2 bit encoding", c as char);
         let cb = complement_base(b);
         let r = self.fw.prepend_base(b);
         self.rc.append_base(cb);
@@ -100,6 +107,37 @@ impl CanonicalKmer {
         r
     }
 
+    // the successors of the forward k-mer for A, C, G, T appended, each with
+    // `IdentityMatch` if its canonical form is its forward k-mer (palindromes
+    // included) and `TwinMatch` if it is its reverse complement
+    pub fn canonical_successors(&self) -> [(CanonicalKmer, MatchType); 4] {
+        [A, C, G, T].map(|b| {
+            let mut next = self.clone();
+            next.append_base(b);
+            let m = next.canonical_match();
+            (next, m)
+        })
+    }
+
+    // same as `canonical_successors`, for A, C, G, T prepended
+    pub fn canonical_predecessors(&self) -> [(CanonicalKmer, MatchType); 4] {
+        [A, C, G, T].map(|b| {
+            let mut prev = self.clone();
+            prev.prepend_base(b);
+            let m = prev.canonical_match();
+            (prev, m)
+        })
+    }
+
+    #[inline]
+    fn canonical_match(&self) -> MatchType {
+        if self.fw.data <= self.rc.data {
+            MatchType::IdentityMatch
+        } else {
+            MatchType::TwinMatch
+        }
+    }
+
     #[inline]
     pub fn get_canonical_kmer(&self) -> Kmer {
         if self.fw.data < self.rc.data {
@@ -138,6 +176,13 @@ impl CanonicalKmer {
         self.rc.data
     }
 
+    // the rank of the canonical k-mer in `0..canonical_space(k)`, see
+    // `Kmer::from_canonical_rank` for the inverse
+    #[inline]
+    pub fn canonical_rank(&self) -> u64 {
+        Kmer::canonical_rank_of_word(self.get_canonical_word(), self.fw.k)
+    }
+
     #[inline]
     pub fn get_kmer_equivalency(&self, other: &Kmer) -> MatchType {
         if self.get_fw_word() == other.data {
@@ -149,6 +194,31 @@ impl CanonicalKmer {
         }
     }
 
+    // the Hamming distance of `other` to the closest of the forward and
+    // reverse complement k-mers, with `IdentityMatch` if it is the forward
+    // one (ties included) and `TwinMatch` otherwise
+    pub fn hamming_distance(&self, other: &Kmer) -> (u32, MatchType) {
+        let fw = self.fw.hamming_distance(other);
+        let rc = self.rc.hamming_distance(other);
+        if fw <= rc {
+            (fw, MatchType::IdentityMatch)
+        } else {
+            (rc, MatchType::TwinMatch)
+        }
+    }
+
+    // the offsets in `other` of its mismatches with the closest of the
+    // forward and reverse complement k-mers, chosen as in `hamming_distance`
+    pub fn mismatch_positions(&self, other: &Kmer) -> (impl Iterator<Item = usize>, MatchType) {
+        let (_, m) = self.hamming_distance(other);
+        let closest = if m == MatchType::IdentityMatch {
+            &self.fw
+        } else {
+            &self.rc
+        };
+        (closest.mismatch_positions(other), m)
+    }
+
     #[inline]
     pub fn get_word_equivalency(&self, other: u64) -> MatchType {
         if self.get_fw_word() == other {
@@ -159,6 +229,161 @@ impl CanonicalKmer {
             MatchType::NoMatch
         }
     }
+
+    // The orientation in which `set`, holding only canonical words, has
+    // this k-mer: one lookup of the canonical word.
+    pub fn query_canonical_set<S: BuildHasher>(&self, set: &HashSet<u64, S>) -> MatchType {
+        let word = self.get_canonical_word();
+        if set.contains(&word) {
+            self.get_word_equivalency(word)
+        } else {
+            MatchType::NoMatch
+        }
+    }
+
+    // The orientation in which `set`, holding words of either orientation,
+    // has this k-mer: `IdentityMatch` if it holds the forward word,
+    // `TwinMatch` if it only holds the reverse complement.
+    pub fn query_oriented_set<S: BuildHasher>(&self, set: &HashSet<u64, S>) -> MatchType {
+        if set.contains(&self.get_fw_word()) {
+            MatchType::IdentityMatch
+        } else if set.contains(&self.get_rc_word()) {
+            MatchType::TwinMatch
+        } else {
+            MatchType::NoMatch
+        }
+    }
+}
+
+// The number of k-mers of `seq` that `set` holds in their forward and
+// reverse complement orientations (see `CanonicalKmer::query_oriented_set`),
+// and of those it does not hold, as `(identity, twin, none)`. The k-mers
+// with a base other than `ACGTacgt` are skipped.
+pub fn count_matches<S: BuildHasher>(
+    seq: &[u8],
+    k: u8,
+    set: &HashSet<u64, S>,
+) -> (usize, usize, usize) {
+    let mut counts = (0, 0, 0);
+    for ckp in CanonicalKmerIterator::from_u8_slice(seq, k) {
+        match ckp.km.query_oriented_set(set) {
+            MatchType::IdentityMatch => counts.0 += 1,
+            MatchType::TwinMatch => counts.1 += 1,
+            MatchType::NoMatch => counts.2 += 1,
+        }
+    }
+    counts
+}
+
+// Equal `CanonicalKmer`s share their canonical word, so hashing it is
+// consistent with the (orientation-sensitive) derived `Eq`.
+impl Hash for CanonicalKmer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fw.k.hash(state);
+        self.get_canonical_word().hash(state);
+    }
+}
+
+// A `CanonicalKmer` that compares equal to its twin, for use as a map key.
+// `CanonicalKmer` itself keeps the orientation-sensitive `Eq`/`Ord`, since
+// which strand a k-mer was read on matters to e.g. `get_kmer_equivalency`.
+#[derive(Debug, Clone)]
+pub struct CanonicalKey(pub CanonicalKmer);
+
+impl CanonicalKey {
+    #[inline]
+    pub fn get_canonical_word(&self) -> u64 {
+        self.0.get_canonical_word()
+    }
+
+    #[inline]
+    fn key(&self) -> (u8, u64) {
+        (self.0.fw.k, self.0.get_canonical_word())
+    }
+}
+
+impl PartialEq for CanonicalKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for CanonicalKey {}
+
+impl PartialOrd for CanonicalKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CanonicalKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Hash for CanonicalKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl From<CanonicalKmer> for CanonicalKey {
+    #[inline]
+    fn from(km: CanonicalKmer) -> Self {
+        Self(km)
+    }
+}
+
+// A set of k-mers stored as their canonical words, that tells on lookup
+// whether a query was found as is or as its reverse complement. All k-mers
+// of a set should have the same length.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound(serialize = "", deserialize = "S: BuildHasher + Default"))]
+pub struct CanonicalSet<S = RandomState> {
+    words: HashSet<u64, S>,
+}
+
+impl CanonicalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: BuildHasher> CanonicalSet<S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            words: HashSet::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // inserts the canonical form of `km`, returns false if it was already
+    // in the set
+    pub fn insert(&mut self, km: &Kmer) -> bool {
+        self.words.insert(km.to_canonical().data)
+    }
+
+    // `IdentityMatch` if the canonical word stored is the forward word of
+    // `ck`, `TwinMatch` if it is its reverse complement
+    pub fn contains(&self, ck: &CanonicalKmer) -> MatchType {
+        ck.query_canonical_set(&self.words)
+    }
+
+    // inserts the k-mers of `seq`, skipping those with a base other
+    // than `ACGTacgt`
+    pub fn extend_from_seq(&mut self, seq: &[u8], k: u8) {
+        for ckp in CanonicalKmerIterator::from_u8_slice(seq, k) {
+            self.words.insert(ckp.km.get_canonical_word());
+        }
+    }
 }
 
 impl From<Kmer> for CanonicalKmer {
@@ -203,16 +428,33 @@ impl From<CanonicalKmer> for String {
 
 impl std::fmt::Display for CanonicalKmer {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let msg: String = self.clone().into();
-        write!(f, "{msg}")
+        // defer to `Kmer` so that `{:#}` emits uppercase here too
+        std::fmt::Display::fmt(&self.get_canonical_kmer(), f)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::rc;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
     const K: u8 = 31;
 
+    fn hash_of<H: Hash>(h: &H) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        h.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[quickcheck]
+    fn hash_is_canonical(word: u64) -> bool {
+        let canon_km = CanonicalKmer::from_u64(word, K);
+        let canon_km2 = CanonicalKmer::from(canon_km.get_rc_mer());
+        hash_of(&canon_km) == hash_of(&canon_km2)
+            && hash_of(&CanonicalKey(canon_km)) == hash_of(&CanonicalKey(canon_km2))
+    }
+
     #[quickcheck]
     fn swap_identity(word: u64) -> bool {
         let mut a = CanonicalKmer::from_u64(word, K);
@@ -279,6 +521,13 @@ mod tests {
         assert_eq!(canon_km.fw.to_string(), "ccttg");
     }
 
+    #[test]
+    fn test_display() {
+        let canon_km = CanonicalKmer::from("caagt");
+        assert_eq!(format!("{canon_km}"), "acttg");
+        assert_eq!(format!("{canon_km:#}"), "ACTTG");
+    }
+
     #[test]
     fn test_equivalency() {
         let canon_km = CanonicalKmer::from("acttg");
@@ -295,4 +544,259 @@ mod tests {
         let e = canon_km.get_kmer_equivalency(&canon_km2.get_fw_mer());
         assert_eq!(e, MatchType::NoMatch);
     }
+
+    #[test]
+    fn test_canonical_key() {
+        let fw = CanonicalKey::from(CanonicalKmer::from("acttg"));
+        let rc = CanonicalKey::from(CanonicalKmer::from("caagt"));
+        assert_ne!(fw.0, rc.0);
+        assert_eq!(fw, rc);
+
+        let mut counts: HashMap<CanonicalKey, u32> = HashMap::new();
+        *counts.entry(fw.clone()).or_default() += 1;
+        *counts.entry(rc.clone()).or_default() += 1;
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts.get(&rc), Some(&2));
+
+        // same canonical word, different k
+        let short = CanonicalKey::from(CanonicalKmer::from("acttg"));
+        let long = CanonicalKey::from(CanonicalKmer::from("acttga"));
+        assert_eq!(short.get_canonical_word(), long.get_canonical_word());
+        assert_ne!(short, long);
+    }
+
+    #[test]
+    fn canonical_set() {
+        let seq =
+            b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGGNGGCTGCCAGAACTAGTGGG";
+        let mut set = CanonicalSet::new();
+        set.extend_from_seq(seq, K);
+        let n = CanonicalKmerIterator::from_u8_slice(seq, K).count();
+        assert_eq!(set.len(), n);
+
+        // the matches of a k-mer and of its reverse complement are twins
+        let expected = |km: &CanonicalKmer| {
+            if km.is_fw_canonical() {
+                MatchType::IdentityMatch
+            } else {
+                MatchType::TwinMatch
+            }
+        };
+        let fw: Vec<MatchType> = CanonicalKmerIterator::from_u8_slice(seq, K)
+            .map(|ckp| set.contains(&ckp.km))
+            .collect();
+        let fw_expected: Vec<MatchType> = CanonicalKmerIterator::from_u8_slice(seq, K)
+            .map(|ckp| expected(&ckp.km))
+            .collect();
+        assert_eq!(fw, fw_expected);
+
+        let rc = rc(seq);
+        let mut rc: Vec<MatchType> = CanonicalKmerIterator::from_u8_slice(&rc, K)
+            .map(|ckp| set.contains(&ckp.km))
+            .collect();
+        rc.reverse();
+        assert_eq!(rc.len(), n);
+        for (f, r) in fw.iter().zip(rc.iter()) {
+            assert_ne!(*f, MatchType::NoMatch);
+            assert_ne!(*r, MatchType::NoMatch);
+            assert_ne!(f, r);
+        }
+
+        let other = CanonicalKmer::from(&b"ACGTACGTACGTACGTACGTACGTACGTACG"[..]);
+        assert_eq!(set.contains(&other), MatchType::NoMatch);
+    }
+
+    #[test]
+    fn canonical_set_insert() {
+        let mut set = CanonicalSet::with_hasher(DefaultHasherBuilder);
+        let km = Kmer::from("tta");
+        assert!(set.insert(&km));
+        assert!(!set.insert(&km.to_reverse_complement()));
+        assert_eq!(set.len(), 1);
+        // "taa" is the canonical form of "tta"
+        assert_eq!(
+            set.contains(&CanonicalKmer::from("taa")),
+            MatchType::IdentityMatch
+        );
+        assert_eq!(
+            set.contains(&CanonicalKmer::from("tta")),
+            MatchType::TwinMatch
+        );
+
+        // palindromes are found as is
+        set.insert(&Kmer::from("acgt"));
+        assert_eq!(
+            set.contains(&CanonicalKmer::from("acgt")),
+            MatchType::IdentityMatch
+        );
+    }
+
+    #[test]
+    fn query_sets() {
+        let seq = b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGG";
+        let kmers: Vec<CanonicalKmer> = CanonicalKmerIterator::from_u8_slice(seq, K)
+            .map(|ckp| ckp.km)
+            .collect();
+        let fw: HashSet<u64> = kmers.iter().map(|km| km.get_fw_word()).collect();
+        let rc: HashSet<u64> = kmers.iter().map(|km| km.get_rc_word()).collect();
+        let canonical: HashSet<u64> = kmers.iter().map(|km| km.get_canonical_word()).collect();
+
+        for km in &kmers {
+            assert_eq!(km.query_oriented_set(&fw), MatchType::IdentityMatch);
+            assert_eq!(km.query_oriented_set(&rc), MatchType::TwinMatch);
+
+            // a canonical set answers the same with either lookup
+            let expected = km.get_word_equivalency(km.get_canonical_word());
+            assert_eq!(km.query_canonical_set(&canonical), expected);
+            assert_eq!(km.query_oriented_set(&canonical), expected);
+            assert_eq!(
+                km.query_canonical_set(&canonical),
+                if km.is_fw_canonical() {
+                    MatchType::IdentityMatch
+                } else {
+                    MatchType::TwinMatch
+                }
+            );
+
+            let other = CanonicalKmer::from(km.get_rc_mer());
+            assert_eq!(other.query_oriented_set(&fw), MatchType::TwinMatch);
+            assert_eq!(other.query_oriented_set(&rc), MatchType::IdentityMatch);
+        }
+
+        let other = CanonicalKmer::from(&b"ACGTACGTACGTACGTACGTACGTACGTACG"[..]);
+        assert_eq!(other.query_oriented_set(&fw), MatchType::NoMatch);
+        assert_eq!(other.query_canonical_set(&canonical), MatchType::NoMatch);
+
+        // palindromes match as is
+        let palindrome = CanonicalKmer::from("ACGT");
+        let set: HashSet<u64> = [palindrome.get_fw_word()].into_iter().collect();
+        assert_eq!(
+            palindrome.query_oriented_set(&set),
+            MatchType::IdentityMatch
+        );
+        assert_eq!(
+            palindrome.query_canonical_set(&set),
+            MatchType::IdentityMatch
+        );
+    }
+
+    #[test]
+    fn test_count_matches() {
+        let seq = b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGG";
+        let n = CanonicalKmerIterator::from_u8_slice(seq, K).count();
+        let fw: HashSet<u64> = CanonicalKmerIterator::from_u8_slice(seq, K)
+            .map(|ckp| ckp.km.get_fw_word())
+            .collect();
+        assert_eq!(count_matches(seq, K, &fw), (n, 0, 0));
+        assert_eq!(count_matches(&rc(seq), K, &fw), (0, n, 0));
+
+        let mut set = CanonicalSet::new();
+        set.extend_from_seq(seq, K);
+        let canonical: HashSet<u64> = set.words.iter().copied().collect();
+        let (identity, twin, none) = count_matches(seq, K, &canonical);
+        assert_eq!((identity + twin, none), (n, 0));
+        let n_fw_canonical = CanonicalKmerIterator::from_u8_slice(seq, K)
+            .filter(|ckp| ckp.km.is_fw_canonical())
+            .count();
+        assert_eq!(identity, n_fw_canonical);
+
+        // the k-mers overlapping the N are skipped, the others are unknown
+        let other = b"ACGTACGTACGTACGTACGTACGTACGTACGTANCGTAC";
+        assert_eq!(count_matches(other, K, &fw), (0, 0, 3));
+        assert_eq!(count_matches(b"", K, &fw), (0, 0, 0));
+    }
+
+    #[derive(Clone, Default)]
+    struct DefaultHasherBuilder;
+
+    impl BuildHasher for DefaultHasherBuilder {
+        type Hasher = DefaultHasher;
+
+        fn build_hasher(&self) -> DefaultHasher {
+            DefaultHasher::new()
+        }
+    }
+
+    #[test]
+    fn canonical_set_serde() {
+        let seq = b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGG";
+        let mut set = CanonicalSet::with_hasher(DefaultHasherBuilder);
+        set.extend_from_seq(seq, 11);
+
+        let json = serde_json::to_string(&set).unwrap();
+        let loaded: CanonicalSet<DefaultHasherBuilder> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.len(), set.len());
+        for ckp in CanonicalKmerIterator::from_u8_slice(seq, 11) {
+            assert_eq!(loaded.contains(&ckp.km), set.contains(&ckp.km));
+            assert_ne!(loaded.contains(&ckp.km), MatchType::NoMatch);
+        }
+    }
+
+    #[test]
+    fn canonical_hamming_distance() {
+        let ck = CanonicalKmer::from("GATTACA");
+        // TGTAATC is the reverse complement
+        assert_eq!(
+            ck.hamming_distance(&Kmer::from("TGTAATG")),
+            (1, MatchType::TwinMatch)
+        );
+        assert_eq!(
+            ck.hamming_distance(&Kmer::from("GATTACC")),
+            (1, MatchType::IdentityMatch)
+        );
+        assert_eq!(
+            ck.hamming_distance(&Kmer::from("GATTACA")),
+            (0, MatchType::IdentityMatch)
+        );
+
+        let (positions, m) = ck.mismatch_positions(&Kmer::from("AGTAATG"));
+        assert_eq!(m, MatchType::TwinMatch);
+        assert_eq!(positions.collect::<Vec<_>>(), [0, 6]);
+
+        // a palindrome is at the same distance of both, reported as forward
+        let ck = CanonicalKmer::from("ACGT");
+        assert_eq!(
+            ck.hamming_distance(&Kmer::from("ACGA")),
+            (1, MatchType::IdentityMatch)
+        );
+    }
+
+    #[test]
+    fn canonical_neighbors() {
+        for s in [
+            "ACGTT",
+            "GATTACA",
+            "ACGT",
+            "T",
+            "CCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC",
+        ] {
+            let ck = CanonicalKmer::from(s);
+            let km = ck.get_fw_mer();
+            let succ = ck.canonical_successors();
+            let pred = ck.canonical_predecessors();
+            for ((next, m), expected) in succ.iter().zip(km.successors()) {
+                assert_eq!(next.get_fw_mer(), expected);
+                assert_eq!(next.get_rc_mer(), expected.to_reverse_complement());
+                let canon = expected.to_canonical();
+                assert_eq!(next.get_canonical_word(), canon.data);
+                let twin = *m == MatchType::TwinMatch;
+                assert_eq!(twin, canon != expected);
+                assert_eq!(next.get_kmer_equivalency(&canon), *m);
+            }
+            for ((prev, m), expected) in pred.iter().zip(km.predecessors()) {
+                assert_eq!(prev.get_fw_mer(), expected);
+                assert_eq!(prev.get_rc_mer(), expected.to_reverse_complement());
+                let canon = expected.to_canonical();
+                assert_eq!(*m == MatchType::TwinMatch, canon != expected);
+            }
+        }
+        // ACGT + A gives CGTA, smaller than its twin TACG in the 2-bit order
+        let (next, m) = &CanonicalKmer::from("ACGT").canonical_successors()[0];
+        assert_eq!(next.get_canonical_kmer().to_string(), "cgta");
+        assert_eq!(*m, MatchType::IdentityMatch);
+        // palindromes count as forward
+        let (next, m) = &CanonicalKmer::from("CACG").canonical_successors()[3];
+        assert_eq!(next.get_fw_mer().to_string(), "acgt");
+        assert_eq!(*m, MatchType::IdentityMatch);
+    }
 }
@@ -4,12 +4,15 @@
 // KmerIterator class (part of bfgraph) :
 // https://github.com/pmelsted/bfgraph/blob/master/src/KmerIterator.cpp
 
+use std::borrow::Cow;
+
 use super::prelude::*;
 use super::CanonicalKmer;
 
 // holds what is essentially a pair of
 // km: the canonical k-mer on the read
 // pos: the offset on the read where this k-mer starts
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CanonicalKmerPos {
     pub km: CanonicalKmer,
     pub pos: i32,
@@ -25,12 +28,13 @@ impl CanonicalKmerPos {
 }
 
 // A CanonicalKmerIterator holds a
-// shared reference to an underlying [u8] slice `seq`.
+// shared reference to an underlying [u8] slice `seq`,
+// or owns it when built with `from_vec`.
 // It is capable of iterating over this sequence (skipping invalid
 // k-mers, e.g. k-mers containing `N`), and producing
 // a `CanonicalKmerPos` struct for all valid k-mers in `seq`.
 pub struct CanonicalKmerIterator<'a> {
-    seq: &'a [u8],
+    seq: Cow<'a, [u8]>,
     value_pair: CanonicalKmerPos,
     invalid: bool,
     last_invalid: i32,
@@ -70,8 +74,12 @@ impl<'slice> CanonicalKmerIterator<'slice> {
     }
 
     pub fn from_u8_slice(s: &'slice [u8], k: u8) -> CanonicalKmerIterator {
+        Self::from_cow(Cow::Borrowed(s), k)
+    }
+
+    fn from_cow(seq: Cow<'slice, [u8]>, k: u8) -> Self {
         let mut r = Self {
-            seq: s,
+            seq,
             value_pair: CanonicalKmerPos::new(k),
             invalid: false,
             last_invalid: -1i32,
@@ -82,6 +90,17 @@ impl<'slice> CanonicalKmerIterator<'slice> {
         r
     }
 
+    // the sequence being iterated over
+    #[inline]
+    pub fn seq(&self) -> &[u8] {
+        &self.seq
+    }
+
+    #[inline]
+    pub fn k(&self) -> u8 {
+        self.k as u8
+    }
+
     // returns true if this iterator is exhausted
     // (i.e. if there are no more valid k-mers beyond)
     // the current position, and false otherwise.
@@ -110,12 +129,72 @@ impl<'slice> CanonicalKmerIterator<'slice> {
         v
     }
 
+    // moves the iterator to the first valid k-mer starting at
+    // or after `pos`, rebuilding the k-mer from scratch rather than
+    // rolling over the intermediate positions. If the current k-mer
+    // already starts at or after `pos`, the iterator is left in place.
+    #[inline]
+    pub fn inc_to(&mut self, pos: usize) -> bool {
+        let pos = pos as i32;
+        if !self.invalid && pos > self.value_pair.pos {
+            // pretend the base just before `pos` was invalid
+            // so that the window restarts at `pos`
+            self.last_invalid = pos - 1;
+            self.find_next(pos - 1, pos - 1);
+        }
+        !self.invalid
+    }
+
     #[inline]
     pub fn get(&self) -> &CanonicalKmerPos {
         &self.value_pair
     }
 }
 
+impl CanonicalKmerIterator<'static> {
+    /// Iterates over the k-mers of a sequence the iterator takes ownership
+    /// of, e.g. to return it from a function reading a record into a local
+    /// buffer or to move it to another thread.
+    ///
+    /// ```
+    /// use std::io::BufRead;
+    /// use kmers::naive_impl::CanonicalKmerIterator;
+    ///
+    /// let reader = std::io::Cursor::new("ACGTACGTAC\nTTGCANNGCAT\n");
+    /// let iters: Vec<CanonicalKmerIterator<'static>> = reader
+    ///     .lines()
+    ///     .map(|line| CanonicalKmerIterator::from_vec(line.unwrap().into_bytes(), 5))
+    ///     .collect();
+    ///
+    /// let handles: Vec<_> = iters
+    ///     .into_iter()
+    ///     .map(|iter| std::thread::spawn(move || iter.count()))
+    ///     .collect();
+    /// let counts: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    /// assert_eq!(counts, vec![6, 1]);
+    /// ```
+    pub fn from_vec(v: Vec<u8>, k: u8) -> Self {
+        Self::from_cow(Cow::Owned(v), k)
+    }
+}
+
+// Yields the current k-mer and then advances the iterator, so the
+// items produced are exactly those observed through `get()` with
+// repeated calls to `inc()`. Note that after a call to `next()`,
+// `get()` refers to the *following* k-mer.
+impl Iterator for CanonicalKmerIterator<'_> {
+    type Item = CanonicalKmerPos;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.invalid {
+            return None;
+        }
+        let r = self.value_pair.clone();
+        self.inc();
+        Some(r)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +283,93 @@ mod tests {
         ck_iter.inc();
         assert!(ck_iter.exhausted());
     }
+
+    fn collect_manual(r: &[u8], k: u8) -> Vec<CanonicalKmerPos> {
+        let mut ck_iter = CanonicalKmerIterator::from_u8_slice(r, k);
+        let mut res = Vec::new();
+        while !ck_iter.exhausted() {
+            res.push(ck_iter.get().clone());
+            ck_iter.inc();
+        }
+        res
+    }
+
+    #[test]
+    fn test_iterator_matches_inc() {
+        let seqs: [&[u8]; 4] = [
+            b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGG",
+            b"TTTTNGGCCATTTTTCCTGTTCTTCAAGAAAACAGGNAGATAACTAGAAGGACTAGAGAATGGN",
+            b"NNTTTTGGCCATNNNNNNNTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGNAGGACTAG",
+            b"TTTTGGCCA",
+        ];
+
+        for r in seqs {
+            for k in [5u8, 17, 31] {
+                let expected = collect_manual(r, k);
+                let kmers: Vec<CanonicalKmerPos> =
+                    CanonicalKmerIterator::from_u8_slice(r, k).collect();
+                assert_eq!(kmers, expected);
+
+                for ckp in kmers {
+                    let p = ckp.pos as usize;
+                    assert_eq!(ckp.km, CanonicalKmer::from(&r[p..p + k as usize]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_vec() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CanonicalKmerIterator<'static>>();
+        assert_send_sync::<CanonicalKmerIterator<'_>>();
+
+        // the iterator outlives the buffer it was read into
+        fn read_record(k: u8) -> CanonicalKmerIterator<'static> {
+            let buf = b"TTTTNGGCCATTTTTCCTGTTCTTCAAGAAAACAGG".to_vec();
+            CanonicalKmerIterator::from_vec(buf, k)
+        }
+
+        let r = b"TTTTNGGCCATTTTTCCTGTTCTTCAAGAAAACAGG";
+        for k in [5u8, 17, 31] {
+            let iter = read_record(k);
+            assert_eq!(iter.seq(), r);
+            assert_eq!(iter.k(), k);
+            let kmers: Vec<CanonicalKmerPos> = iter.collect();
+            assert_eq!(kmers, collect_manual(r, k));
+        }
+
+        let iter = CanonicalKmerIterator::from_u8_slice(r, 17);
+        assert_eq!((iter.seq(), iter.k()), (&r[..], 17));
+    }
+
+    #[test]
+    fn test_inc_to() {
+        let r = b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGNAGATAACTAGAAGGACTAGAGAATGGGGCTGCCAGAACTAGTGG";
+        let k = 31u8;
+
+        let mut ck_iter = CanonicalKmerIterator::from_u8_slice(&r[..], k);
+        assert!(ck_iter.inc_to(3));
+        assert_eq!(3, ck_iter.get().pos);
+        assert_eq!(CanonicalKmer::from(&r[3..34]), ck_iter.get().km);
+
+        // moving backwards leaves the iterator in place
+        assert!(ck_iter.inc_to(1));
+        assert_eq!(3, ck_iter.get().pos);
+
+        // any k-mer starting in 5..=35 would span the N
+        assert!(ck_iter.inc_to(5));
+        assert_eq!(36, ck_iter.get().pos);
+        assert_eq!(CanonicalKmer::from(&r[36..67]), ck_iter.get().km);
+
+        assert!(ck_iter.inc_to(40));
+        let rest: Vec<i32> = ck_iter.map(|ckp| ckp.pos).collect();
+        let expected: Vec<i32> = (40..=(r.len() as i32 - 31)).collect();
+        assert_eq!(rest, expected);
+
+        let mut ck_iter = CanonicalKmerIterator::from_u8_slice(&r[..], k);
+        assert!(!ck_iter.inc_to(r.len() - 30));
+        assert!(ck_iter.exhausted());
+        assert!(ck_iter.next().is_none());
+    }
 }
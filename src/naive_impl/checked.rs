@@ -0,0 +1,546 @@
+// Non-panicking counterparts to the encoding routines used by
+// `Kmer` and `SeqVector`, for input that may contain bases outside
+// of `ACGTacgt` (e.g. `N`s or IUPAC codes in real FASTA data).
+
+use std::borrow::Cow;
+
+use super::hash::splitmix64;
+use super::prelude::*;
+use super::{CanonicalKmer, Kmer};
+
+// The error returned when a k-mer or sequence cannot be built.
+// For `InvalidBase`, `pos` is the offset of the offending byte in the
+// input as given by the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KmerError {
+    InvalidBase { pos: usize, byte: u8 },
+    // the sequence does not fit in the target type, e.g. more than 32
+    // bases for a `Kmer`
+    TooLong { len: usize },
+    // a k outside of 1..=max, e.g. 32 for a `Kmer`
+    InvalidK { k: u8, max: u8 },
+}
+
+// the former name of `KmerError`, when it only covered encoding errors
+pub type EncodeError = KmerError;
+
+impl std::fmt::Display for KmerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::InvalidBase { pos, byte } => write!(
+                f,
+                "cannot encode {:?} (0x{:02x}) at position {} into 2 bit encoding",
+                *byte as char, byte, pos
+            ),
+            Self::TooLong { len } => write!(f, "sequence of length {} is too long", len),
+            Self::InvalidK { k, max } => write!(f, "k must be between 1 and {}, got {}", max, k),
+        }
+    }
+}
+
+impl std::error::Error for KmerError {}
+
+// The error returned when decoding into a buffer shorter than the
+// `needed` bases to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferTooSmall {
+    pub needed: usize,
+    pub len: usize,
+}
+
+impl std::fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "buffer of length {} cannot hold {} bases",
+            self.len, self.needed
+        )
+    }
+}
+
+impl std::error::Error for BufferTooSmall {}
+
+// `Ok(())` if `buf` can hold `needed` bases
+#[inline]
+pub(crate) fn check_buffer(buf: &[u8], needed: usize) -> Result<(), BufferTooSmall> {
+    if buf.len() < needed {
+        Err(BufferTooSmall {
+            needed,
+            len: buf.len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// returns the position and value of the first byte of `bytes`
+// that is not a valid nucleotide, if any
+#[inline]
+pub(crate) fn find_invalid(bytes: &[u8]) -> Result<(), KmerError> {
+    match bytes.iter().position(|&c| encode_binary_u8(c) > T) {
+        Some(pos) => Err(KmerError::InvalidBase {
+            pos,
+            byte: bytes[pos],
+        }),
+        None => Ok(()),
+    }
+}
+
+// the 2 bit code of a single base
+#[inline]
+fn encode_checked(c: u8) -> Result<Base, KmerError> {
+    match encode_binary_u8(c) {
+        b if b > T => Err(KmerError::InvalidBase { pos: 0, byte: c }),
+        b => Ok(b),
+    }
+}
+
+// Pack up to 32 bases into a word, mapping any invalid base to `A`.
+#[cfg_attr(not(feature = "seq-vector"), allow(dead_code))]
+#[inline]
+pub(crate) fn encode_word_lossy(bytes: &[u8]) -> u64 {
+    assert!(bytes.len() <= 32);
+
+    let mut w = 0_u64;
+    // read sequence "left to right" from "lower to higher" order bits
+    for c in bytes.iter().rev() {
+        let b = encode_binary_u8(*c);
+        w <<= 2;
+        w |= if b > T { A } else { b };
+    }
+    w
+}
+
+// How the `*_with_policy` constructors handle bytes outside of `ACGTacgt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmbiguityPolicy {
+    // fail with `KmerError::InvalidBase` at the first such byte
+    Reject,
+    // encode any such byte as `A`, as `SeqVector::push_chars` does
+    MapToA,
+    // replace each IUPAC code (`RYSWKMBDHVN`, in either case) by one of
+    // the bases it stands for, chosen uniformly from a generator seeded
+    // with the given value, so that the same seed and input always give
+    // the same sequence. Other bytes are rejected as with `Reject`.
+    Randomize(u64),
+}
+
+// the bases an IUPAC ambiguity code stands for
+#[inline]
+fn iupac_bases(c: u8) -> Option<&'static [u8]> {
+    match c.to_ascii_uppercase() {
+        b'R' => Some(b"AG"),
+        b'Y' => Some(b"CT"),
+        b'S' => Some(b"CG"),
+        b'W' => Some(b"AT"),
+        b'K' => Some(b"GT"),
+        b'M' => Some(b"AC"),
+        b'B' => Some(b"CGT"),
+        b'D' => Some(b"AGT"),
+        b'H' => Some(b"ACT"),
+        b'V' => Some(b"ACG"),
+        b'N' => Some(b"ACGT"),
+        _ => None,
+    }
+}
+
+// `bytes` with every byte outside of `ACGTacgt` resolved by `policy`,
+// only copied if some byte has to be replaced.
+pub(crate) fn resolve_ambiguous(
+    bytes: &[u8],
+    policy: AmbiguityPolicy,
+) -> Result<Cow<'_, [u8]>, KmerError> {
+    let first = match bytes.iter().position(|&c| encode_binary_u8(c) > T) {
+        Some(pos) => pos,
+        None => return Ok(Cow::Borrowed(bytes)),
+    };
+
+    let mut resolved = bytes.to_vec();
+    match policy {
+        AmbiguityPolicy::Reject => {
+            return Err(KmerError::InvalidBase {
+                pos: first,
+                byte: bytes[first],
+            })
+        }
+        AmbiguityPolicy::MapToA => {
+            for c in resolved[first..]
+                .iter_mut()
+                .filter(|c| encode_binary_u8(**c) > T)
+            {
+                *c = b'A';
+            }
+        }
+        AmbiguityPolicy::Randomize(seed) => {
+            let mut state = seed;
+            for (pos, c) in resolved.iter_mut().enumerate().skip(first) {
+                if encode_binary_u8(*c) <= T {
+                    continue;
+                }
+                let bases = iupac_bases(*c).ok_or(KmerError::InvalidBase { pos, byte: *c })?;
+                *c = bases[(splitmix64(&mut state) % bases.len() as u64) as usize];
+            }
+        }
+    }
+    Ok(Cow::Owned(resolved))
+}
+
+// The word of the k-mer spelled by `s`, the inverse of `word_to_string`.
+// Fails on bases other than `ACGTacgt` and on more than 32 bases.
+pub fn string_to_word(s: &str) -> Result<u64, KmerError> {
+    Kmer::from_bytes_checked(s.as_bytes()).map(|km| km.into_u64())
+}
+
+impl Kmer {
+    // Like `from_u64`, but k must be between 1 and 32
+    pub fn from_u64_checked(data: u64, k: u8) -> Result<Self, KmerError> {
+        if !(1..=32).contains(&k) {
+            return Err(KmerError::InvalidK { k, max: 32 });
+        }
+        Ok(Self::from_u64(data, k))
+    }
+
+    // Like `From<&[u8]>` and `From<&str>`, which `Kmer` already implements
+    // and so can not also get `TryFrom` for, but returns an error instead
+    // of panicking on invalid input.
+    pub fn from_bytes_checked<S: AsRef<[u8]>>(s: S) -> Result<Self, KmerError> {
+        let s = s.as_ref();
+        if s.len() > 32 {
+            return Err(KmerError::TooLong { len: s.len() });
+        }
+        find_invalid(s)?;
+        Ok(Self::from(s))
+    }
+
+    // Like `from_bytes_checked`, but bytes other than `ACGTacgt` are
+    // handled according to `policy` rather than always rejected.
+    pub fn from_bytes_with_policy(s: &[u8], policy: AmbiguityPolicy) -> Result<Self, KmerError> {
+        if s.len() > 32 {
+            return Err(KmerError::TooLong { len: s.len() });
+        }
+        Ok(Self::from(resolve_ambiguous(s, policy)?.as_ref()))
+    }
+
+    // Writes the uppercase bases at the start of `buf` and returns how
+    // many were written, without allocating. Fails if `buf` is shorter
+    // than the k-mer, leaving it untouched.
+    pub fn write_into(&self, buf: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        check_buffer(buf, self.len())?;
+        self.write_bytes(buf);
+        Ok(self.len())
+    }
+
+    // Like `append_base_u8`, but the k-mer is left untouched if `c` is
+    // not a valid nucleotide.
+    pub fn try_append_base_u8(&mut self, c: u8) -> Result<Base, KmerError> {
+        Ok(self.append_base(encode_checked(c)?))
+    }
+
+    // Like `prepend_base_u8`, but the k-mer is left untouched if `c` is
+    // not a valid nucleotide.
+    pub fn try_prepend_base_u8(&mut self, c: u8) -> Result<Base, KmerError> {
+        Ok(self.prepend_base(encode_checked(c)?))
+    }
+}
+
+impl std::str::FromStr for Kmer {
+    type Err = KmerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes_checked(s.as_bytes())
+    }
+}
+
+impl CanonicalKmer {
+    pub fn try_append_base_u8(&mut self, c: u8) -> Result<Base, KmerError> {
+        Ok(self.append_base(encode_checked(c)?))
+    }
+
+    pub fn try_prepend_base_u8(&mut self, c: u8) -> Result<Base, KmerError> {
+        Ok(self.prepend_base(encode_checked(c)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes_checked() {
+        assert_eq!(
+            Kmer::from_bytes_checked(b"ACGTacgt"),
+            Ok(Kmer::from(b"ACGTacgt"))
+        );
+
+        let e = Kmer::from_bytes_checked(b"ACGTNCGT").unwrap_err();
+        assert_eq!(e, KmerError::InvalidBase { pos: 4, byte: b'N' });
+        assert_eq!(
+            e.to_string(),
+            "cannot encode 'N' (0x4e) at position 4 into 2 bit encoding"
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_checked_errors() {
+        let s = b"ACGTACGTACGTACGTACGTACGTACGTACGTA";
+        assert_eq!(s.len(), 33);
+        let e = Kmer::from_bytes_checked(s).unwrap_err();
+        assert_eq!(e, KmerError::TooLong { len: 33 });
+        assert_eq!(e.to_string(), "sequence of length 33 is too long");
+        assert!(Kmer::from_bytes_checked(&s[..32]).is_ok());
+
+        // positions are reported as in the input, not in the packed word
+        for pos in [0, 15, 31] {
+            let mut s = s[..32].to_vec();
+            s[pos] = b'n';
+            assert_eq!(
+                Kmer::from_bytes_checked(&s),
+                Err(KmerError::InvalidBase { pos, byte: b'n' })
+            );
+        }
+        assert_eq!(Kmer::from_bytes_checked(b""), Ok(Kmer::from(b"")));
+    }
+
+    #[quickcheck]
+    fn word_string_round_trip(word: u64, k: u8) -> bool {
+        let k = k % 32 + 1;
+        let km = Kmer::from_u64(word, k);
+        let s = super::super::word_to_string(word, k);
+        s == km.to_string_upper()
+            && s == format!("{:#}", km)
+            && string_to_word(&s) == Ok(km.into_u64())
+            && string_to_word(&s.to_ascii_lowercase()) == Ok(km.into_u64())
+    }
+
+    #[test]
+    fn test_string_to_word() {
+        assert_eq!(string_to_word("GT"), Ok(0b1110));
+        assert_eq!(string_to_word(""), Ok(0));
+        assert_eq!(
+            string_to_word("GATNACA"),
+            Err(KmerError::InvalidBase { pos: 3, byte: b'N' })
+        );
+        assert_eq!(
+            string_to_word(&"A".repeat(33)),
+            Err(KmerError::TooLong { len: 33 })
+        );
+    }
+
+    #[test]
+    fn test_from_u64_checked() {
+        assert_eq!(Kmer::from_u64_checked(0b1110, 2), Ok(Kmer::from("GT")));
+        assert_eq!(
+            Kmer::from_u64_checked(u64::MAX, 32),
+            Ok(Kmer::from_u64(u64::MAX, 32))
+        );
+        for k in [0, 33, 255] {
+            let e = Kmer::from_u64_checked(1, k).unwrap_err();
+            assert_eq!(e, KmerError::InvalidK { k, max: 32 });
+            assert_eq!(
+                e.to_string(),
+                format!("k must be between 1 and 32, got {}", k)
+            );
+        }
+        // the former name still works
+        let e: EncodeError = Kmer::from_bytes_checked(b"AN").unwrap_err();
+        assert!(matches!(e, KmerError::InvalidBase { pos: 1, byte: b'N' }));
+    }
+
+    #[test]
+    #[should_panic(expected = "k-mers longer than 32 bases not supported, got k = 33")]
+    fn test_from_u64_too_long() {
+        let _ = Kmer::from_u64(0, 33);
+    }
+
+    #[test]
+    fn test_from_str_checked() -> Result<(), KmerError> {
+        let km = Kmer::from_bytes_checked("acgta")?;
+        assert_eq!(km, Kmer::from("acgta"));
+        assert_eq!(Kmer::from_bytes_checked(&b"ACGTA"[..])?, km);
+        assert_eq!("ACGTA".parse::<Kmer>()?, km);
+
+        assert_eq!(
+            Kmer::from_bytes_checked("acgtR"),
+            Err(KmerError::InvalidBase { pos: 4, byte: b'R' })
+        );
+        assert_eq!(
+            "a".repeat(40).parse::<Kmer>(),
+            Err(KmerError::TooLong { len: 40 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_append_base_u8() {
+        let mut km = Kmer::from(b"ACGTA");
+        assert_eq!(km.try_append_base_u8(b'c'), Ok(A));
+        assert_eq!(km.try_prepend_base_u8(b'G'), Ok(C));
+        assert_eq!(km, Kmer::from(b"GCGTA"));
+
+        let e = KmerError::InvalidBase { pos: 0, byte: b'N' };
+        assert_eq!(km.try_append_base_u8(b'N'), Err(e));
+        assert_eq!(km.try_prepend_base_u8(b'N'), Err(e));
+        assert_eq!(km, Kmer::from(b"GCGTA"));
+
+        let mut ckm = CanonicalKmer::from("ACGTA");
+        assert_eq!(ckm.try_append_base_u8(b'c'), Ok(A));
+        assert_eq!(ckm.try_append_base_u8(b'N'), Err(e));
+        assert_eq!(ckm.try_prepend_base_u8(b'N'), Err(e));
+        assert_eq!(ckm, CanonicalKmer::from("CGTAC"));
+        assert_eq!(ckm.get_rc_mer(), Kmer::from(b"GTACG"));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_append_invalid_base_u8() {
+        Kmer::from(b"ACGTA").append_base_u8(b'N');
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_prepend_invalid_base_u8_canonical() {
+        CanonicalKmer::from("ACGTA").prepend_base_u8(b'N');
+    }
+
+    const IUPAC: [(u8, &[u8]); 11] = [
+        (b'R', b"AG"),
+        (b'Y', b"CT"),
+        (b'S', b"CG"),
+        (b'W', b"AT"),
+        (b'K', b"GT"),
+        (b'M', b"AC"),
+        (b'B', b"CGT"),
+        (b'D', b"AGT"),
+        (b'H', b"ACT"),
+        (b'V', b"ACG"),
+        (b'N', b"ACGT"),
+    ];
+
+    #[test]
+    fn test_from_bytes_with_policy() {
+        for policy in [
+            AmbiguityPolicy::Reject,
+            AmbiguityPolicy::MapToA,
+            AmbiguityPolicy::Randomize(7),
+        ] {
+            assert_eq!(
+                Kmer::from_bytes_with_policy(b"ACGTacgt", policy),
+                Ok(Kmer::from(b"ACGTacgt"))
+            );
+            assert_eq!(
+                Kmer::from_bytes_with_policy(&[b'A'; 33], policy),
+                Err(KmerError::TooLong { len: 33 })
+            );
+        }
+
+        for (code, _) in IUPAC {
+            for c in [code, code.to_ascii_lowercase()] {
+                let s = [b'C', b'G', c, b'T'];
+                assert_eq!(
+                    Kmer::from_bytes_with_policy(&s, AmbiguityPolicy::Reject),
+                    Err(KmerError::InvalidBase { pos: 2, byte: c })
+                );
+                assert_eq!(
+                    Kmer::from_bytes_with_policy(&s, AmbiguityPolicy::MapToA),
+                    Ok(Kmer::from(b"CGAT"))
+                );
+            }
+        }
+
+        // bytes that are not IUPAC codes are rejected even when randomizing
+        for c in [b'X', b'-', b'.', b'U', b'*'] {
+            let s = [b'N', b'A', c];
+            assert_eq!(
+                Kmer::from_bytes_with_policy(&s, AmbiguityPolicy::Randomize(1)),
+                Err(KmerError::InvalidBase { pos: 2, byte: c })
+            );
+            assert_eq!(
+                Kmer::from_bytes_with_policy(&s, AmbiguityPolicy::MapToA),
+                Ok(Kmer::from(b"AAA"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_randomize_iupac() {
+        for (code, compatible) in IUPAC {
+            for c in [code, code.to_ascii_lowercase()] {
+                let s = [c; 32];
+                let mut seen = [false; 4];
+                for seed in 0..20 {
+                    let policy = AmbiguityPolicy::Randomize(seed);
+                    let km = Kmer::from_bytes_with_policy(&s, policy).unwrap();
+                    // reproducible for a given seed
+                    assert_eq!(Kmer::from_bytes_with_policy(&s, policy), Ok(km.clone()));
+                    for b in km.to_bytes() {
+                        assert!(compatible.contains(&b), "{} from {}", b as char, c as char);
+                        seen[encode_binary_u8(b) as usize] = true;
+                    }
+                }
+                // every compatible base is drawn
+                for b in compatible {
+                    assert!(seen[encode_binary_u8(*b) as usize]);
+                }
+            }
+        }
+
+        // valid bases are kept as they are
+        let s = b"ACNNGTNNACGTNNNN";
+        let km = Kmer::from_bytes_with_policy(s, AmbiguityPolicy::Randomize(3)).unwrap();
+        for (b, c) in km.to_bytes().iter().zip(s) {
+            if *c != b'N' {
+                assert_eq!(b, c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_randomize_uniform() {
+        let s = [b'N'; 32];
+        let mut counts = [0usize; 4];
+        for seed in 0..1000 {
+            let km = Kmer::from_bytes_with_policy(&s, AmbiguityPolicy::Randomize(seed)).unwrap();
+            for b in km.to_bytes() {
+                counts[encode_binary_u8(b) as usize] += 1;
+            }
+        }
+        // 8000 expected per base
+        for count in counts {
+            assert!((7500..8500).contains(&count), "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn test_write_into() {
+        let mut buf = [b'-'; 40];
+        for s in ["", "a", "GATTACA", "ACGTACGTACGTACGTACGTACGTACGTACGT"] {
+            let km = Kmer::from(s);
+            assert_eq!(km.write_into(&mut buf), Ok(s.len()));
+            assert_eq!(
+                &buf[..s.len()],
+                String::from(km.clone()).to_uppercase().as_bytes()
+            );
+            assert_eq!(&buf[..s.len()], format!("{:#}", km).as_bytes());
+            // exactly the k-mer length is enough
+            assert_eq!(km.write_into(&mut buf[..s.len()]), Ok(s.len()));
+        }
+
+        let km = Kmer::from("GATTACA");
+        let mut buf = [b'-'; 6];
+        let e = km.write_into(&mut buf).unwrap_err();
+        assert_eq!(e, BufferTooSmall { needed: 7, len: 6 });
+        assert_eq!(e.to_string(), "buffer of length 6 cannot hold 7 bases");
+        assert_eq!(buf, [b'-'; 6]);
+    }
+
+    #[test]
+    fn test_encode_word_lossy() {
+        assert_eq!(
+            encode_word_lossy(b"ACNGTR"),
+            Kmer::from(b"ACAGTA").into_u64()
+        );
+        assert_eq!(encode_word_lossy(b""), 0);
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use super::hash::WordHasherState;
+use super::prelude::{encode_binary_u8, is_valid_nuc};
+use super::seq_vector::SeqVector;
+use super::{CanonicalKmer, CanonicalKmerIterator, Kmer, KmerError};
+use crate::utils::kmer_space;
+
+// Counts the canonical k-mers of a set of sequences. Counts are keyed by
+// canonical word, so a k-mer and its reverse complement share a count.
+// All sequences must be counted with the same `k`.
+#[derive(Clone, Debug)]
+pub struct KmerCounter<S = WordHasherState> {
+    k: Option<u8>,
+    counts: HashMap<u64, u64, S>,
+}
+
+impl KmerCounter {
+    pub fn new() -> Self {
+        Self::with_hasher(WordHasherState)
+    }
+}
+
+impl Default for KmerCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: BuildHasher> KmerCounter<S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            k: None,
+            counts: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    // the k-mer length, once something has been counted
+    pub fn k(&self) -> Option<u8> {
+        self.k
+    }
+
+    // number of distinct canonical k-mers
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    fn set_k(&mut self, k: u8) {
+        let prev = *self.k.get_or_insert(k);
+        assert_eq!(prev, k, "KmerCounter already holds {}-mers", prev);
+    }
+
+    // Count the k-mers of an ASCII sequence; k-mers overlapping a base
+    // other than `ACGTacgt` (e.g. `N`) are skipped.
+    pub fn count_seq(&mut self, seq: &[u8], k: u8) {
+        self.set_k(k);
+        for ckm in CanonicalKmerIterator::from_u8_slice(seq, k) {
+            *self.counts.entry(ckm.km.get_canonical_word()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn count_seqvec(&mut self, sv: &SeqVector, k: u8) {
+        self.set_k(k);
+        for ckm in sv.iter_canonical_kmers(k as usize) {
+            *self.counts.entry(ckm.get_canonical_word()).or_insert(0) += 1;
+        }
+    }
+
+    // Count of `km` or its reverse complement; 0 if it was never seen or
+    // is not of the counted length.
+    pub fn get(&self, km: &Kmer) -> u64 {
+        if self.k != Some(km.k) {
+            return 0;
+        }
+        let rc = km.to_reverse_complement();
+        let word = km.data.min(rc.data);
+        self.counts.get(&word).copied().unwrap_or(0)
+    }
+
+    // The counted canonical k-mers, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Kmer, u64)> + '_ {
+        let k = self.k.unwrap_or(0);
+        self.counts
+            .iter()
+            .map(move |(&word, &count)| (Kmer::from_u64(word, k), count))
+    }
+
+    // Add the counts of `other`, e.g. a counter filled by another thread.
+    pub fn merge(&mut self, other: Self) {
+        if let Some(k) = other.k {
+            self.set_k(k);
+        }
+        if self.counts.is_empty() {
+            self.counts = other.counts;
+            return;
+        }
+        for (word, count) in other.counts {
+            *self.counts.entry(word).or_insert(0) += count;
+        }
+    }
+}
+
+// The largest k a `DenseKmerCounter` accepts, its table has 4^k counts.
+pub const MAX_DENSE_K: u8 = 16;
+
+// A count stored in a `DenseKmerCounter`, saturating instead of overflowing.
+pub trait DenseCount: Copy + Default + Into<u32> {
+    fn saturating_add(self, other: Self) -> Self;
+    fn one() -> Self;
+}
+
+impl DenseCount for u16 {
+    fn saturating_add(self, other: Self) -> Self {
+        u16::saturating_add(self, other)
+    }
+
+    fn one() -> Self {
+        1
+    }
+}
+
+impl DenseCount for u32 {
+    fn saturating_add(self, other: Self) -> Self {
+        u32::saturating_add(self, other)
+    }
+
+    fn one() -> Self {
+        1
+    }
+}
+
+// Counts every k-mer of a set of sequences for a small k, in a table of
+// `kmer_space(k)` counts indexed by the k-mer word, without hashing. Counts
+// are `u32` by default, or `u16` to halve the memory; both saturate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DenseKmerCounter<C = u32> {
+    k: u8,
+    counts: Vec<C>,
+}
+
+impl<C: DenseCount> DenseKmerCounter<C> {
+    pub fn new(k: u8) -> Result<Self, KmerError> {
+        if k == 0 || k > MAX_DENSE_K {
+            return Err(KmerError::InvalidK {
+                k,
+                max: MAX_DENSE_K,
+            });
+        }
+        Ok(Self {
+            k,
+            counts: vec![C::default(); kmer_space(k as u32)],
+        })
+    }
+
+    pub fn k(&self) -> u8 {
+        self.k
+    }
+
+    // Count the k-mers of an ASCII sequence on its forward strand; k-mers
+    // overlapping a base other than `ACGTacgt` (e.g. `N`) are skipped.
+    pub fn count_seq(&mut self, seq: &[u8]) {
+        let mut km = Kmer::from_u64(0, self.k);
+        let mut valid = 0;
+        for &c in seq {
+            let b = encode_binary_u8(c);
+            if !is_valid_nuc(b) {
+                valid = 0;
+                continue;
+            }
+            km.append_base(b);
+            valid += 1;
+            if valid >= self.k as usize {
+                self.add(km.data);
+            }
+        }
+    }
+
+    // Same as `count_seq`, but a k-mer and its reverse complement share
+    // the count of the canonical one.
+    pub fn count_canonical_seq(&mut self, seq: &[u8]) {
+        let mut km = CanonicalKmer::from_u64(0, self.k);
+        let mut valid = 0;
+        for &c in seq {
+            let b = encode_binary_u8(c);
+            if !is_valid_nuc(b) {
+                valid = 0;
+                continue;
+            }
+            km.append_base(b);
+            valid += 1;
+            if valid >= self.k as usize {
+                self.add(km.get_canonical_word());
+            }
+        }
+    }
+
+    #[inline]
+    fn add(&mut self, word: u64) {
+        let count = &mut self.counts[word as usize];
+        *count = count.saturating_add(C::one());
+    }
+
+    // the count of `km`, 0 if it is not of the counted length
+    pub fn get(&self, km: &Kmer) -> u32 {
+        if km.k != self.k {
+            return 0;
+        }
+        self.counts[km.data as usize].into()
+    }
+
+    // The counted k-mers, in increasing order of their words.
+    pub fn iter_nonzero(&self) -> impl Iterator<Item = (Kmer, u32)> + '_ {
+        let k = self.k;
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(word, &count)| (word, count.into()))
+            .filter(|&(_, count)| count > 0)
+            .map(move |(word, count)| (Kmer::from_u64(word as u64, k), count))
+    }
+
+    // Add the counts of `other`, which must count k-mers of the same length.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.k, other.k,
+            "DenseKmerCounter of {}-mers can not merge {}-mers",
+            self.k, other.k
+        );
+        for (count, &more) in self.counts.iter_mut().zip(&other.counts) {
+            *count = count.saturating_add(more);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::hash_map::RandomState;
+
+    #[test]
+    fn count_known_seq() {
+        let seq = b"ACTTGNACTTGCANNCAAGT";
+        let mut counter = KmerCounter::new();
+        counter.count_seq(seq, 5);
+
+        // ACTTG twice on the forward strand, once as its twin CAAGT
+        assert_eq!(counter.get(&Kmer::from("acttg")), 3);
+        assert_eq!(counter.get(&Kmer::from("caagt")), 3);
+        // GCAAG only appears as its reverse complement CTTGC
+        assert!(!seq.windows(5).any(|w| w == b"GCAAG"));
+        assert_eq!(counter.get(&Kmer::from("gcaag")), 1);
+        assert_eq!(counter.get(&Kmer::from("cttgc")), 1);
+        // rc(TTGCA) = TGCAA
+        assert_eq!(counter.get(&Kmer::from("tgcaa")), 1);
+        assert_eq!(counter.get(&Kmer::from("aaaaa")), 0);
+        assert_eq!(counter.get(&Kmer::from("acttgc")), 0);
+
+        // ACTTG ACTTG CTTGC TTGCA CAAGT
+        assert_eq!(counter.len(), 3);
+        let total: u64 = counter.iter().map(|(_, n)| n).sum();
+        assert_eq!(total, 5);
+        for (km, n) in counter.iter() {
+            assert!(km.is_canonical());
+            assert_eq!(counter.get(&km), n);
+        }
+    }
+
+    #[test]
+    fn count_seqvec_matches_seq() {
+        let s = b"ACGTTGCAAGGCTTACAGATTTAGGACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+        let sv = SeqVector::from(s);
+
+        let mut a = KmerCounter::new();
+        a.count_seq(s, 7);
+        let mut b = KmerCounter::with_hasher(RandomState::new());
+        b.count_seqvec(&sv, 7);
+
+        assert_eq!(a.len(), b.len());
+        for (km, n) in a.iter() {
+            assert_eq!(b.get(&km), n);
+        }
+    }
+
+    #[test]
+    fn merge_counters() {
+        let mut a = KmerCounter::new();
+        a.count_seq(b"ACTTGCA", 5);
+        let mut b = KmerCounter::new();
+        b.count_seq(b"TGCAAGT", 5);
+
+        a.merge(b);
+        assert_eq!(a.get(&Kmer::from("acttg")), 2);
+        assert_eq!(a.get(&Kmer::from("ttgca")), 2);
+        assert_eq!(a.get(&Kmer::from("gcaag")), 2);
+
+        a.merge(KmerCounter::new());
+        assert_eq!(a.get(&Kmer::from("acttg")), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mixed_k() {
+        let mut counter = KmerCounter::new();
+        counter.count_seq(b"ACTTGCA", 5);
+        counter.count_seq(b"ACTTGCA", 4);
+    }
+
+    #[test]
+    fn dense_count_known_seq() {
+        let mut counter = DenseKmerCounter::<u32>::new(3).unwrap();
+        counter.count_seq(b"ACGTNACGACGNN");
+
+        // ACG CGT ACG CGA GAC ACG
+        assert_eq!(counter.get(&Kmer::from("acg")), 3);
+        assert_eq!(counter.get(&Kmer::from("cgt")), 1);
+        assert_eq!(counter.get(&Kmer::from("cga")), 1);
+        assert_eq!(counter.get(&Kmer::from("gac")), 1);
+        assert_eq!(counter.get(&Kmer::from("aaa")), 0);
+        assert_eq!(counter.get(&Kmer::from("acgt")), 0);
+
+        // in the order of the words, the first base is the lowest
+        let counted: Vec<(String, u32)> = counter
+            .iter_nonzero()
+            .map(|(km, n)| (km.to_string(), n))
+            .collect();
+        assert_eq!(
+            counted,
+            [
+                ("cga".to_string(), 1),
+                ("gac".to_string(), 1),
+                ("acg".to_string(), 3),
+                ("cgt".to_string(), 1)
+            ]
+        );
+    }
+
+    const SEQ: &[u8] = b"ACGTTGCAAGGCTTACAGATTTAGGNACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+
+    #[test]
+    fn dense_canonical_counts() {
+        for k in [1, 4, 5, 8] {
+            let mut fw = DenseKmerCounter::<u32>::new(k).unwrap();
+            fw.count_seq(SEQ);
+            let mut canonical = DenseKmerCounter::<u32>::new(k).unwrap();
+            canonical.count_canonical_seq(SEQ);
+
+            // the forward counts of a k-mer and its twin add up
+            let mut collapsed = DenseKmerCounter::<u32>::new(k).unwrap();
+            for (km, n) in fw.iter_nonzero() {
+                let word = km.to_canonical().data as usize;
+                collapsed.counts[word] += n;
+            }
+            assert_eq!(canonical, collapsed);
+
+            // the same as the hashing counter
+            let mut hashed = KmerCounter::new();
+            hashed.count_seq(SEQ, k);
+            assert_eq!(hashed.len(), canonical.iter_nonzero().count());
+            for (km, n) in canonical.iter_nonzero() {
+                assert!(km.is_canonical());
+                assert_eq!(hashed.get(&km), n as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn dense_merge() {
+        let k = 6;
+        let (a, b) = SEQ.split_at(45);
+        let mut merged = DenseKmerCounter::<u16>::new(k).unwrap();
+        merged.count_seq(a);
+        let mut other = DenseKmerCounter::new(k).unwrap();
+        other.count_seq(b);
+        merged.merge(&other);
+
+        // the k - 1 k-mers across the junction are in neither half
+        let mut junction = DenseKmerCounter::new(k).unwrap();
+        junction.count_seq(&SEQ[45 - (k as usize - 1)..45 + k as usize - 1]);
+        let n: u32 = junction.iter_nonzero().map(|(_, n)| n).sum();
+        assert_eq!(n, k as u32 - 1);
+        merged.merge(&junction);
+
+        let mut whole = DenseKmerCounter::new(k).unwrap();
+        whole.count_seq(SEQ);
+        assert_eq!(merged, whole);
+    }
+
+    #[test]
+    fn dense_saturates() {
+        let mut counter = DenseKmerCounter::<u16>::new(1).unwrap();
+        let seq = vec![b'A'; 70000];
+        counter.count_seq(&seq);
+        assert_eq!(counter.get(&Kmer::from("a")), u16::MAX as u32);
+        counter.merge(&counter.clone());
+        assert_eq!(counter.get(&Kmer::from("a")), u16::MAX as u32);
+    }
+
+    #[test]
+    fn dense_invalid_k() {
+        assert_eq!(
+            DenseKmerCounter::<u32>::new(17),
+            Err(KmerError::InvalidK { k: 17, max: 16 })
+        );
+        assert_eq!(
+            DenseKmerCounter::<u16>::new(0),
+            Err(KmerError::InvalidK { k: 0, max: 16 })
+        );
+        assert_eq!(
+            DenseKmerCounter::<u32>::new(17).unwrap_err().to_string(),
+            "k must be between 1 and 16, got 17"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn dense_merge_mixed_k() {
+        let mut a = DenseKmerCounter::<u32>::new(3).unwrap();
+        a.merge(&DenseKmerCounter::new(4).unwrap());
+    }
+}
@@ -0,0 +1,209 @@
+// The edges of a de Bruijn graph induced by a sequence: every pair of
+// consecutive valid k-mers, i.e. every (k+1)-mer without an invalid base,
+// joins the canonical forms of its two k-mers. The orientation flags tell
+// whether each k-mer is read on the sequence as its canonical form, so that
+// graph builders can tell which sides of the nodes the edge links.
+
+use super::canonical_kmer_iterator::CanonicalKmerPos;
+use super::seq_vector::{SeqVecCanonicalKmerIterator, SeqVectorSlice};
+use super::{CanonicalKmer, CanonicalKmerIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    // the canonical words of the source and target k-mers
+    pub from: u64,
+    pub to: u64,
+    // whether the k-mers appear on the sequence as their canonical form,
+    // always true for palindromic k-mers
+    pub from_fw: bool,
+    pub to_fw: bool,
+    // the position of the source k-mer on the sequence
+    pub pos: usize,
+}
+
+// a k-mer on the sequence, its canonical word and orientation
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    word: u64,
+    fw: bool,
+    pos: usize,
+}
+
+impl Node {
+    fn new(km: &CanonicalKmer, pos: usize) -> Self {
+        let word = km.get_canonical_word();
+        Self {
+            word,
+            fw: km.get_fw_mer().data == word,
+            pos,
+        }
+    }
+}
+
+// Positioned canonical k-mers, as produced by the underlying iterators
+pub trait PositionedKmer {
+    fn node(&self) -> (usize, &CanonicalKmer);
+}
+
+impl PositionedKmer for CanonicalKmerPos {
+    fn node(&self) -> (usize, &CanonicalKmer) {
+        (self.pos as usize, &self.km)
+    }
+}
+
+impl PositionedKmer for (usize, CanonicalKmer) {
+    fn node(&self) -> (usize, &CanonicalKmer) {
+        (self.0, &self.1)
+    }
+}
+
+// Iterates over the edges between consecutive k-mers of a sequence; no edge
+// spans an invalid base, e.g. an `N`, so a sequence holding `n` valid k-mers
+// in `s` runs of consecutive k-mers yields `n - s` edges.
+pub struct EdgeIterator<I> {
+    kmers: I,
+    prev: Option<Node>,
+}
+
+impl<'a> EdgeIterator<CanonicalKmerIterator<'a>> {
+    pub fn from_u8_slice(seq: &'a [u8], k: u8) -> Self {
+        Self {
+            kmers: CanonicalKmerIterator::from_u8_slice(seq, k),
+            prev: None,
+        }
+    }
+}
+
+impl<'a> EdgeIterator<std::iter::Enumerate<SeqVecCanonicalKmerIterator<'a>>> {
+    pub fn from_seq_vector(seq: SeqVectorSlice<'a>, k: usize) -> Self {
+        Self {
+            kmers: SeqVecCanonicalKmerIterator::new(seq, k).enumerate(),
+            prev: None,
+        }
+    }
+}
+
+impl<I> Iterator for EdgeIterator<I>
+where
+    I: Iterator,
+    I::Item: PositionedKmer,
+{
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.kmers.next()?;
+            let (pos, km) = item.node();
+            let node = Node::new(km, pos);
+            let prev = self.prev.replace(node);
+
+            match prev {
+                Some(prev) if prev.pos + 1 == pos => {
+                    return Some(Edge {
+                        from: prev.word,
+                        to: node.word,
+                        from_fw: prev.fw,
+                        to_fw: node.fw,
+                        pos: prev.pos,
+                    });
+                }
+                // first k-mer, or first k-mer after an invalid base
+                _ => continue,
+            }
+        }
+    }
+}
+
+impl<I> std::iter::FusedIterator for EdgeIterator<I>
+where
+    I: std::iter::FusedIterator,
+    I::Item: PositionedKmer,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::naive_impl::seq_vector::SeqVector;
+    use crate::naive_impl::Kmer;
+
+    fn word(s: &str) -> u64 {
+        Kmer::from(s).data
+    }
+
+    #[test]
+    fn palindromic_kmer() {
+        // TACG is read reverse complemented, ACGT is its own reverse
+        // complement and CGTT is read reverse complemented
+        let edges: Vec<Edge> = EdgeIterator::from_u8_slice(b"TACGTT", 4).collect();
+        assert_eq!(
+            edges,
+            vec![
+                Edge {
+                    from: word("CGTA"),
+                    to: word("ACGT"),
+                    from_fw: false,
+                    to_fw: true,
+                    pos: 0,
+                },
+                Edge {
+                    from: word("ACGT"),
+                    to: word("AACG"),
+                    from_fw: true,
+                    to_fw: false,
+                    pos: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_edge_across_n() {
+        let edges: Vec<Edge> = EdgeIterator::from_u8_slice(b"ACGTNacgta", 3).collect();
+        let pos: Vec<usize> = edges.iter().map(|e| e.pos).collect();
+        assert_eq!(pos, vec![0, 5, 6]);
+        assert_eq!(
+            edges[0],
+            Edge {
+                from: word("ACG"),
+                to: word("ACG"),
+                from_fw: true,
+                to_fw: false,
+                pos: 0,
+            }
+        );
+        assert_eq!(edges[1].from, edges[0].from);
+
+        assert_eq!(EdgeIterator::from_u8_slice(b"ACGNTTT", 4).count(), 0);
+        assert_eq!(EdgeIterator::from_u8_slice(b"", 4).count(), 0);
+    }
+
+    #[test]
+    fn edge_count() {
+        let seq = b"NNACGTTGCANGGTACCANNNATTTGCNCGTGCATTGCATAN";
+        for k in 1..8 {
+            let runs: Vec<usize> = seq
+                .split(|&c| c == b'N')
+                .filter(|run| run.len() >= k)
+                .map(|run| run.len() + 1 - k)
+                .collect();
+            let n_kmers: usize = runs.iter().sum();
+            assert_eq!(
+                EdgeIterator::from_u8_slice(seq, k as u8).count(),
+                n_kmers - runs.len()
+            );
+        }
+    }
+
+    #[test]
+    fn seq_vector_edges() {
+        let seq = b"ACGTTGCATTAGGCTACCGATACGTAGGCGCGATATACGTT";
+        let sv = SeqVector::from(&seq[..]);
+        for k in [1, 4, 7, 15, 32] {
+            let expected: Vec<Edge> = EdgeIterator::from_u8_slice(seq, k as u8).collect();
+            let edges: Vec<Edge> = EdgeIterator::from_seq_vector(sv.as_slice(), k).collect();
+            assert_eq!(edges, expected);
+            assert_eq!(edges.len(), (seq.len() + 1).saturating_sub(k + 1));
+        }
+    }
+}
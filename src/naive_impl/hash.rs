@@ -1,4 +1,7 @@
+use super::prelude::{encode_binary_u8, is_valid_nuc};
+use super::seq_vector::SeqVectorSlice;
 use super::Kmer;
+use serde::{Deserialize, Serialize};
 use std::hash::{BuildHasher, Hash, Hasher};
 
 impl Hash for Kmer {
@@ -7,23 +10,169 @@ impl Hash for Kmer {
     }
 }
 
+// `BuildHasher::hash_one`, which is only stable since Rust 1.71
 pub fn hash_one<H, T>(state: &H, x: T) -> u64
 where
     H: BuildHasher,
     T: Hash,
-    H: Sized,
-    H::Hasher: Hasher,
 {
     let mut hasher = state.build_hasher();
     x.hash(&mut hasher);
     hasher.finish()
 }
 
+// wyhash's constants
+const P0: u64 = 0xa076_1d64_78bd_642f;
+const P1: u64 = 0xe703_7ed1_a0b4_28db;
+const P2: u64 = 0x8ebc_6af0_9c88_c6e3;
+const P3: u64 = 0x5899_65cc_7537_4cc3;
+
+// the high and low words of the 128 bits product, xor-ed together
+#[inline]
+fn mum(a: u64, b: u64) -> u64 {
+    let r = a as u128 * b as u128;
+    (r as u64) ^ ((r >> 64) as u64)
+}
+
+// A fast hasher that only depends on its seed, unlike `RandomState`, so
+// that minimizers, and the indexes built on them, are the same from one run
+// to the next. Words are mixed in with wyhash's multiply-xor step, and, as
+// in wyhash, the seed goes into the accumulator rather than the multipliers,
+// so that no seed zeroes them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeededState(pub u64);
+
+impl BuildHasher for SeededState {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        SeededHasher {
+            seed: self.0,
+            acc: mum(self.0 ^ P0, P1),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SeededHasher {
+    seed: u64,
+    acc: u64,
+}
+
+impl Hasher for SeededHasher {
+    #[inline]
+    fn write_u64(&mut self, x: u64) {
+        self.acc = mum(self.acc ^ x ^ P1, P2);
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.write_u64(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut buf = [0u8; 8];
+            buf[..rest.len()].copy_from_slice(rest);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
+        // tell apart inputs that only differ by trailing zeros
+        self.acc ^= bytes.len() as u64;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        mum(self.acc ^ self.seed ^ P3, P0 ^ P3)
+    }
+}
+
+// murmur3's 64 bits finalizer and the inverses of its multipliers
+const FMIX_M1: u64 = 0xff51_afd7_ed55_8ccd;
+const FMIX_M2: u64 = 0xc4ce_b9fe_1a85_ec53;
+const FMIX_M1_INV: u64 = 0x4f74_430c_22a5_4005;
+const FMIX_M2_INV: u64 = 0x9cb4_b2f8_1293_37db;
+
+// murmur3's finalizer, a bijection on u64 that mixes every input bit into
+// every output bit
+#[inline]
+pub fn mix(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(FMIX_M1);
+    x ^= x >> 33;
+    x = x.wrapping_mul(FMIX_M2);
+    x ^ x >> 33
+}
+
+// The inverse of `mix`, e.g. to get back the k-mer word from a hash stored
+// by `MixHasherState`. A shift by 33 bits is its own inverse.
+#[inline]
+pub fn unhash(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(FMIX_M2_INV);
+    h ^= h >> 33;
+    h = h.wrapping_mul(FMIX_M1_INV);
+    h ^ h >> 33
+}
+
+// A deterministic hasher with no seed: a single word, e.g. a `Kmer` or an
+// l-mer word, hashes to `mix(word)`, which `unhash` inverts. Minimizers and
+// indexes built on it agree across runs and machines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MixHasherState;
+
+impl BuildHasher for MixHasherState {
+    type Hasher = MixHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        MixHasher { h: 0 }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MixHasher {
+    h: u64,
+}
+
+impl Hasher for MixHasher {
+    #[inline]
+    fn write_u64(&mut self, x: u64) {
+        self.h = mix(self.h ^ x);
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.write_u64(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut buf = [0u8; 8];
+            buf[..rest.len()].copy_from_slice(rest);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
+        // tell apart inputs that only differ by trailing zeros
+        self.h = mix(self.h ^ P0 ^ bytes.len() as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.h
+    }
+}
+
+// Order-preserving "hash" of k-mers: the hash of a k-mer word is its rank
+// in lexicographic order, so minimizers computed with it are the
+// lexicographically smallest l-mers (build it with `k` = l-mer length).
 #[derive(Clone, Debug, PartialEq)]
 pub struct LexHasherState(usize);
 
 impl LexHasherState {
     pub fn new(k: usize) -> Self {
+        assert!(
+            k > 0 && k <= 32,
+            "LexHasher supports 1 <= k <= 32, got {}",
+            k
+        );
         Self(k)
     }
 }
@@ -35,8 +184,9 @@ impl BuildHasher for LexHasherState {
     }
 }
 
-// TODO impl with debug assertions with #[cfg(debug_assertions)]
-// like https://github.com/paritytech/nohash-hasher/.../lib.rs#L106
+// Only a single `write_u64` of a k-mer word is order preserving. Anything
+// else (e.g. a tuple containing a `Kmer`) is still hashed, by folding each
+// word into the state, but the result carries no ordering.
 pub struct LexHasher {
     state: u64,
     k: usize,
@@ -44,13 +194,24 @@ pub struct LexHasher {
 
 impl LexHasher {
     pub fn new(k: usize) -> Self {
+        assert!(
+            k > 0 && k <= 32,
+            "LexHasher supports 1 <= k <= 32, got {}",
+            k
+        );
         Self { k, state: 0 }
     }
 }
 
 impl Hasher for LexHasher {
-    fn write(&mut self, _: &[u8]) {
-        unimplemented!("Hash with write_u64");
+    // bytes are read as little-endian words, so writing the 8 bytes of a
+    // word is the same as `write_u64`
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut buf = [0u8; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
     }
 
     fn finish(&self) -> u64 {
@@ -67,13 +228,471 @@ impl Hasher for LexHasher {
         res = (res >> 32 & 0x00000000FFFFFFFF) | (res & 0x00000000FFFFFFFF) << 32;
 
         res >>= (32 - self.k) * 2;
-        self.state = res;
+        // the first word is hashed as is
+        self.state = self.state.rotate_left(2 * self.k as u32) ^ res;
+    }
+}
+
+// A cheap hasher for maps keyed by k-mer words: the words are already
+// well spread integers, so the splitmix64 finalizer is enough mixing.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WordHasherState;
+
+impl BuildHasher for WordHasherState {
+    type Hasher = WordHasher;
+    fn build_hasher(&self) -> Self::Hasher {
+        WordHasher::default()
+    }
+}
+
+#[derive(Default)]
+pub struct WordHasher {
+    state: u64,
+}
+
+// the next value of a splitmix64 generator
+#[inline]
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl Hasher for WordHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut buf = [0u8; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write_u64(&mut self, word: u64) {
+        let mut z = self.state ^ word;
+        self.state = splitmix64(&mut z);
+    }
+}
+
+// ntHash (Mohamadi et al. 2016): the hash of a k-mer is the xor of the
+// per-base seeds below, each rotated left by its distance to the end
+// of the k-mer, so sliding the k-mer by one base is O(1).
+const NT_SEEDS: [u64; 4] = [
+    0x3c8bfbb395c60474, // A
+    0x3193c18562a02b4c, // C
+    0x20323ed082572324, // G
+    0x295549f54be24456, // T
+];
+
+#[inline]
+fn nt_seed(base: u64) -> u64 {
+    NT_SEEDS[base as usize]
+}
+
+#[inline]
+fn nt_seed_rc(base: u64) -> u64 {
+    NT_SEEDS[3 - base as usize]
+}
+
+// forward ntHash of the k-mer `word`
+#[inline]
+fn nt_hash_word(word: u64, k: usize) -> u64 {
+    (0..k).fold(0, |h, i| {
+        h ^ nt_seed((word >> (2 * i)) & 3).rotate_left((k - 1 - i) as u32)
+    })
+}
+
+// ntHash of the reverse complement of the k-mer `word`
+#[inline]
+fn nt_hash_word_rc(word: u64, k: usize) -> u64 {
+    (0..k).fold(0, |h, i| {
+        h ^ nt_seed_rc((word >> (2 * i)) & 3).rotate_left(i as u32)
+    })
+}
+
+// The k-mers hashed through an `NtHashState` must all have length `k`.
+// Hashing a k-mer from scratch is O(k); `NtHashIterator` gives the same
+// values in O(1) per k-mer, and the minimizer iterators built with
+// `with_nthash` use it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NtHashState(usize);
+
+impl NtHashState {
+    pub fn new(k: usize) -> Self {
+        assert!(k <= 32);
+        Self(k)
+    }
+
+    pub fn k(&self) -> usize {
+        self.0
+    }
+}
+
+impl BuildHasher for NtHashState {
+    type Hasher = NtHasher;
+    fn build_hasher(&self) -> Self::Hasher {
+        NtHasher::new(self.0)
+    }
+}
+
+pub struct NtHasher {
+    state: u64,
+    k: usize,
+}
+
+impl NtHasher {
+    pub fn new(k: usize) -> Self {
+        Self { k, state: 0 }
+    }
+}
+
+impl NtHasher {
+    // folds the ntHash of the `k`-mer `word` into the state, as the k-mer
+    // following those already written
+    #[inline]
+    fn fold(&mut self, word: u64, k: usize) {
+        self.state = self.state.rotate_left(k as u32) ^ nt_hash_word(word, k);
+    }
+}
+
+// Only a single `write_u64` of a k-mer word gives its ntHash. Further
+// words are folded in as the k-mers following it, and anything else (e.g. a
+// `str`) is read as little-endian words of 32 bases, so that no bit is lost.
+impl Hasher for NtHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut buf = [0u8; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.fold(u64::from_le_bytes(buf), 32);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write_u64(&mut self, word: u64) {
+        self.fold(word, self.k);
+    }
+}
+
+// Rolling ntHash over all k-mers of a sequence. Yields the forward hash
+// of each k-mer, or min(forward, reverse complement) for `new_canonical`,
+// equal to hashing each k-mer (or its canonical k-mer) with `NtHashState`.
+pub struct NtHashIterator<'a> {
+    seq: SeqVectorSlice<'a>,
+    k: usize,
+    pos: usize,
+    fw_hash: u64,
+    rc_hash: u64,
+    canonical: bool,
+}
+
+impl<'a> NtHashIterator<'a> {
+    pub fn new(seq: &SeqVectorSlice<'a>, k: usize) -> Self {
+        assert!(k > 0 && k <= 32);
+        Self {
+            seq: seq.clone(),
+            k,
+            pos: 0,
+            fw_hash: 0,
+            rc_hash: 0,
+            canonical: false,
+        }
+    }
+
+    pub fn new_canonical(seq: &SeqVectorSlice<'a>, k: usize) -> Self {
+        Self {
+            canonical: true,
+            ..Self::new(seq, k)
+        }
+    }
+
+    // the hashes of the next k-mer and of its reverse complement
+    #[inline]
+    pub(crate) fn next_pair(&mut self) -> Option<(u64, u64)> {
+        if self.pos + self.k > self.seq.len() {
+            return None;
+        }
+
+        if self.pos == 0 {
+            let word = self.seq.get_kmer_u64(0, self.k);
+            self.fw_hash = nt_hash_word(word, self.k);
+            self.rc_hash = nt_hash_word_rc(word, self.k);
+        } else {
+            let k = self.k as u32;
+            let out = self.seq.get_base(self.pos - 1);
+            let inc = self.seq.get_base(self.pos + self.k - 1);
+            self.fw_hash = self.fw_hash.rotate_left(1) ^ nt_seed(out).rotate_left(k) ^ nt_seed(inc);
+            self.rc_hash = self.rc_hash.rotate_right(1)
+                ^ nt_seed_rc(out).rotate_right(1)
+                ^ nt_seed_rc(inc).rotate_left(k - 1);
+        }
+
+        self.pos += 1;
+        Some((self.fw_hash, self.rc_hash))
+    }
+}
+
+impl Iterator for NtHashIterator<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (fw, rc) = self.next_pair()?;
+        if self.canonical {
+            Some(fw.min(rc))
+        } else {
+            Some(fw)
+        }
+    }
+}
+
+// The forward and reverse complement words of a k-mer, rolled one base at
+// a time. Both are only meaningful once `k` bases have been appended.
+#[derive(Clone, Copy, Debug)]
+struct RollingWords {
+    fw: u64,
+    rc: u64,
+    k: usize,
+    mask: u64,
+}
+
+impl RollingWords {
+    fn new(k: usize) -> Self {
+        assert!(k > 0 && k <= 32);
+        Self {
+            fw: 0,
+            rc: 0,
+            k,
+            mask: u64::MAX >> (64 - 2 * k),
+        }
+    }
+
+    #[inline]
+    fn append_base(&mut self, b: u64) {
+        self.fw = (self.fw >> 2) | (b << (2 * self.k - 2));
+        self.rc = ((self.rc << 2) | (3 - b)) & self.mask;
+    }
+
+    #[inline]
+    fn canonical_word(&self) -> u64 {
+        self.fw.min(self.rc)
+    }
+}
+
+// Hashes of the canonical words of all k-mers of a slice, i.e.
+// `hash_one(state, km.get_canonical_word())` for every `CanonicalKmer`
+// of `SeqVectorSlice::iter_canonical_kmers`, without building the k-mers.
+pub struct SeqVecCanonicalHashIterator<'a, T> {
+    seq: SeqVectorSlice<'a>,
+    words: RollingWords,
+    // the next base to append
+    pos: usize,
+    state: T,
+}
+
+impl<'a, T: BuildHasher> SeqVecCanonicalHashIterator<'a, T> {
+    pub fn new(seq: SeqVectorSlice<'a>, k: usize, state: T) -> Self {
+        Self {
+            seq,
+            words: RollingWords::new(k),
+            pos: 0,
+            state,
+        }
+    }
+}
+
+impl<T: BuildHasher> Iterator for SeqVecCanonicalHashIterator<'_, T> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.seq.len() {
+            self.words.append_base(self.seq.get_base(self.pos));
+            self.pos += 1;
+            if self.pos >= self.words.k {
+                return Some(hash_one(&self.state, self.words.canonical_word()));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let start = self.pos.max(self.words.k - 1);
+        let len = self.seq.len().saturating_sub(start);
+        (len, Some(len))
+    }
+}
+
+impl<T: BuildHasher> ExactSizeIterator for SeqVecCanonicalHashIterator<'_, T> {}
+
+impl<T: BuildHasher> std::iter::FusedIterator for SeqVecCanonicalHashIterator<'_, T> {}
+
+// Same as `SeqVecCanonicalHashIterator` on ASCII bases. Like
+// `CanonicalKmerIterator`, the k-mers containing a base other than
+// `ACGTacgt` are skipped.
+pub struct CanonicalHashIterator<'a, T> {
+    seq: &'a [u8],
+    words: RollingWords,
+    pos: usize,
+    // the number of valid bases ending at `pos - 1`
+    run: usize,
+    state: T,
+}
+
+impl<'a, T: BuildHasher> CanonicalHashIterator<'a, T> {
+    pub fn from_u8_slice(seq: &'a [u8], k: usize, state: T) -> Self {
+        Self {
+            seq,
+            words: RollingWords::new(k),
+            pos: 0,
+            run: 0,
+            state,
+        }
     }
 }
 
+impl<T: BuildHasher> Iterator for CanonicalHashIterator<'_, T> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.seq.len() {
+            let b = encode_binary_u8(self.seq[self.pos]);
+            self.pos += 1;
+            if !is_valid_nuc(b) {
+                self.run = 0;
+                continue;
+            }
+            self.words.append_base(b);
+            self.run += 1;
+            if self.run >= self.words.k {
+                return Some(hash_one(&self.state, self.words.canonical_word()));
+            }
+        }
+        None
+    }
+}
+
+impl<T: BuildHasher> std::iter::FusedIterator for CanonicalHashIterator<'_, T> {}
+
 #[cfg(test)]
 mod test {
+    use super::super::seq_vector::SeqVector;
     use super::*;
+    use crate::test_utils::{random_seq, random_seq_with_ns};
+
+    #[test]
+    fn seeded_state() {
+        let km = Kmer::from("ACGTTGCA");
+        let h = hash_one(&SeededState(1), &km);
+        assert_eq!(h, hash_one(&SeededState(1), &km));
+        assert_ne!(h, hash_one(&SeededState(2), &km));
+        assert_ne!(h, hash_one(&SeededState(1), Kmer::from("ACGTTGCT")));
+
+        // a `Kmer` hashes as its word
+        let mut hasher = SeededState(1).build_hasher();
+        hasher.write_u64(km.into_u64());
+        assert_eq!(hasher.finish(), h);
+
+        // the bytes that do not fill a word still count
+        let state = SeededState::default();
+        assert_ne!(
+            hash_one(&state, &b"ACGTTGCAA"[..]),
+            hash_one(&state, &b"ACGTTGCA"[..])
+        );
+        assert_ne!(hash_one(&state, &b"\0"[..]), hash_one(&state, &b""[..]));
+
+        // about half of the bits flip when the input changes by one bit
+        let flips: u32 = (0..64)
+            .map(|i| (hash_one(&state, 0u64) ^ hash_one(&state, 1u64 << i)).count_ones())
+            .sum();
+        let mean = flips as f64 / 64.0;
+        assert!((mean - 32.0).abs() < 4.0, "{}", mean);
+    }
+
+    #[test]
+    fn seeded_state_any_seed() {
+        // seeds that would cancel out a constant multiplier
+        for seed in [0, u64::MAX, P0, P1, P2, P3, P0 ^ P3, P1 ^ P2] {
+            let state = SeededState(seed);
+            let mut hashes: Vec<u64> = (0..10_000u64).map(|x| hash_one(&state, x)).collect();
+            hashes.sort_unstable();
+            hashes.dedup();
+            assert_eq!(hashes.len(), 10_000, "seed {:#x}", seed);
+        }
+    }
+
+    #[quickcheck]
+    fn mix_hasher_invertible(word: u64) -> bool {
+        let h = hash_one(&MixHasherState, word);
+        h == mix(word) && unhash(h) == word && mix(unhash(word)) == word
+    }
+
+    #[test]
+    fn mix_hasher_state() {
+        let km = Kmer::from("ACGTTGCA");
+        let h = hash_one(&MixHasherState, &km);
+        assert_eq!(unhash(h), km.into_u64());
+        assert_eq!(Kmer::from_u64(unhash(h), 8), km);
+        // murmur3's fmix64
+        assert_eq!(mix(1), 0xb456_bcfc_34c2_cb2c);
+
+        let state = MixHasherState;
+        assert_ne!(
+            hash_one(&state, &b"ACGTTGCAA"[..]),
+            hash_one(&state, &b"ACGTTGCA"[..])
+        );
+        assert_ne!(hash_one(&state, &b"\0"[..]), hash_one(&state, &b""[..]));
+
+        let flips: u32 = (0..64)
+            .map(|i| {
+                (mix(0x9E37_79B9_7F4A_7C15) ^ mix(0x9E37_79B9_7F4A_7C15 ^ 1 << i)).count_ones()
+            })
+            .sum();
+        let mean = flips as f64 / 64.0;
+        assert!((mean - 32.0).abs() < 4.0, "{}", mean);
+    }
+
+    #[test]
+    fn mix_hasher_density() {
+        let seq = random_seq(200_000, 5);
+        let sv = SeqVector::from(seq.as_slice());
+        for (k, l) in [(31, 15), (21, 11), (15, 5)] {
+            let w = k - l + 1;
+            let mut positions: Vec<usize> = sv
+                .iter_minimizers(k, l, MixHasherState)
+                .map(|mmer| mmer.pos)
+                .collect();
+            let n_kmers = positions.len();
+            positions.dedup();
+            let density = positions.len() as f64 / n_kmers as f64;
+            let expected = 2.0 / (w + 1) as f64;
+            assert!(
+                (density - expected).abs() < 0.1 * expected,
+                "w = {w}: density {density}, expected {expected}"
+            );
+
+            // the other minimizer APIs take it as well
+            let mmers: Vec<_> = sv.iter_canonical_minimizers(k, l, MixHasherState).collect();
+            assert_eq!(mmers.len(), n_kmers);
+            let (mm, pos) = sv.get_kmer(0, k).minimizer(l, &MixHasherState);
+            assert_eq!(mm.into_u64(), sv.get_kmer_u64(pos, l));
+        }
+    }
+
+    #[test]
+    fn hash_one_is_build_hasher_hash() {
+        let state = SeededState(3);
+        let mut hasher = state.build_hasher();
+        42u64.hash(&mut hasher);
+        assert_eq!(hash_one(&state, 42u64), hasher.finish());
+    }
 
     #[test]
     fn new() {
@@ -102,4 +721,140 @@ mod test {
         assert_eq!(caa, 0b010000);
         assert_eq!(cac, 0b010001);
     }
+
+    #[test]
+    fn lex_order_k32() {
+        let seed = LexHasherState::new(32);
+        let a = hash_one(&seed, Kmer::from(vec![b'a'; 32].as_slice()));
+        let c = hash_one(
+            &seed,
+            Kmer::from([[b'a'; 31].as_slice(), b"c"].concat().as_slice()),
+        );
+        let t = hash_one(&seed, Kmer::from(vec![b't'; 32].as_slice()));
+        assert_eq!((a, c, t), (0, 1, u64::MAX));
+    }
+
+    #[test]
+    #[should_panic]
+    fn lex_k_too_large() {
+        LexHasherState::new(33);
+    }
+
+    #[test]
+    fn lex_write() {
+        let seed = LexHasherState::new(5);
+        let km = Kmer::from(b"acgtt");
+
+        // hashing the bytes of a word is the same as hashing the word
+        let mut h = seed.build_hasher();
+        h.write(&km.into_u64().to_le_bytes());
+        assert_eq!(h.finish(), hash_one(&seed, &km));
+
+        // and composite keys no longer panic
+        let h1 = hash_one(&seed, (&km, 7usize));
+        let h2 = hash_one(&seed, (&km, 8usize));
+        assert_ne!(h1, h2);
+        hash_one(&seed, [km.clone(), km].as_slice());
+    }
+
+    #[test]
+    fn nthash_rc() {
+        let seed = NtHashState::new(5);
+        let km = Kmer::from(b"acggt");
+        let rc = km.to_reverse_complement();
+        assert_eq!(nt_hash_word_rc(km.into_u64(), 5), hash_one(&seed, &rc));
+        assert_ne!(hash_one(&seed, &km), hash_one(&seed, &rc));
+    }
+
+    #[test]
+    fn nthash_write() {
+        let state = NtHashState::new(4);
+        // hashing a pair of 4-mers is hashing the 8-mer they make up
+        let (a, b) = (Kmer::from("ACGG"), Kmer::from("TTAC"));
+        assert_eq!(
+            hash_one(&state, (&a, &b)),
+            hash_one(&NtHashState::new(8), Kmer::from("ACGGTTAC"))
+        );
+
+        // byte strings no longer panic, and are told apart
+        assert_eq!(hash_one(&state, "ACGT"), hash_one(&state, "ACGT"));
+        assert_ne!(hash_one(&state, "ACGT"), hash_one(&state, "ACGA"));
+        assert_ne!(
+            hash_one(&state, b"ACGTACGTA".as_slice()),
+            hash_one(&state, b"ACGTACGTC".as_slice())
+        );
+    }
+
+    #[test]
+    fn nthash_rolling() {
+        let s = b"ACGTTGCAAGGCTTACAGATTTAGGACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+        let sv = SeqVector::from(s);
+
+        for k in 1..32 {
+            let seed = NtHashState::new(k);
+            let kmers: Vec<Kmer> = sv.iter_kmers(k).collect();
+
+            let fw: Vec<u64> = kmers.iter().map(|km| hash_one(&seed, km)).collect();
+            let rolled: Vec<u64> = NtHashIterator::new(&sv.as_slice(), k).collect();
+            assert_eq!(rolled, fw);
+
+            let canon: Vec<u64> = kmers
+                .iter()
+                .map(|km| {
+                    let rc = km.to_reverse_complement();
+                    hash_one(&seed, km).min(hash_one(&seed, rc))
+                })
+                .collect();
+            let rolled: Vec<u64> = NtHashIterator::new_canonical(&sv.as_slice(), k).collect();
+            assert_eq!(rolled, canon);
+
+            // slices start rolling from their own first k-mer
+            let rolled: Vec<u64> = NtHashIterator::new(&sv.slice(3, s.len()), k).collect();
+            assert_eq!(rolled, fw[3..]);
+        }
+    }
+
+    #[test]
+    fn nthash_short_seq() {
+        let sv = SeqVector::from(b"ACG");
+        assert_eq!(NtHashIterator::new(&sv.as_slice(), 4).count(), 0);
+        assert_eq!(NtHashIterator::new(&sv.as_slice(), 3).count(), 1);
+    }
+
+    #[test]
+    fn canonical_hashes() {
+        use super::super::CanonicalKmerIterator;
+        use std::collections::hash_map::RandomState;
+
+        let state = RandomState::new();
+        for (len, seed) in [(0, 1), (10, 2), (1000, 3), (5000, 4)] {
+            let seq = random_seq_with_ns(len, seed);
+            for k in [1, 5, 21, 31, 32] {
+                let expected: Vec<u64> = CanonicalKmerIterator::from_u8_slice(&seq, k as u8)
+                    .map(|ckp| hash_one(&state, ckp.km.get_canonical_word()))
+                    .collect();
+                let hashes: Vec<u64> =
+                    CanonicalHashIterator::from_u8_slice(&seq, k, state.clone()).collect();
+                assert_eq!(hashes, expected);
+            }
+
+            // without the Ns, on a SeqVector
+            let seq: Vec<u8> = seq
+                .into_iter()
+                .filter(|c| !c.eq_ignore_ascii_case(&b'N'))
+                .collect();
+            let sv = SeqVector::from(seq.as_slice());
+            for k in [1, 5, 21, 31, 32] {
+                for slice in [sv.as_slice(), sv.slice(sv.len() / 3, sv.len())] {
+                    let expected: Vec<u64> = slice
+                        .iter_canonical_kmers(k)
+                        .map(|km| hash_one(&state, km.get_canonical_word()))
+                        .collect();
+                    let hashes = slice.iter_canonical_hashes(k, state.clone());
+                    assert_eq!(hashes.len(), expected.len());
+                    assert_eq!(hashes.collect::<Vec<u64>>(), expected);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,184 @@
+// Homopolymer-compressed (HPC) k-mers: runs of identical bases are
+// collapsed to a single base before k-mers are extracted, e.g. the
+// HPC 3-mers of `AAACCGTT` are `ACG` and `CGT`.
+
+use std::collections::VecDeque;
+
+use super::prelude::*;
+use super::{CanonicalKmer, Kmer};
+
+// the end (exclusive) of the run of `seq[start]`
+#[inline]
+fn run_end(seq: &[u8], start: usize, b: Base) -> usize {
+    start
+        + seq[start..]
+            .iter()
+            .take_while(|&&c| encode_binary_u8(c) == b)
+            .count()
+}
+
+impl Kmer {
+    // The HPC k-mer of the first `k` runs of `s`, and the number of bases
+    // of `s` these runs span. Returns `None` if `s` has fewer than `k` runs
+    // before its end or its first base other than `ACGTacgt`.
+    pub fn from_bytes_hpc(s: &[u8], k: u8) -> Option<(Kmer, usize)> {
+        assert!(k > 0 && k <= 32, "k must be in 1..=32");
+        let mut km = Kmer::from_u64(0, k);
+        let mut pos = 0;
+
+        for _ in 0..k {
+            let b = encode_binary_u8(*s.get(pos)?);
+            if !is_valid_nuc(b) {
+                return None;
+            }
+            km.append_base(b);
+            pos = run_end(s, pos, b);
+        }
+
+        Some((km, pos))
+    }
+}
+
+// Iterates over the canonical HPC k-mers of a sequence, together with the
+// bases they span in the uncompressed sequence: `(km, raw_start, raw_end)`
+// where `seq[raw_start..raw_end]` compresses to `km` (on one strand).
+// Like `CanonicalKmerIterator`, bases other than `ACGTacgt` (e.g. `N`) break
+// the sequence and no k-mer spans them.
+pub struct HpcKmerIterator<'a> {
+    seq: &'a [u8],
+    pos: usize,
+    k: usize,
+    km: CanonicalKmer,
+    // raw starts of the runs in the current window
+    run_starts: VecDeque<usize>,
+}
+
+impl<'slice> HpcKmerIterator<'slice> {
+    pub fn from_u8_slice(s: &'slice [u8], k: u8) -> HpcKmerIterator {
+        Self {
+            seq: s,
+            pos: 0,
+            k: k as usize,
+            km: CanonicalKmer::blank_of_size(k),
+            run_starts: VecDeque::with_capacity(k as usize + 1),
+        }
+    }
+}
+
+impl Iterator for HpcKmerIterator<'_> {
+    type Item = (CanonicalKmer, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.seq.len() {
+            let start = self.pos;
+            let b = encode_binary_u8(self.seq[start]);
+
+            if !is_valid_nuc(b) {
+                // restart the window after the invalid base
+                self.run_starts.clear();
+                self.pos += 1;
+                continue;
+            }
+
+            self.pos = run_end(self.seq, start, b);
+            self.km.append_base(b);
+            self.run_starts.push_back(start);
+            if self.run_starts.len() > self.k {
+                self.run_starts.pop_front();
+            }
+
+            if self.run_starts.len() == self.k {
+                return Some((self.km.clone(), self.run_starts[0], self.pos));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hpc(s: &[u8]) -> String {
+        let mut out: Vec<u8> = s.to_ascii_lowercase();
+        out.dedup();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_from_bytes_hpc() {
+        let (km, n) = Kmer::from_bytes_hpc(b"AAACCGTT", 3).unwrap();
+        assert_eq!(km.to_string(), "acg");
+        assert_eq!(n, 6);
+
+        // the last run is consumed in full
+        let (km, n) = Kmer::from_bytes_hpc(b"AAACCGTT", 4).unwrap();
+        assert_eq!(km.to_string(), "acgt");
+        assert_eq!(n, 8);
+
+        assert_eq!(Kmer::from_bytes_hpc(b"AAACCGTT", 5), None);
+        assert_eq!(Kmer::from_bytes_hpc(b"AAACCNGTT", 3), None);
+        assert_eq!(Kmer::from_bytes_hpc(b"aaAcCg", 3).unwrap().1, 6);
+    }
+
+    #[test]
+    fn test_long_runs() {
+        let seq = b"AAAAAAAAAACCCCCCCCCCCCGTTTTTTTTTTTTTTTTA";
+        let kmers: Vec<(String, usize, usize)> = HpcKmerIterator::from_u8_slice(seq, 3)
+            .map(|(km, s, e)| (km.get_fw_mer().to_string(), s, e))
+            .collect();
+
+        assert_eq!(
+            kmers,
+            vec![
+                ("acg".to_string(), 0, 23),
+                ("cgt".to_string(), 10, 39),
+                ("gta".to_string(), 22, 40),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_runs_interrupted_by_n() {
+        // the N splits the run of As, which does not carry over
+        let seq = b"CAAANAAAGTNNCG";
+        let kmers: Vec<(String, usize, usize)> = HpcKmerIterator::from_u8_slice(seq, 2)
+            .map(|(km, s, e)| (km.get_fw_mer().to_string(), s, e))
+            .collect();
+
+        assert_eq!(
+            kmers,
+            vec![
+                ("ca".to_string(), 0, 4),
+                ("ag".to_string(), 5, 9),
+                ("gt".to_string(), 8, 10),
+                ("cg".to_string(), 12, 14),
+            ]
+        );
+
+        assert_eq!(HpcKmerIterator::from_u8_slice(b"AAAANCCCC", 2).count(), 0);
+    }
+
+    #[test]
+    fn test_raw_coordinates() {
+        let seq = b"ACCGTTTAGGNCATTTTGGACCCAGTAAATTGCNNGGCATTTACG";
+        for k in 1..8 {
+            for (km, start, end) in HpcKmerIterator::from_u8_slice(seq, k) {
+                let raw = &seq[start..end];
+
+                // the raw bases re-expand the k-mer...
+                assert_eq!(hpc(raw), km.get_fw_mer().to_string());
+                assert_eq!(
+                    Kmer::from_bytes_hpc(raw, k),
+                    Some((km.get_fw_mer(), raw.len()))
+                );
+                assert_eq!(km, CanonicalKmer::from(km.get_fw_mer()));
+
+                // ...and include the full runs at both ends
+                assert!(start == 0 || seq[start - 1] != seq[start]);
+                assert!(end == seq.len() || seq[end] != seq[end - 1]);
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::hash::BuildHasher;
 
+use super::minimizer_queue::check_window;
 use super::prelude::*;
+use super::seq_vector::minimizers::MappedMinimizer;
 use serde::{Deserialize, Serialize};
 
 #[derive(Eq, Default, Debug, Clone, Ord, PartialOrd)]
@@ -18,17 +20,30 @@ impl PartialEq for Kmer {
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
 pub enum Orientation {
     IsCanonical,
-    NotCanononical,
+    NotCanonical,
 }
 
-const BASE_TABLE: [char; 4] = ['a', 'c', 'g', 't'];
+impl Orientation {
+    // the former, misspelled name of `NotCanonical`, which still works in
+    // patterns
+    #[deprecated(note = "renamed to `Orientation::NotCanonical`")]
+    #[allow(non_upper_case_globals)]
+    pub const NotCanononical: Orientation = Orientation::NotCanonical;
+}
+
+pub(crate) const BASE_TABLE: [char; 4] = ['a', 'c', 'g', 't'];
 // const RC_TABLE: [u64; 4] = [0x11, 0x10, 0x01, 0x00];
 
 // A bitmask that masks out the topmost 64-pos bits
 // Taking the bitwise `&` with this bitmask will return
 // the number setting all bits above the `pos` bit to 0.
+// `1 << 64` would overflow, so the full mask is special cased.
 const fn bitmask(pos: u64) -> u64 {
-    (1 << pos) - 1
+    if pos >= 64 {
+        u64::MAX
+    } else {
+        (1 << pos) - 1
+    }
 }
 
 impl Kmer {
@@ -43,6 +58,11 @@ impl Kmer {
     }
 
     pub fn from_u64(data: u64, k: u8) -> Self {
+        assert!(
+            k <= 32,
+            "k-mers longer than 32 bases not supported, got k = {}",
+            k
+        );
         let data = data & MASK_TABLE[k as usize];
         Kmer { data, k }
     }
@@ -52,55 +72,112 @@ impl Kmer {
         self.data
     }
 
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; self.len()];
+        self.write_bytes(&mut buf);
+        buf
+    }
+
+    pub fn write_bytes(&self, buf: &mut [u8]) {
+        decode_word(self.data, self.k, buf)
+    }
+
+    pub fn to_string_upper(&self) -> String {
+        // the decoded bases are always valid ASCII
+        String::from_utf8(self.to_bytes()).unwrap()
+    }
+
     pub fn is_canonical(&self) -> bool {
         let rc = self.to_reverse_complement();
         *self <= rc
     }
 
     pub fn orientation(&self) -> Orientation {
-        if self.is_canonical() {
-            Orientation::IsCanonical
-        } else {
-            Orientation::NotCanononical
-        }
+        self.canonicalize().1
     }
 
     pub fn to_canonical(&self) -> Self {
-        if self.is_canonical() {
-            self.clone()
+        self.canonicalize().0
+    }
+
+    // the canonical k-mer and whether `self` was it, computing the reverse
+    // complement once
+    pub fn canonicalize(&self) -> (Self, Orientation) {
+        let rc = self.to_reverse_complement();
+        if *self <= rc {
+            (self.clone(), Orientation::IsCanonical)
         } else {
-            self.to_reverse_complement()
+            (rc, Orientation::NotCanonical)
         }
     }
 
     #[inline]
     pub fn prepend_base_u8(&mut self, c: u8) -> Base {
-        let r = (self.data >> (2 * self.k - 2)) & 0x03;
-        self.data = MASK_TABLE[self.k as usize] & ((self.data << 2) | encode_binary_u8(c));
-        r
+        let b = encode_binary_u8(c);
+        debug_assert!(b <= T, "cannot encode {:?} into 2 bit encoding", c as char);
+        self.prepend_base(b)
     }
 
     #[inline]
     pub fn append_base_u8(&mut self, c: u8) -> Base {
-        let r = self.data & 0x03;
-        self.data = (self.data >> 2) | (encode_binary_u8(c) << (2 * self.k - 2));
-        r
+        let b = encode_binary_u8(c);
+        debug_assert!(b <= T, "cannot encode {:?} into 2 bit encoding", c as char);
+        self.append_base(b)
     }
 
     #[inline]
     pub fn prepend_base(&mut self, c: Base) -> Base {
         let r = (self.data >> (2 * self.k - 2)) & 0x03;
-        self.data = MASK_TABLE[self.k as usize] & ((self.data << 2) | c);
+        self.data = Self::prepend_base_word(self.data, self.k as usize, c);
         r
     }
 
     #[inline]
     pub fn append_base(&mut self, c: Base) -> Base {
         let r = self.data & 0x03;
-        self.data = (self.data >> 2) | (c << (2 * self.k - 2));
+        self.data = Self::append_base_word(self.data, self.k as usize, c);
         r
     }
 
+    // The word of the k-mer `word` with its first base dropped and `b`
+    // appended, as `append_base` does on a `Kmer`
+    #[inline]
+    pub fn append_base_word(word: u64, k: usize, b: Base) -> u64 {
+        (word >> 2) | (b << (2 * k).saturating_sub(2))
+    }
+
+    // The word of the k-mer `word` with its last base dropped and `b`
+    // prepended, as `prepend_base` does on a `Kmer`
+    #[inline]
+    pub fn prepend_base_word(word: u64, k: usize, b: Base) -> u64 {
+        MASK_TABLE[k] & ((word << 2) | b)
+    }
+
+    // The smaller of the k-mer `word` and its reverse complement, the word
+    // of `to_canonical`
+    #[inline]
+    pub fn canonical_word(word: u64, k: usize) -> u64 {
+        word.min(Self::get_reverse_complement_word(word, k as u8))
+    }
+
+    // the k-mers following `self` in a de Bruijn graph, i.e. `self` minus its
+    // first base plus each of A, C, G, T appended, in that order
+    pub fn successors(&self) -> [Kmer; 4] {
+        let k = self.k as usize;
+        // `from_u64` masks the empty k-mer
+        [A, C, G, T].map(|b| Kmer::from_u64(Self::append_base_word(self.data, k, b), self.k))
+    }
+
+    // the k-mers preceding `self`, i.e. each of A, C, G, T prepended to
+    // `self` minus its last base, in that order
+    pub fn predecessors(&self) -> [Kmer; 4] {
+        let k = self.k as usize;
+        [A, C, G, T].map(|b| Kmer {
+            data: Self::prepend_base_word(self.data, k, b),
+            k: self.k,
+        })
+    }
+
     /*
          * for now, use ugly but fast version below
         pub fn to_reverse_complement(&self) -> Self {
@@ -129,8 +206,9 @@ impl Kmer {
         res = (res >> 16 & 0x0000FFFF0000FFFF) | (res & 0x0000FFFF0000FFFF) << 16;
         res = (res >> 32 & 0x00000000FFFFFFFF) | (res & 0x00000000FFFFFFFF) << 32;
 
+        // the empty k-mer would shift by 64
         Self {
-            data: res >> (2 * (32 - self.k)),
+            data: res.checked_shr(2 * (32 - self.k as u32)).unwrap_or(0),
             k: self.k,
         }
     }
@@ -143,7 +221,7 @@ impl Kmer {
         res = (res >> 16 & 0x0000FFFF0000FFFF) | (res & 0x0000FFFF0000FFFF) << 16;
         res = (res >> 32 & 0x00000000FFFFFFFF) | (res & 0x00000000FFFFFFFF) << 32;
 
-        res >> (2 * (32 - k))
+        res.checked_shr(2 * (32 - k as u32)).unwrap_or(0)
     }
 }
 
@@ -173,11 +251,13 @@ impl Kmer {
         width: usize,
         state: &T,
     ) -> (u64, usize) {
+        check_window(k, width);
         let mut min_mmer = Self::sub_kmer_word(word, k, 0, width);
-        let mut min_hash = u64::MAX;
+        let mut min_hash = super::hash::hash_one(state, min_mmer);
         let mut offset = 0;
 
-        for pos in 0..(k - width + 1) {
+        // the leftmost of equal minima wins
+        for pos in 1..(k - width + 1) {
             let mmer = Self::sub_kmer_word(word, k, pos, width);
             let hash = super::hash::hash_one(state, mmer);
 
@@ -190,6 +270,420 @@ impl Kmer {
 
         (min_mmer, offset)
     }
+
+    // Circular shift of the bases of the k-mer, so that the base at `n_bases`
+    // (mod k) comes first: rotating ACGTT left by 1 gives CGTTA.
+    pub fn rotate_left(&self, n_bases: usize) -> Self {
+        let k = self.k as usize;
+        if k == 0 || n_bases % k == 0 {
+            return self.clone();
+        }
+        // the first base is the lowest one, so a left rotation of the string
+        // shifts the word right
+        let n = n_bases % k;
+        let data = (self.data >> (2 * n)) | (self.data << (2 * (k - n)));
+        Kmer {
+            data: data & MASK_TABLE[k],
+            k: self.k,
+        }
+    }
+
+    // The inverse of `rotate_left`: rotating ACGTT right by 1 gives TACGT.
+    pub fn rotate_right(&self, n_bases: usize) -> Self {
+        let k = self.k as usize;
+        if k == 0 {
+            return self.clone();
+        }
+        self.rotate_left(k - n_bases % k)
+    }
+
+    // The rotation of the k-mer starting at its minimizer, as returned by
+    // `minimizer`, and the offset of the minimizer, i.e. the amount of the
+    // left rotation.
+    pub fn anchor_minimizer<T: BuildHasher>(&self, width: usize, state: &T) -> (Self, usize) {
+        let (_, o) = self.minimizer(width, state);
+        (self.rotate_left(o), o)
+    }
+
+    // The minimizer of every suffix of the k-mer that is at least `width`
+    // long: the i-th entry is the minimizer of the bases from i on, with its
+    // offset in the k-mer. The first entry is `minimizer_word`'s result.
+    pub fn minimizers_all<T: BuildHasher>(&self, width: usize, state: &T) -> Vec<(u64, usize)> {
+        let k = self.k as usize;
+        check_window(k, width);
+        let n_lmers = k - width + 1;
+        let mut mmers = vec![(0, 0); n_lmers];
+        let mut min_hash = u64::MAX;
+
+        // right to left, so that the leftmost of equal minima wins
+        for pos in (0..n_lmers).rev() {
+            let mmer = Self::sub_kmer_word(self.data, k, pos, width);
+            let hash = super::hash::hash_one(state, mmer);
+            if pos == n_lmers - 1 || hash <= min_hash {
+                min_hash = hash;
+                mmers[pos] = (mmer, pos);
+            } else {
+                mmers[pos] = mmers[pos + 1];
+            }
+        }
+
+        mmers
+    }
+
+    pub fn canonical_minimizer<T: BuildHasher>(
+        &self,
+        width: usize,
+        state: &T,
+    ) -> (Self, usize, bool) {
+        let (mm, o, is_fw) =
+            Self::canonical_minimizer_word(self.data, self.k as usize, width, state);
+        let mm = Kmer::from_u64(mm, width as u8);
+        (mm, o, is_fw)
+    }
+
+    // The canonical minimizer located on the k-mer as it is read, the same
+    // as `CanonicalMinimizerIter` yields for a sequence holding only this
+    // k-mer: `pos` is the offset in `self` and `is_fw()` whether the word
+    // is read on the strand of `self`. A non-canonical k-mer mirrors the
+    // offset and strand given by `canonical_minimizer`.
+    pub fn canonical_mapped_minimizer<T: BuildHasher>(
+        &self,
+        width: usize,
+        state: &T,
+    ) -> MappedMinimizer {
+        let k = self.k as usize;
+        let (mm, o, is_fw) = Self::canonical_minimizer_word(self.data, k, width, state);
+        if self.is_canonical() {
+            MappedMinimizer::new_stranded(mm, o, is_fw)
+        } else {
+            MappedMinimizer::new_stranded(mm, k - width - o, !is_fw)
+        }
+    }
+
+    /// Computes the canonical minimizer of the k-mer `word`, i.e. the
+    /// minimizer over the `width`-mers of both strands of its canonical
+    /// k-mer, so that a k-mer and its reverse complement always share it.
+    ///
+    /// Returns the minimizer word, its offset in the *canonical* k-mer,
+    /// and whether it was read from the forward strand of the canonical
+    /// k-mer (`true`) or from its reverse complement (`false`).
+    ///
+    /// Ties are broken as follows:
+    /// - between the forward and reverse complement `width`-mers at the
+    ///   same offset, the forward one wins (so palindromes are forward);
+    /// - between offsets, the leftmost one on the canonical k-mer wins,
+    ///   which is the rightmost one when reading the k-mer's reverse
+    ///   complement.
+    ///
+    /// ```
+    /// use kmers::naive_impl::hash::LexHasherState;
+    /// use kmers::naive_impl::Kmer;
+    ///
+    /// let state = LexHasherState::new(3);
+    /// // TAAATTTC is not canonical, its canonical k-mer is GAAATTTA
+    /// let km = Kmer::from("TAAATTTC");
+    /// let rc = km.to_reverse_complement();
+    /// assert!(!km.is_canonical());
+    ///
+    /// // the minimizer is the AAA at offset 1 of GAAATTTA: it is on the
+    /// // forward strand of the canonical k-mer even though it only appears
+    /// // as TTT (at offset 4) in the input, whichever way we read the k-mer.
+    /// let (mm, o, is_fw) = km.canonical_minimizer(3, &state);
+    /// assert_eq!((mm.to_string().as_str(), o, is_fw), ("aaa", 1, true));
+    /// assert_eq!(rc.canonical_minimizer(3, &state), (mm, o, is_fw));
+    /// ```
+    pub fn canonical_minimizer_word<T: BuildHasher>(
+        word: u64,
+        k: usize,
+        width: usize,
+        state: &T,
+    ) -> (u64, usize, bool) {
+        check_window(k, width);
+        let rc_word = Self::get_reverse_complement_word(word, k as u8);
+        let word = word.min(rc_word);
+
+        let mut min_mmer = 0;
+        let mut min_hash = u64::MAX;
+        let mut offset = 0;
+        let mut is_fw = true;
+
+        for pos in 0..(k - width + 1) {
+            let fw_mmer = Self::sub_kmer_word(word, k, pos, width);
+            let rc_mmer = Self::get_reverse_complement_word(fw_mmer, width as u8);
+            let fw_hash = super::hash::hash_one(state, fw_mmer);
+            let rc_hash = super::hash::hash_one(state, rc_mmer);
+
+            // forward wins ties at the same offset...
+            let (mmer, hash, fw) = if fw_hash <= rc_hash {
+                (fw_mmer, fw_hash, true)
+            } else {
+                (rc_mmer, rc_hash, false)
+            };
+
+            // ...and the leftmost offset wins ties between offsets
+            if pos == 0 || hash < min_hash {
+                min_mmer = mmer;
+                min_hash = hash;
+                offset = pos;
+                is_fw = fw;
+            }
+        }
+
+        (min_mmer, offset, is_fw)
+    }
+}
+
+// Hamming distance and neighborhoods, working on the packed words
+impl Kmer {
+    // number of positions at which `self` and `other` have different bases
+    pub fn hamming_distance(&self, other: &Kmer) -> u32 {
+        assert_eq!(self.k, other.k, "k-mers of different lengths");
+        Self::mismatch_word(self.data, other.data).count_ones()
+    }
+
+    // the offsets, in increasing order, at which `self` and `other` have
+    // different bases
+    pub fn mismatch_positions(&self, other: &Kmer) -> impl Iterator<Item = usize> {
+        assert_eq!(self.k, other.k, "k-mers of different lengths");
+        let mut x = Self::mismatch_word(self.data, other.data);
+        std::iter::from_fn(move || {
+            if x == 0 {
+                return None;
+            }
+            let pos = x.trailing_zeros() as usize / 2;
+            x &= x - 1;
+            Some(pos)
+        })
+    }
+
+    // one bit per base (the low bit of its pair), set if either bit of the
+    // base differs between `a` and `b`
+    #[inline]
+    fn mismatch_word(a: u64, b: u64) -> u64 {
+        let x = a ^ b;
+        (x | x >> 1) & 0x5555555555555555
+    }
+
+    // the 3k k-mers at distance 1, substituting each base in turn
+    pub fn hamming_neighbors(&self) -> impl Iterator<Item = Kmer> {
+        let (data, k) = (self.data, self.k);
+        (0..k as usize)
+            .flat_map(move |i| (1..=3u64).map(move |x| Kmer::from_u64(data ^ (x << (2 * i)), k)))
+    }
+
+    // the k-mers at distance 1 up to `d` (excluding `self`), for d <= 2
+    pub fn neighbors_within(&self, d: u8) -> impl Iterator<Item = Kmer> {
+        assert!(d <= 2, "neighborhoods only supported up to distance 2");
+        let (data, k) = (self.data, self.k);
+        let n1 = if d >= 1 { k as usize } else { 0 };
+        let n2 = if d >= 2 { k as usize } else { 0 };
+
+        // substitute 2 bases, at positions i < j
+        let distance_2 = (0..n2)
+            .flat_map(move |i| ((i + 1)..n2).map(move |j| (i, j)))
+            .flat_map(|(i, j)| (1..=3u64).flat_map(move |x| (1..=3u64).map(move |y| (i, j, x, y))))
+            .map(move |(i, j, x, y)| Kmer::from_u64(data ^ (x << (2 * i)) ^ (y << (2 * j)), k));
+
+        self.hamming_neighbors().take(3 * n1).chain(distance_2)
+    }
+}
+
+// Shannon entropy (in bits, so at most 2) of the base distribution
+// given by `counts`, 0 for an empty sequence
+pub(crate) fn entropy_of_counts(counts: &[u64; 4]) -> f64 {
+    let n: u64 = counts.iter().sum();
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / n as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// Base composition
+impl Kmer {
+    // The number of `A`, `C`, `G` and `T` among the `n` lowest order bases
+    // of `word`, from the popcounts of its low and high bit planes.
+    pub fn base_counts_word(word: u64, n: usize) -> [u32; 4] {
+        assert!(n <= 32);
+        let n_mask = if n == 32 {
+            u64::MAX
+        } else {
+            (1 << (2 * n)) - 1
+        };
+        let lo = word & n_mask & 0x5555555555555555;
+        let hi = (word & n_mask) >> 1 & 0x5555555555555555;
+
+        let t = (lo & hi).count_ones();
+        let c = (lo & !hi).count_ones();
+        let g = (hi & !lo).count_ones();
+        [n as u32 - c - g - t, c, g, t]
+    }
+
+    pub fn base_counts(&self) -> [u32; 4] {
+        Self::base_counts_word(self.data, self.len())
+    }
+
+    // the fraction of `C`s and `G`s, 0 for an empty k-mer
+    pub fn gc_content(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let [_, c, g, _] = self.base_counts();
+        (c + g) as f64 / self.len() as f64
+    }
+
+    pub fn shannon_entropy(&self) -> f64 {
+        entropy_of_counts(&self.base_counts().map(u64::from))
+    }
+
+    // the number of distinct dinucleotides (out of 16) among the k - 1
+    // overlapping ones, a cheaper complexity measure than the entropy
+    pub fn distinct_dimer_count(&self) -> u8 {
+        let seen = (0..self.len().saturating_sub(1))
+            .fold(0u16, |seen, i| seen | 1 << (self.data >> (2 * i) & 0xF));
+        seen.count_ones() as u8
+    }
+
+    // whether the entropy of the base distribution is below `threshold`
+    // (in bits, between 0 for a homopolymer and 2)
+    pub fn is_low_complexity(&self, threshold: f64) -> bool {
+        self.shannon_entropy() < threshold
+    }
+}
+
+// Repeats
+impl Kmer {
+    // whether base i equals base i + p for all i < k - p, i.e. the k-mer is
+    // made of copies of its first p bases, the last one maybe partial
+    pub fn is_repeat_of_period(&self, p: usize) -> bool {
+        assert!(p > 0, "period must be positive");
+        let k = self.len();
+        if p >= k {
+            return true;
+        }
+        let overlap_mask = (1 << (2 * (k - p))) - 1;
+        (self.data >> (2 * p)) ^ (self.data & overlap_mask) == 0
+    }
+
+    // the smallest p > 0 such that the k-mer is a repeat of period p, which
+    // is k for k-mers with no shorter period (and 0 for the empty k-mer)
+    pub fn smallest_period(&self) -> usize {
+        (1..self.len())
+            .find(|&p| self.is_repeat_of_period(p))
+            .unwrap_or(self.len())
+    }
+
+    pub fn is_homopolymer(&self) -> bool {
+        self.is_empty() || self.is_repeat_of_period(1)
+    }
+}
+
+// Ranks, i.e. dense integer indices of k-mers, e.g. to address count arrays.
+// Ranks preserve the order of the words.
+impl Kmer {
+    // The rank of the k-mer among all k-mers of length k, in
+    // `0..kmer_space(k)`: this is simply its word.
+    #[inline]
+    pub fn rank(&self) -> u64 {
+        self.data
+    }
+
+    // The canonical k-mer of rank `rank` among the canonical k-mers of
+    // length k, the inverse of `CanonicalKmer::canonical_rank`.
+    pub fn from_canonical_rank(rank: u64, k: u8) -> Self {
+        assert!(k <= 32);
+        let mut rank = rank as u128;
+        assert!(
+            rank < n_canonical_with_prefix(&[], k as usize),
+            "rank out of the canonical space"
+        );
+
+        // pick the bases from the most significant one down
+        let mut prefix = Vec::with_capacity(k as usize);
+        while prefix.len() < k as usize {
+            for b in 0..4 {
+                prefix.push(b);
+                let n = n_canonical_with_prefix(&prefix, k as usize);
+                if rank < n {
+                    break;
+                }
+                rank -= n;
+                prefix.pop();
+            }
+        }
+
+        let data = prefix.iter().fold(0, |w, &b| (w << 2) | b as u64);
+        Kmer { data, k }
+    }
+
+    // The rank of the canonical word `word` of length `k` among the
+    // canonical words, in `0..canonical_space(k)`.
+    pub fn canonical_rank_of_word(word: u64, k: u8) -> u64 {
+        let k = k as usize;
+        debug_assert!(word <= Kmer::get_reverse_complement_word(word, k as u8) || k == 0);
+
+        // count the canonical words smaller than `word`, by the first
+        // (most significant) base where they differ
+        let mut prefix = Vec::with_capacity(k);
+        let mut rank = 0;
+        for i in (0..k).rev() {
+            let b = ((word >> (2 * i)) & 3) as u8;
+            for smaller in 0..b {
+                prefix.push(smaller);
+                rank += n_canonical_with_prefix(&prefix, k);
+                prefix.pop();
+            }
+            prefix.push(b);
+        }
+        rank as u64
+    }
+}
+
+// The number of canonical words of length `k` whose most significant bases
+// are `prefix`. A word is canonical iff it is not greater than its reverse
+// complement, which is decided by the outermost pair of bases at mirrored
+// positions (most significant base `t` and least significant base `t`)
+// that do not sum to 3: the word is canonical iff they sum to less. If
+// every pair sums to 3, the word is its own reverse complement.
+fn n_canonical_with_prefix(prefix: &[u8], k: usize) -> u128 {
+    let fixed = |i: usize| prefix.get(i).map(|&b| b as usize);
+    let mut n_free = k - prefix.len();
+    // number of ways the pairs seen so far all sum to 3
+    let mut ways: u128 = 1;
+    let mut count: u128 = 0;
+
+    for t in 0..k / 2 {
+        let (n_less, n_eq) = match (fixed(t), fixed(k - 1 - t)) {
+            (Some(a), Some(b)) => ((a + b < 3) as u128, (a + b == 3) as u128),
+            (Some(a), None) => {
+                n_free -= 1;
+                (3 - a as u128, 1)
+            }
+            (None, _) => {
+                n_free -= 2;
+                (6, 4)
+            }
+        };
+        count += ways * n_less * (1 << (2 * n_free));
+        ways *= n_eq;
+        if ways == 0 {
+            return count;
+        }
+    }
+
+    if k % 2 == 1 {
+        // the middle base is compared to its own complement
+        let n_less = match fixed(k / 2) {
+            Some(a) => (a < 2) as u128,
+            None => 2,
+        };
+        count + ways * n_less
+    } else {
+        count + ways
+    }
 }
 
 // Converting to and from Kmers
@@ -256,16 +750,30 @@ impl From<&str> for Kmer {
     }
 }
 
+impl From<Kmer> for Vec<u8> {
+    fn from(kmer: Kmer) -> Self {
+        kmer.to_bytes()
+    }
+}
+
 impl From<Kmer> for u64 {
     fn from(kmer: Kmer) -> Self {
         kmer.data
     }
 }
 
+// `{}` emits lowercase bases, while the alternate form `{:#}` emits uppercase
 impl std::fmt::Display for Kmer {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let msg: String = self.clone().into();
-        write!(f, "{msg}")
+        // decoded on the stack rather than through a `String`
+        let mut buf = [0u8; 32];
+        let buf = &mut buf[..self.len()];
+        self.write_bytes(buf);
+        if !f.alternate() {
+            buf.make_ascii_lowercase();
+        }
+        // only ACGTacgt
+        f.write_str(std::str::from_utf8(buf).unwrap())
     }
 }
 
@@ -274,8 +782,11 @@ mod test {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
-    use super::super::hash::hash_one;
+    use super::super::hash::{hash_one, LexHasherState, SeededState};
+    use super::super::seq_vector::SeqVector;
+    use super::super::CanonicalKmer;
     use super::*;
+    use crate::test_utils::random_bases;
 
     #[quickcheck]
     fn rc_identity(word: u64) -> bool {
@@ -283,6 +794,370 @@ mod test {
         km == km.to_reverse_complement().to_reverse_complement()
     }
 
+    #[quickcheck]
+    fn canonicalize(word: u64, k: u8) -> bool {
+        let km = Kmer::from_u64(word, k % 33);
+        let (canon, o) = km.canonicalize();
+        canon == km.to_canonical()
+            && o == km.orientation()
+            && (o == Orientation::IsCanonical) == km.is_canonical()
+            && (canon == km || o == Orientation::NotCanonical)
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn misspelled_orientation() {
+        let km = Kmer::from("TTT");
+        assert_eq!(km.orientation(), Orientation::NotCanononical);
+        assert!(matches!(km.orientation(), Orientation::NotCanononical));
+    }
+
+    #[quickcheck]
+    fn from_u64_round_trip_k32(word: u64) -> bool {
+        let s: String = (0..32)
+            .map(|i| BASE_TABLE[((word >> (2 * i)) & 3) as usize])
+            .collect();
+        let km = Kmer::from(s.as_str());
+        Kmer::from_u64(km.into_u64(), 32) == km && km.into_u64() == word
+    }
+
+    #[test]
+    fn canonical_ranks() {
+        use crate::utils::canonical_space;
+
+        for k in 1..=7u8 {
+            let n = canonical_space(k as u32) as u64;
+            let mut next_rank = 0;
+            for word in 0..(1u64 << (2 * k)) {
+                let km = Kmer::from_u64(word, k);
+                assert_eq!(km.rank(), word);
+                if !km.is_canonical() {
+                    continue;
+                }
+                // canonical k-mers are ranked in increasing order of words
+                let rank = CanonicalKmer::from(km.clone()).canonical_rank();
+                assert_eq!(rank, next_rank);
+                assert_eq!(Kmer::from_canonical_rank(rank, k), km);
+                next_rank += 1;
+            }
+            assert_eq!(next_rank, n, "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn canonical_ranks_k32() {
+        let km = Kmer::from("acgtttgcaggatcacagtcgatgctagctat").to_canonical();
+        let rank = CanonicalKmer::from(km.clone()).canonical_rank();
+        assert_eq!(Kmer::from_canonical_rank(rank, 32), km);
+        let last = Kmer::from_canonical_rank((1 << 63) + (1 << 31) - 1, 32);
+        assert!(last.is_canonical());
+    }
+
+    #[test]
+    #[should_panic]
+    fn canonical_rank_out_of_space() {
+        Kmer::from_canonical_rank(32, 3);
+    }
+
+    #[test]
+    fn empty_kmer() {
+        let km = Kmer::from("");
+        assert_eq!(km.to_reverse_complement(), km);
+        assert_eq!(Kmer::get_reverse_complement_word(0, 0), 0);
+        assert!(km.is_canonical());
+    }
+
+    #[test]
+    fn full_width_kmers() {
+        let s = "acgtttgcaggatcacagtcgatgctagctat";
+        let km = Kmer::from(s);
+        assert_eq!(Kmer::from_u64(km.into_u64(), 32).to_string(), s);
+        assert_eq!(km.sub_kmer(0, 32), km);
+
+        let mut prepended = km.clone();
+        assert_eq!(prepended.prepend_base(G), T);
+        assert_eq!(prepended.to_string(), format!("g{}", &s[..31]));
+        assert_eq!(prepended.prepend_base_u8(b'c'), A);
+        assert_eq!(prepended.to_string(), format!("cg{}", &s[..30]));
+
+        let (mm, o) = km.minimizer(32, &LexHasherState::new(32));
+        assert_eq!((mm, o), (km.clone(), 0));
+    }
+
+    fn hamming_reference(a: &Kmer, b: &Kmer) -> u32 {
+        let (a, b) = (a.to_string(), b.to_string());
+        a.chars().zip(b.chars()).filter(|(x, y)| x != y).count() as u32
+    }
+
+    #[quickcheck]
+    fn hamming_distance(x: u64, y: u64) -> bool {
+        let (a, b) = (Kmer::from_u64(x, 31), Kmer::from_u64(y, 31));
+        a.hamming_distance(&b) == hamming_reference(&a, &b)
+            && b.hamming_distance(&a) == a.hamming_distance(&b)
+            && a.hamming_distance(&a) == 0
+    }
+
+    #[test]
+    #[should_panic]
+    fn hamming_distance_different_k() {
+        Kmer::from("acgt").hamming_distance(&Kmer::from("acg"));
+    }
+
+    #[quickcheck]
+    fn mismatch_positions(x: u64, y: u64) -> bool {
+        let (a, b) = (Kmer::from_u64(x, 32), Kmer::from_u64(y, 27));
+        let b = Kmer::from_u64(b.data << 10 | a.data & 0x3FF, 32);
+        let (sa, sb) = (a.to_string().into_bytes(), b.to_string().into_bytes());
+        let expected: Vec<usize> = (0..32).filter(|&i| sa[i] != sb[i]).collect();
+
+        let positions: Vec<usize> = a.mismatch_positions(&b).collect();
+        positions == expected && positions.len() as u32 == a.hamming_distance(&b)
+    }
+
+    #[test]
+    fn mismatch_positions_examples() {
+        let a = Kmer::from("GATTACA");
+        let positions: Vec<usize> = a.mismatch_positions(&Kmer::from("GACTACT")).collect();
+        assert_eq!(positions, [2, 6]);
+        assert_eq!(a.mismatch_positions(&a).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "k-mers of different lengths")]
+    fn mismatch_positions_different_k() {
+        Kmer::from("acgt")
+            .mismatch_positions(&Kmer::from("acg"))
+            .count();
+    }
+
+    #[quickcheck]
+    fn word_level_bases(word: u64, k: u8, b: u8) -> bool {
+        let k = k % 32 + 1;
+        let b = (b % 4) as Base;
+        let km = Kmer::from_u64(word, k);
+        let word = km.into_u64();
+        let k = k as usize;
+
+        let mut appended = km.clone();
+        appended.append_base(b);
+        let mut prepended = km.clone();
+        prepended.prepend_base(b);
+
+        Kmer::append_base_word(word, k, b) == appended.into_u64()
+            && Kmer::prepend_base_word(word, k, b) == prepended.into_u64()
+            && Kmer::canonical_word(word, k) == km.to_canonical().into_u64()
+            && km.successors()[b as usize] == appended
+            && km.predecessors()[b as usize] == prepended
+    }
+
+    #[test]
+    fn word_level_bases_all_k() {
+        for k in 1..=32 {
+            for word in [0, u64::MAX, 0x9E3779B97F4A7C15, 0x0123_4567_89AB_CDEF] {
+                let km = Kmer::from_u64(word, k);
+                let word = km.into_u64();
+                for b in [A, C, G, T] {
+                    let mut appended = km.clone();
+                    appended.append_base(b);
+                    assert_eq!(
+                        Kmer::append_base_word(word, k as usize, b),
+                        appended.into_u64()
+                    );
+                    // the words stay within k bases
+                    assert_eq!(Kmer::from_u64(appended.into_u64(), k), appended);
+
+                    let mut prepended = km.clone();
+                    prepended.prepend_base(b);
+                    assert_eq!(
+                        Kmer::prepend_base_word(word, k as usize, b),
+                        prepended.into_u64()
+                    );
+                }
+                let canonical = Kmer::canonical_word(word, k as usize);
+                assert_eq!(canonical, km.to_canonical().into_u64());
+                assert_eq!(Kmer::canonical_word(canonical, k as usize), canonical);
+            }
+        }
+    }
+
+    #[test]
+    fn successors_predecessors() {
+        let km = Kmer::from("ACGTT");
+        let succ: Vec<String> = km.successors().iter().map(|s| s.to_string()).collect();
+        assert_eq!(succ, ["cgtta", "cgttc", "cgttg", "cgttt"]);
+        let pred: Vec<String> = km.predecessors().iter().map(|p| p.to_string()).collect();
+        assert_eq!(pred, ["aacgt", "cacgt", "gacgt", "tacgt"]);
+        // not mutated
+        assert_eq!(km.to_string(), "acgtt");
+
+        for k in [1, 2, 31, 32] {
+            let km = Kmer::from_u64(0x9E3779B97F4A7C15, k);
+            for (b, succ) in km.successors().into_iter().enumerate() {
+                let mut expected = km.clone();
+                expected.append_base(b as u64);
+                assert_eq!(succ, expected);
+                assert_eq!(succ, Kmer::from_u64(succ.into_u64(), k));
+            }
+            for (b, pred) in km.predecessors().into_iter().enumerate() {
+                let mut expected = km.clone();
+                expected.prepend_base(b as u64);
+                assert_eq!(pred, expected);
+                // no stale base above k
+                assert_eq!(pred, Kmer::from_u64(pred.into_u64(), k));
+                assert!(pred.successors().contains(&km));
+            }
+        }
+        assert_eq!(Kmer::from("G").successors(), Kmer::from("G").predecessors());
+    }
+
+    #[test]
+    fn hamming_neighbors() {
+        for k in [1, 2, 5, 17, 31] {
+            let km = Kmer::from_u64(0x9E3779B97F4A7C15, k);
+            let k = k as usize;
+
+            let n1: Vec<Kmer> = km.hamming_neighbors().collect();
+            assert_eq!(n1.len(), 3 * k);
+            assert!(n1.iter().all(|n| hamming_reference(&km, n) == 1));
+            assert_eq!(km.neighbors_within(1).collect::<Vec<_>>(), n1);
+            assert_eq!(km.neighbors_within(0).count(), 0);
+
+            let n2: Vec<Kmer> = km.neighbors_within(2).collect();
+            assert_eq!(n2.len(), 3 * k + 9 * k * (k - 1) / 2);
+            assert!(n2.iter().all(|n| (1..=2).contains(&km.hamming_distance(n))));
+
+            let mut unique = n2.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), n2.len());
+        }
+    }
+
+    #[test]
+    fn base_counts() {
+        let mut seed = 0x2545F4914F6CDD1D_u64;
+        for k in 1..=32 {
+            for _ in 0..20 {
+                let s = String::from_utf8(random_bases(k, &mut seed, b"acgt")).unwrap();
+                let km = Kmer::from(s.as_str());
+
+                let expected = ['a', 'c', 'g', 't'].map(|b| s.matches(b).count() as u32);
+                assert_eq!(km.base_counts(), expected, "{s}");
+
+                let gc = (expected[1] + expected[2]) as f64 / k as f64;
+                assert!((km.gc_content() - gc).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn repeats() {
+        for k in [1, 2, 31, 32] {
+            for b in ["a", "c", "g", "t"] {
+                let km = Kmer::from(b.repeat(k).as_str());
+                assert!(km.is_homopolymer());
+                assert_eq!(km.smallest_period(), 1);
+            }
+        }
+        assert!(!Kmer::from("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaac").is_homopolymer());
+        assert!(!Kmer::from("caaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").is_homopolymer());
+
+        // period 2 and 3, with partial last copies
+        for k in 2..=32 {
+            let at: String = "at".repeat(16)[..k].to_string();
+            assert_eq!(Kmer::from(at.as_str()).smallest_period(), 2, "{at}");
+        }
+        for k in 3..=32 {
+            let cag: String = "cag".repeat(11)[..k].to_string();
+            let km = Kmer::from(cag.as_str());
+            assert_eq!(km.smallest_period(), 3, "{cag}");
+            assert!(km.is_repeat_of_period(6));
+            assert!(!km.is_repeat_of_period(2));
+            assert!(!km.is_homopolymer());
+        }
+        assert_eq!(Kmer::from("acgtacgta").smallest_period(), 4);
+        assert_eq!(Kmer::from("aacaa").smallest_period(), 3);
+        assert_eq!(Kmer::from("").smallest_period(), 0);
+
+        // a period p < k means base i == base i + p everywhere
+        let mut seed = 0x853C49E6748FEA9B_u64;
+        for k in 1..=32 {
+            for _ in 0..20 {
+                let s = random_bases(k, &mut seed, b"acgt");
+                let km = Kmer::from(s.as_slice());
+                let expected = (1..k)
+                    .find(|&p| (0..k - p).all(|i| s[i] == s[i + p]))
+                    .unwrap_or(k);
+                assert_eq!(km.smallest_period(), expected);
+            }
+        }
+        let km = Kmer::from("acgtgcatacgtgcaagtcctgatgacatggt");
+        assert_eq!(km.smallest_period(), 32);
+    }
+
+    #[test]
+    fn entropy() {
+        assert_eq!(Kmer::from("aaaaaaaa").shannon_entropy(), 0.0);
+        assert!((Kmer::from("acgtacgt").shannon_entropy() - 2.0).abs() < 1e-12);
+        assert!((Kmer::from("acacacac").shannon_entropy() - 1.0).abs() < 1e-12);
+        assert_eq!(Kmer::from("").shannon_entropy(), 0.0);
+        assert_eq!(Kmer::from("").gc_content(), 0.0);
+
+        assert!(Kmer::from("aaaaaaaaaaaaaaaaaaac").is_low_complexity(1.0));
+        assert!(!Kmer::from("acgttgcaagtcctga").is_low_complexity(1.0));
+    }
+
+    fn entropy_reference(s: &str) -> f64 {
+        let n = s.len() as f64;
+        b"ACGT"
+            .iter()
+            .map(|b| s.bytes().filter(|c| c.to_ascii_uppercase() == *b).count() as f64 / n)
+            .filter(|&p| p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum()
+    }
+
+    fn dimers_reference(s: &str) -> u8 {
+        let dimers: std::collections::HashSet<&[u8]> = s.as_bytes().windows(2).collect();
+        dimers.len() as u8
+    }
+
+    #[quickcheck]
+    fn complexity_matches_strings(x: u64, k: u8) -> bool {
+        let km = Kmer::from_u64(x, k % 32 + 1);
+        let s = km.to_string();
+        (km.shannon_entropy() - entropy_reference(&s)).abs() < 1e-12
+            && km.distinct_dimer_count() == dimers_reference(&s)
+    }
+
+    #[test]
+    fn complexity() {
+        for s in [
+            "A",
+            "TTTTTTTTTTTTTTTTTTTTTTTTTTTTTTTT",
+            "CACACACACACACACACACACACACACACACA",
+            "ATATATATATATATATATATA",
+            "GATTACAGATTACA",
+            "ACGTTGCAAGTCCTGAGGCATAACTTCGATCG",
+        ] {
+            let km = Kmer::from(s);
+            assert!((km.shannon_entropy() - entropy_reference(s)).abs() < 1e-12);
+            assert_eq!(km.distinct_dimer_count(), dimers_reference(s));
+        }
+        assert_eq!(Kmer::from("TTTTTTTT").distinct_dimer_count(), 1);
+        assert_eq!(Kmer::from("CACACACA").distinct_dimer_count(), 2);
+        assert_eq!(Kmer::from("").distinct_dimer_count(), 0);
+
+        // masking low complexity k-mers
+        let sv = crate::naive_impl::seq_vector::SeqVector::from(b"AAAAAAAAAAGATTACAGG");
+        let kept: Vec<usize> = sv
+            .iter_kmers_with_pos(6)
+            .filter(|(km, _)| km.shannon_entropy() > 1.5)
+            .map(|(_, pos)| pos)
+            .collect();
+        assert_eq!(kept, [10, 12, 13]);
+    }
+
     #[quickcheck]
     fn to_canonical_is_canonical(word: u64) -> bool {
         let km = Kmer::from_u64(word, 31);
@@ -430,6 +1305,58 @@ mod test {
         assert_eq!(seq, seq_);
     }
 
+    #[test]
+    fn upper_repr() {
+        let km = Kmer::from("catagatacat");
+        assert_eq!(km.to_string_upper(), "CATAGATACAT");
+        assert_eq!(format!("{km:#}"), format!("{km}").to_uppercase());
+
+        assert_eq!(Kmer::from(format!("{km:#}")), km);
+        assert_eq!(Kmer::from(format!("{km}")), km);
+    }
+
+    #[test]
+    fn bytes_repr() {
+        let seq = b"CATAGATACAT";
+        assert_eq!(Kmer::from(seq).to_bytes(), seq.to_vec());
+        assert_eq!(Kmer::from("catagatacat").to_bytes(), seq.to_vec());
+
+        let bytes: Vec<u8> = Kmer::from(seq).into();
+        assert_eq!(bytes, seq.to_vec());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let s = b"TTGACCATTTTTCCTGTTCTTCAAGAAAACAGG";
+        for k in 1..=32 {
+            for w in s.windows(k) {
+                assert_eq!(Kmer::from(w).to_bytes(), w);
+            }
+            // leading 'A's leave the top bits of the word zero
+            let a = "A".repeat(k);
+            assert_eq!(Kmer::from(a.as_bytes()).to_bytes(), a.as_bytes());
+        }
+    }
+
+    #[test]
+    fn write_bytes() {
+        let km = Kmer::from("acgtt");
+        let mut buf = [b'N'; 7];
+        km.write_bytes(&mut buf);
+        assert_eq!(&buf, b"ACGTTNN");
+
+        let mut buf = [0u8; 5];
+        decode_word(km.into_u64(), 5, &mut buf);
+        assert_eq!(&buf, b"ACGTT");
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_bytes_short_buffer() {
+        let mut buf = [0u8; 4];
+        Kmer::from("acgtt").write_bytes(&mut buf);
+    }
+
     #[test]
     fn bin_repr() {
         let aaa = 0b000000;
@@ -577,11 +1504,205 @@ mod test {
             assert_eq!(wmer, mm);
         }
     }
+
+    #[test]
+    fn minimizer_window_bounds() {
+        let state = SeededState(7);
+        for k in [1, 5, 32] {
+            let km = Kmer::from(&"ACTTGATCCGATTGCAGGCTAAGCTTACGTAC"[..k]);
+            assert_eq!(km.minimizer(k, &state), (km.clone(), 0));
+            assert_eq!(km.minimizers_all(k, &state), vec![(km.data, 0)]);
+
+            let (mm, o, is_fw) = km.canonical_minimizer(k, &state);
+            let canonical = km.to_canonical();
+            assert_eq!(o, 0);
+            if is_fw {
+                assert_eq!(mm, canonical);
+            } else {
+                assert_eq!(mm, canonical.to_reverse_complement());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "got k = 5 and w = 6")]
+    fn minimizer_window_longer_than_k() {
+        let state = SeededState(7);
+        Kmer::from("ACTTG").minimizer(6, &state);
+    }
+
+    #[test]
+    #[should_panic(expected = "got k = 5 and w = 0")]
+    fn canonical_minimizer_empty_window() {
+        let state = SeededState(7);
+        Kmer::from("ACTTG").canonical_minimizer(0, &state);
+    }
+
+    #[test]
+    fn rotations() {
+        let s = "ACTTGATCCGATTGCAGGCTAAGCTTACGTAC";
+        for k in [5, 31, 32] {
+            let s = &s[..k];
+            let km = Kmer::from(s);
+            assert_eq!(km.rotate_left(k), km);
+            assert_eq!(km.rotate_right(k), km);
+            assert_eq!(km.rotate_left(0), km);
+
+            for n in 0..2 * k {
+                let rotated = format!("{}{}", &s[n % k..], &s[..n % k]);
+                assert_eq!(format!("{:#}", km.rotate_left(n)), rotated);
+                assert_eq!(km.rotate_right(n).rotate_left(n), km);
+                assert_eq!(km.rotate_right(n), km.rotate_left(k - n % k));
+
+                for m in [1, 3, k - 1] {
+                    assert_eq!(
+                        km.rotate_left(n).rotate_left(m),
+                        km.rotate_left((n + m) % k)
+                    );
+                }
+            }
+        }
+
+        let km = Kmer::from("ACGTT");
+        assert_eq!(km.rotate_left(1), Kmer::from("CGTTA"));
+        assert_eq!(km.rotate_right(1), Kmer::from("TACGT"));
+    }
+
+    #[test]
+    fn anchor_minimizer() {
+        let state = LexHasherState::new(3);
+        // the lexicographic minimizer AAG is at offset 4
+        let km = Kmer::from("TCGTAAGC");
+        let (anchored, o) = km.anchor_minimizer(3, &state);
+        assert_eq!(o, 4);
+        assert_eq!(anchored, Kmer::from("AAGCTCGT"));
+        assert_eq!(anchored.rotate_right(o), km);
+
+        let seed = SeededState(7);
+        let km = Kmer::from("ACTTGATCCGATTGCAGGCTAAGCTTACGTA");
+        for w in [1, 7, 15, 31] {
+            let (mm, o) = km.minimizer(w, &seed);
+            let (anchored, r) = km.anchor_minimizer(w, &seed);
+            assert_eq!(r, o);
+            assert_eq!(anchored.sub_kmer(0, w), mm);
+        }
+    }
+
+    // leftmost l-mer of minimal hash in the bases of `km` from `from` on
+    fn brute_force_minimizer(
+        km: &Kmer,
+        from: usize,
+        w: usize,
+        state: &LexHasherState,
+    ) -> (u64, usize) {
+        let k = km.len();
+        (from..(k - w + 1))
+            .map(|o| (Kmer::sub_kmer_word(km.data, k, o, w), o))
+            .min_by_key(|&(mm, o)| (hash_one(state, mm), o))
+            .unwrap()
+    }
+
+    #[quickcheck]
+    fn minimizers_match_brute_force(word: u64) -> bool {
+        [(31, 15), (31, 1), (31, 31), (15, 4), (8, 3)]
+            .into_iter()
+            .all(|(k, w)| {
+                let km = Kmer::from_u64(word, k as u8);
+                let state = LexHasherState::new(w);
+                let all = km.minimizers_all(w, &state);
+
+                Kmer::minimizer_word(km.data, k, w, &state)
+                    == brute_force_minimizer(&km, 0, w, &state)
+                    && all.len() == k - w + 1
+                    && all
+                        .iter()
+                        .enumerate()
+                        .all(|(i, &mm)| mm == brute_force_minimizer(&km, i, w, &state))
+            })
+    }
+
+    #[test]
+    fn test_minimizers_all() {
+        // every 3-mer ties with the others but the last
+        let km = Kmer::from("AAAAAC");
+        let state = LexHasherState::new(3);
+        assert_eq!(
+            km.minimizers_all(3, &state),
+            vec![(0, 0), (0, 1), (0, 2), (0b010000, 3)]
+        );
+        assert_eq!(Kmer::minimizer_word(km.data, 6, 3, &state), (0, 0));
+    }
+
+    // a k-mer agrees with the canonical minimizer iterator over its own sequence
+    #[quickcheck]
+    fn canonical_mapped_minimizer(x: u64, k: u8, w: u8, seed: u64) -> bool {
+        let km = Kmer::from_u64(x, k % 32 + 1);
+        let w = w as usize % km.len() + 1;
+        let state = SeededState(seed);
+        let sv = SeqVector::from(km.to_string().as_bytes());
+        let mut mmers = sv.iter_canonical_minimizers(km.len(), w, state);
+
+        let mm = km.canonical_mapped_minimizer(w, &state);
+        let (word, o, is_fw) = km.canonical_minimizer(w, &state);
+        mmers.next() == Some(mm.clone())
+            && mmers.next().is_none()
+            && mm.as_u64() == word.into_u64()
+            && (km.is_canonical() || mm.pos == km.len() - w - o)
+            && mm.is_fw() == (is_fw == km.is_canonical())
+    }
+
+    #[test]
+    fn test_canonical_minimizer() {
+        let seqs = [
+            "TAAATTTC",
+            "ACTTGATTTCAAGT",
+            "AAAAAAATTTTTTT",
+            "CACACACCACGTGTGGTG",
+            "GATCGGATCCGATCAAGCTTGAATTC",
+        ];
+
+        for s in seqs {
+            let sv = SeqVector::from(s.as_bytes());
+            for k in 1..=s.len() {
+                for w in 1..=k {
+                    let state = LexHasherState::new(w);
+                    let mmers = sv.iter_canonical_minimizers(k, w, state.clone());
+
+                    for (i, mmer) in mmers.enumerate() {
+                        let km = sv.get_kmer(i, k);
+                        let (mm, o, is_fw) = km.canonical_minimizer(w, &state);
+
+                        // the same minimizer whichever strand the k-mer is read from
+                        let rc = km.to_reverse_complement();
+                        assert_eq!(rc.canonical_minimizer(w, &state), (mm.clone(), o, is_fw));
+
+                        // offsets are relative to the canonical k-mer
+                        let canon = km.to_canonical();
+                        let on_canon = canon.sub_kmer(o, w);
+                        if is_fw {
+                            assert_eq!(mm, on_canon);
+                        } else {
+                            assert_eq!(mm, on_canon.to_reverse_complement());
+                        }
+
+                        // and the iterator reports positions in the sequence
+                        let pos = if km.is_canonical() {
+                            i + o
+                        } else {
+                            i + k - w - o
+                        };
+                        assert_eq!(mmer.as_u64(), mm.into_u64(), "{s} {k} {w} {i}");
+                        assert_eq!(mmer.pos, pos, "{s} {k} {w} {i}");
+                    }
+                }
+            }
+        }
+    }
 }
 
 // table that contains bit patterns to mask out the top bits of a word.
 // Mask_Table[k] is a word such that lowest k*2 order bits are 1s, and 0 otherwise.
-const MASK_TABLE: [u64; 33] = [
+pub(crate) const MASK_TABLE: [u64; 33] = [
     bitmask(0),
     bitmask(2),
     bitmask(4),
@@ -614,5 +1735,5 @@ const MASK_TABLE: [u64; 33] = [
     bitmask(58),
     bitmask(60),
     bitmask(62),
-    0,
+    bitmask(64),
 ];
@@ -0,0 +1,595 @@
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use super::kmer::{Orientation, BASE_TABLE, MASK_TABLE};
+use super::minimizer_queue::minimizers_of_word_seq;
+use super::prelude::*;
+use super::Kmer;
+
+// A k-mer for 1 <= k <= 64 stored 2 bits per base in a single u128, so that
+// the bit tricks of `Kmer` carry over as is. As with `Kmer`, the sequence is
+// read "left to right" from "lower to higher" order bits.
+#[derive(Eq, Default, Debug, Clone, Ord, PartialOrd)]
+pub struct Kmer128 {
+    pub k: u8,
+    pub(crate) data: u128,
+}
+
+const MAX_K128: u8 = 64;
+
+impl PartialEq for Kmer128 {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.k == other.k
+    }
+}
+
+impl Hash for Kmer128 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.data);
+    }
+}
+
+impl Kmer128 {
+    pub fn len(&self) -> usize {
+        self.k as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.k == 0
+    }
+
+    pub fn from_u128(data: u128, k: u8) -> Self {
+        assert!(
+            k <= MAX_K128,
+            "kmers longer than {MAX_K128} bases not supported"
+        );
+        let data = data & MASK_TABLE_128[k as usize];
+        Kmer128 { data, k }
+    }
+
+    // `lo` holds bases 0..32 and `hi` bases 32..64
+    pub fn from_u64_pair(lo: u64, hi: u64, k: u8) -> Self {
+        Self::from_u128((hi as u128) << 64 | lo as u128, k)
+    }
+
+    pub fn into_u128(&self) -> u128 {
+        self.data
+    }
+
+    #[inline]
+    pub fn get_base(&self, pos: usize) -> Base {
+        assert!(pos < self.len());
+        (self.data >> (2 * pos)) as u64 & 0x03
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        let rc = self.to_reverse_complement();
+        *self <= rc
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.is_canonical() {
+            Orientation::IsCanonical
+        } else {
+            Orientation::NotCanonical
+        }
+    }
+
+    pub fn to_canonical(&self) -> Self {
+        if self.is_canonical() {
+            self.clone()
+        } else {
+            self.to_reverse_complement()
+        }
+    }
+
+    #[inline]
+    pub fn prepend_base_u8(&mut self, c: u8) -> Base {
+        self.prepend_base(encode_binary_u8(c))
+    }
+
+    #[inline]
+    pub fn append_base_u8(&mut self, c: u8) -> Base {
+        self.append_base(encode_binary_u8(c))
+    }
+
+    #[inline]
+    pub fn prepend_base(&mut self, c: Base) -> Base {
+        let r = self.get_base(self.len() - 1);
+        self.data = MASK_TABLE_128[self.k as usize] & ((self.data << 2) | c as u128);
+        r
+    }
+
+    #[inline]
+    pub fn append_base(&mut self, c: Base) -> Base {
+        let r = self.data as u64 & 0x03;
+        self.data = (self.data >> 2) | (c as u128) << (2 * (self.k as usize - 1));
+        r
+    }
+
+    pub fn to_reverse_complement(&self) -> Self {
+        Self {
+            data: Self::get_reverse_complement_word(self.data, self.k),
+            k: self.k,
+        }
+    }
+
+    // The 128-bit version of `Kmer::get_reverse_complement_word`
+    pub fn get_reverse_complement_word(w: u128, k: u8) -> u128 {
+        let mut res = !w;
+        res = (res >> 2 & 0x33333333333333333333333333333333)
+            | (res & 0x33333333333333333333333333333333) << 2;
+        res = (res >> 4 & 0x0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F)
+            | (res & 0x0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F0F) << 4;
+        res = (res >> 8 & 0x00FF00FF00FF00FF00FF00FF00FF00FF)
+            | (res & 0x00FF00FF00FF00FF00FF00FF00FF00FF) << 8;
+        res = (res >> 16 & 0x0000FFFF0000FFFF0000FFFF0000FFFF)
+            | (res & 0x0000FFFF0000FFFF0000FFFF0000FFFF) << 16;
+        res = (res >> 32 & 0x00000000FFFFFFFF00000000FFFFFFFF)
+            | (res & 0x00000000FFFFFFFF00000000FFFFFFFF) << 32;
+        // swap the two 64-bit halves
+        res = res.rotate_left(64);
+
+        res.checked_shr(2 * (MAX_K128 - k) as u32).unwrap_or(0)
+    }
+
+    pub fn sub_kmer(&self, pos: usize, width: usize) -> Self {
+        assert!(pos < self.len());
+        assert!(pos + width <= self.len());
+
+        let w = self.data >> (pos * 2); // shift out low order bits
+        Self::from_u128(w, width as u8) // mask out high order bits
+    }
+
+    // same as `Kmer::minimizer`, the minimizer and its offset in the k-mer;
+    // the minimizer is at most 32 bases long, so it is a `Kmer`
+    pub fn minimizer<T: BuildHasher>(&self, width: usize, state: &T) -> (Kmer, usize) {
+        let k = self.len();
+        assert!(
+            (1..=k).contains(&width) && width <= 32,
+            "minimizers need 1 <= w <= k and w <= 32, got k = {} and w = {}",
+            k,
+            width
+        );
+        let lmers =
+            (0..(k - width + 1)).map(|pos| (self.data >> (2 * pos)) as u64 & MASK_TABLE[width]);
+        let (mm, o) = minimizers_of_word_seq(lmers, k, width, state)
+            .next()
+            .unwrap();
+        (Kmer::from_u64(mm, width as u8), o)
+    }
+}
+
+// Converting to and from Kmer128s
+impl From<Kmer128> for String {
+    fn from(kmer: Kmer128) -> Self {
+        (0..kmer.len())
+            .map(|i| BASE_TABLE[kmer.get_base(i) as usize])
+            .collect()
+    }
+}
+
+impl From<&[u8]> for Kmer128 {
+    fn from(s: &[u8]) -> Self {
+        if s.len() > MAX_K128 as usize {
+            panic!("kmers longer than {MAX_K128} bases not supported");
+        }
+
+        let k = s.len() as u8;
+
+        let mut w = 0_u128;
+        // read sequence "left to right" from "lower to higher" order bits
+        for c in s.iter().rev() {
+            w <<= 2;
+            w |= encode_binary(*c as char) as u128;
+        }
+        Self { data: w, k }
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for Kmer128 {
+    fn from(bytes: &[u8; N]) -> Self {
+        Self::from(bytes.as_slice())
+    }
+}
+
+impl From<&str> for Kmer128 {
+    fn from(s: &str) -> Self {
+        Self::from(s.as_bytes())
+    }
+}
+
+impl From<String> for Kmer128 {
+    fn from(s: String) -> Self {
+        Self::from(s.as_bytes())
+    }
+}
+
+impl From<Kmer> for Kmer128 {
+    fn from(kmer: Kmer) -> Self {
+        Self {
+            data: kmer.data as u128,
+            k: kmer.k,
+        }
+    }
+}
+
+// only k-mers of up to 32 bases fit in a `Kmer`, longer ones are given back
+impl TryFrom<Kmer128> for Kmer {
+    type Error = Kmer128;
+
+    fn try_from(kmer: Kmer128) -> Result<Self, Self::Error> {
+        if kmer.k <= 32 {
+            Ok(Kmer::from_u64(kmer.data as u64, kmer.k))
+        } else {
+            Err(kmer)
+        }
+    }
+}
+
+impl From<Kmer128> for u128 {
+    fn from(kmer: Kmer128) -> Self {
+        kmer.data
+    }
+}
+
+// `{}` emits lowercase bases, while the alternate form `{:#}` emits uppercase
+impl std::fmt::Display for Kmer128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let msg: String = if f.alternate() {
+            (0..self.len())
+                .map(|i| decode_binary_u8(self.get_base(i)) as char)
+                .collect()
+        } else {
+            self.clone().into()
+        };
+        write!(f, "{msg}")
+    }
+}
+
+// The mirror of `CanonicalKmer`: a k-mer along with its reverse complement,
+// both updated as bases are added.
+#[derive(Eq, PartialEq, Default, Debug, Clone, Ord, PartialOrd)]
+pub struct CanonicalKmer128 {
+    fw: Kmer128,
+    rc: Kmer128,
+}
+
+impl CanonicalKmer128 {
+    #[inline]
+    pub fn blank_of_size(k: u8) -> Self {
+        Self {
+            fw: Kmer128::from_u128(0, k),
+            rc: Kmer128::from_u128(u128::MAX, k),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fw.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fw.len()
+    }
+
+    #[inline]
+    pub fn from_u128(data: u128, k: u8) -> Self {
+        Self::from(Kmer128::from_u128(data, k))
+    }
+
+    #[inline]
+    pub fn is_fw_canonical(&self) -> bool {
+        self.fw.data < self.rc.data
+    }
+
+    #[inline]
+    pub fn append_base_u8(&mut self, c: u8) -> Base {
+        self.append_base(encode_binary_u8(c))
+    }
+
+    #[inline]
+    pub fn prepend_base_u8(&mut self, c: u8) -> Base {
+        self.prepend_base(encode_binary_u8(c))
+    }
+
+    #[inline]
+    pub fn append_base(&mut self, b: Base) -> Base {
+        let r = self.fw.append_base(b);
+        self.rc.prepend_base(complement_base(b));
+        r
+    }
+
+    #[inline]
+    pub fn prepend_base(&mut self, b: Base) -> Base {
+        let r = self.fw.prepend_base(b);
+        self.rc.append_base(complement_base(b));
+        r
+    }
+
+    #[inline]
+    pub fn get_canonical_kmer(&self) -> Kmer128 {
+        if self.fw.data < self.rc.data {
+            self.fw.clone()
+        } else {
+            self.rc.clone()
+        }
+    }
+
+    #[inline]
+    pub fn get_canonical_word(&self) -> u128 {
+        self.fw.data.min(self.rc.data)
+    }
+
+    #[inline]
+    pub fn get_fw_mer(&self) -> Kmer128 {
+        self.fw.clone()
+    }
+
+    #[inline]
+    pub fn get_rc_mer(&self) -> Kmer128 {
+        self.rc.clone()
+    }
+
+    #[inline]
+    pub fn get_fw_word(&self) -> u128 {
+        self.fw.data
+    }
+
+    #[inline]
+    pub fn get_rc_word(&self) -> u128 {
+        self.rc.data
+    }
+}
+
+// as for `CanonicalKmer`, hash the canonical word
+impl Hash for CanonicalKmer128 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fw.k.hash(state);
+        self.get_canonical_word().hash(state);
+    }
+}
+
+impl From<Kmer128> for CanonicalKmer128 {
+    #[inline]
+    fn from(km: Kmer128) -> Self {
+        Self {
+            rc: km.to_reverse_complement(),
+            fw: km,
+        }
+    }
+}
+
+impl From<&[u8]> for CanonicalKmer128 {
+    fn from(s: &[u8]) -> Self {
+        Self::from(Kmer128::from(s))
+    }
+}
+
+impl From<&str> for CanonicalKmer128 {
+    fn from(s: &str) -> Self {
+        Self::from(Kmer128::from(s))
+    }
+}
+
+impl std::fmt::Display for CanonicalKmer128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.get_canonical_kmer(), f)
+    }
+}
+
+// MASK_TABLE_128[k] keeps the lowest 2k bits, for 0 <= k <= 64
+const fn mask_table_128() -> [u128; 65] {
+    let mut table = [0; 65];
+    let mut k = 1;
+    while k < 64 {
+        table[k] = (1 << (2 * k)) - 1;
+        k += 1;
+    }
+    table[64] = u128::MAX;
+    table
+}
+
+const MASK_TABLE_128: [u128; 65] = mask_table_128();
+
+#[cfg(test)]
+mod test {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::super::hash::SeededState;
+    use super::*;
+    use crate::test_utils::rc_string;
+
+    const KS: [u8; 5] = [1, 31, 33, 47, 64];
+
+    const SEQ: &str = "GATACATAGGATGGACTTGATCCAGTTACGGATCAAGTCCATCCTATGTATCAAAAGGGGCCTTT";
+
+    #[quickcheck]
+    fn rc_identity(lo: u64, hi: u64) -> bool {
+        [33, 64].iter().all(|&k| {
+            let km = Kmer128::from_u64_pair(lo, hi, k);
+            km == km.to_reverse_complement().to_reverse_complement()
+        })
+    }
+
+    #[quickcheck]
+    fn to_canonical_is_idempotent(lo: u64, hi: u64) -> bool {
+        [33, 64].iter().all(|&k| {
+            let km = Kmer128::from_u64_pair(lo, hi, k);
+            let canon = km.to_canonical();
+            canon.is_canonical()
+                && canon == canon.to_canonical()
+                && canon == km.to_reverse_complement().to_canonical()
+        })
+    }
+
+    #[quickcheck]
+    fn agrees_with_kmer(word: u64) -> bool {
+        let km = Kmer::from_u64(word, 31);
+        let km128 = Kmer128::from(km.clone());
+        Kmer128::from(km.to_reverse_complement()) == km128.to_reverse_complement()
+            && km.is_canonical() == km128.is_canonical()
+    }
+
+    #[test]
+    fn test_rc() {
+        for k in KS {
+            let s = &SEQ[..k as usize];
+            let expected = rc_string(s).to_ascii_lowercase();
+
+            let km = Kmer128::from(s).to_reverse_complement();
+            assert_eq!(km.to_string(), expected);
+            assert_eq!(km, Kmer128::from(expected.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_str_repr() {
+        for k in KS {
+            let s = &SEQ[..k as usize];
+            let km = Kmer128::from(s);
+            assert_eq!(km.len(), k as usize);
+            assert_eq!(km.to_string(), s.to_lowercase());
+            assert_eq!(format!("{:#}", km), s);
+            assert_eq!(Kmer128::from(km.to_string()), km);
+        }
+    }
+
+    #[test]
+    fn test_from_u64_pair() {
+        let km = Kmer128::from_u64_pair(u64::MAX, u64::MAX, 33);
+        assert_eq!(km.into_u128(), (0b11 << 64) | u64::MAX as u128);
+        assert_eq!(km.to_string(), "t".repeat(33));
+
+        let km = Kmer128::from_u64_pair(u64::MAX, u64::MAX, 64);
+        assert_eq!(km.into_u128(), u128::MAX);
+
+        let km = Kmer128::from(&SEQ[..40]);
+        let data = km.into_u128();
+        assert_eq!(
+            Kmer128::from_u64_pair(data as u64, (data >> 64) as u64, 40),
+            km
+        );
+    }
+
+    #[test]
+    fn test_append_prepend() {
+        for k in KS {
+            let k = k as usize;
+            let mut km = Kmer128::from(&SEQ[..k]);
+            let shift_off = km.append_base_u8(SEQ.as_bytes()[k]);
+            assert_eq!(km, Kmer128::from(&SEQ[1..k + 1]));
+            assert_eq!(shift_off, encode_binary_u8(SEQ.as_bytes()[0]));
+
+            let shift_off = km.prepend_base(encode_binary_u8(SEQ.as_bytes()[0]));
+            assert_eq!(km, Kmer128::from(&SEQ[..k]));
+            assert_eq!(shift_off, encode_binary_u8(SEQ.as_bytes()[k]));
+        }
+    }
+
+    #[test]
+    fn test_sub_kmer() {
+        let s = &SEQ[..64];
+        let km = Kmer128::from(s);
+
+        for i in 0..s.len() {
+            for j in i..s.len() {
+                assert_eq!(Kmer128::from(&s[i..j]), km.sub_kmer(i, j - i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_kmer128() {
+        for k in [33, 47, 64] {
+            let mut ckm = CanonicalKmer128::from(&SEQ[..k]);
+            for i in k..SEQ.len() {
+                ckm.append_base_u8(SEQ.as_bytes()[i]);
+
+                let s = &SEQ[i + 1 - k..i + 1];
+                let fw = Kmer128::from(s);
+                assert_eq!(ckm.get_fw_mer(), fw);
+                assert_eq!(ckm.get_rc_mer(), fw.to_reverse_complement());
+                assert_eq!(ckm.get_canonical_kmer(), fw.to_canonical());
+                assert_eq!(ckm.to_string(), fw.to_canonical().to_string());
+            }
+
+            let mut blank = CanonicalKmer128::blank_of_size(k as u8);
+            for c in SEQ[..k].bytes() {
+                blank.append_base_u8(c);
+            }
+            assert_eq!(blank, CanonicalKmer128::from(&SEQ[..k]));
+        }
+    }
+
+    fn hash_of<H: Hash>(h: &H) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        h.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_hash() {
+        let km = Kmer128::from(&SEQ[..47]);
+        let rc = km.to_reverse_complement();
+        assert_eq!(hash_of(&km), hash_of(&km.clone()));
+        assert_ne!(hash_of(&km), hash_of(&rc));
+
+        // the twins share their canonical hash
+        let ckm = CanonicalKmer128::from(km);
+        let crc = CanonicalKmer128::from(rc);
+        assert_eq!(hash_of(&ckm), hash_of(&crc));
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_long() {
+        let _ = Kmer128::from("a".repeat(65));
+    }
+
+    #[test]
+    fn test_minimizer() {
+        let state = SeededState(7);
+        for k in KS {
+            let km = Kmer128::from(&SEQ[..k as usize]);
+            for w in [1, 7, 19, 32] {
+                if w > k as usize {
+                    continue;
+                }
+                let (mm, o) = km.minimizer(w, &state);
+                // the leftmost l-mer of smallest hash
+                let expected = (0..=k as usize - w)
+                    .min_by_key(|&pos| {
+                        let lmer = km.sub_kmer(pos, w).data as u64;
+                        super::super::hash::hash_one(&state, lmer)
+                    })
+                    .unwrap();
+                assert_eq!(o, expected);
+                assert_eq!(Kmer128::from(mm.clone()), km.sub_kmer(o, w));
+
+                if k <= 32 {
+                    let small: Kmer = km.clone().try_into().unwrap();
+                    assert_eq!(small.minimizer(w, &state), (mm, o));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_into_kmer() {
+        for k in [1, 31, 32] {
+            let km = Kmer::from(&SEQ.as_bytes()[..k]);
+            let back: Result<Kmer, _> = Kmer128::from(km.clone()).try_into();
+            assert_eq!(back, Ok(km));
+        }
+        let long = Kmer128::from(&SEQ[..33]);
+        let back: Result<Kmer, _> = long.clone().try_into();
+        assert_eq!(back, Err(long));
+    }
+
+    #[test]
+    #[should_panic]
+    fn minimizer_too_wide() {
+        let _ = Kmer128::from(&SEQ[..40]).minimizer(33, &SeededState(7));
+    }
+}
@@ -0,0 +1,259 @@
+use super::prelude::*;
+use super::{CanonicalKmer, Kmer};
+
+// holds what is essentially a pair of
+// km: the k-mer on the read, a `CanonicalKmer` for the canonical iterator
+// pos: the offset on the read where this k-mer starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KmerPos<K = Kmer> {
+    pub km: K,
+    pub pos: usize,
+}
+
+// A KmerFromBytesIter wraps any iterator over ASCII bases
+// (e.g. bytes decoded incrementally from a gzip stream) and
+// produces a `KmerPos` for every valid k-mer in the stream.
+// Successive k-mers are built by rolling the previous one
+// rather than re-encoding each window. Invalid characters
+// (e.g. `N`) reset the window, so no k-mer spanning them
+// is ever produced.
+pub struct KmerFromBytesIter<I> {
+    iter: I,
+    km: Kmer,
+    k: usize,
+    // number of bytes consumed from `iter` so far
+    consumed: usize,
+    // number of consecutive valid bases ending at the last consumed byte
+    valid: usize,
+}
+
+impl<I> KmerFromBytesIter<I>
+where
+    I: Iterator<Item = u8>,
+{
+    pub fn new(iter: I, k: u8) -> Self {
+        assert!(
+            k > 0 && k <= 32,
+            "k must be between 1 and 32 (inclusive), got {k}"
+        );
+        Self {
+            iter,
+            km: Kmer::from_u64(0, k),
+            k: k as usize,
+            consumed: 0,
+            valid: 0,
+        }
+    }
+}
+
+impl<I> Iterator for KmerFromBytesIter<I>
+where
+    I: Iterator<Item = u8>,
+{
+    type Item = KmerPos;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for c in self.iter.by_ref() {
+            self.consumed += 1;
+            let b = encode_binary_u8(c);
+
+            // c is an invalid code if >= 4
+            if b < 4 {
+                self.km.append_base(b);
+                self.valid += 1;
+                if self.valid >= self.k {
+                    return Some(KmerPos {
+                        km: self.km.clone(),
+                        pos: self.consumed - self.k,
+                    });
+                }
+            } else {
+                // restart the window after the invalid base
+                self.valid = 0;
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every remaining byte yields at most one k-mer, once
+        // the current window has been filled
+        let (_, upper) = self.iter.size_hint();
+        let missing = (self.k - 1).saturating_sub(self.valid);
+        (0, upper.map(|u| u.saturating_sub(missing)))
+    }
+}
+
+impl<I> KmerFromBytesIter<I>
+where
+    I: Iterator<Item = u8>,
+{
+    // the same k-mers in canonical form, rolling both strands at once
+    pub fn canonical(self) -> CanonicalKmerFromBytesIter<I> {
+        // the strands stay reverse complements of each other as bases roll
+        // in, so a partially filled window carries over
+        CanonicalKmerFromBytesIter {
+            iter: self.iter,
+            km: CanonicalKmer::from_u64(self.km.data, self.k as u8),
+            k: self.k,
+            consumed: self.consumed,
+            valid: self.valid,
+        }
+    }
+}
+
+// The canonical counterpart of `KmerFromBytesIter`: each k-mer comes with
+// both its strands, updated in O(1) per base.
+pub struct CanonicalKmerFromBytesIter<I> {
+    iter: I,
+    km: CanonicalKmer,
+    k: usize,
+    consumed: usize,
+    valid: usize,
+}
+
+impl<I> Iterator for CanonicalKmerFromBytesIter<I>
+where
+    I: Iterator<Item = u8>,
+{
+    type Item = KmerPos<CanonicalKmer>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for c in self.iter.by_ref() {
+            self.consumed += 1;
+            let b = encode_binary_u8(c);
+
+            if b < 4 {
+                self.km.append_base(b);
+                self.valid += 1;
+                if self.valid >= self.k {
+                    return Some(KmerPos {
+                        km: self.km.clone(),
+                        pos: self.consumed - self.k,
+                    });
+                }
+            } else {
+                self.valid = 0;
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.iter.size_hint();
+        let missing = (self.k - 1).saturating_sub(self.valid);
+        (0, upper.map(|u| u.saturating_sub(missing)))
+    }
+}
+
+impl Kmer {
+    pub fn iter_from_bytes<I>(iter: I, k: u8) -> KmerFromBytesIter<I::IntoIter>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        KmerFromBytesIter::new(iter.into_iter(), k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEQ: &[u8] = b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGGGG";
+
+    #[test]
+    fn test_matches_windows() {
+        let k = 11u8;
+        let expected: Vec<KmerPos> = SEQ
+            .windows(k as usize)
+            .enumerate()
+            .map(|(pos, w)| KmerPos {
+                km: Kmer::from(w),
+                pos,
+            })
+            .collect();
+
+        let kmers: Vec<KmerPos> = Kmer::iter_from_bytes(SEQ.iter().copied(), k).collect();
+        assert_eq!(kmers, expected);
+    }
+
+    #[test]
+    fn test_k32() {
+        let kmers: Vec<KmerPos> = Kmer::iter_from_bytes(SEQ.iter().copied(), 32).collect();
+        assert_eq!(kmers.len(), SEQ.len() - 31);
+        assert_eq!(kmers[3].km, Kmer::from(&SEQ[3..35]));
+    }
+
+    #[test]
+    fn test_skip_invalid() {
+        let r = b"ACGTNACGTACNNGTACGTAn";
+        let k = 4u8;
+
+        let kmers: Vec<KmerPos> = Kmer::iter_from_bytes(r.iter().copied(), k).collect();
+        let positions: Vec<usize> = kmers.iter().map(|kp| kp.pos).collect();
+        assert_eq!(positions, vec![0, 5, 6, 7, 13, 14, 15, 16]);
+
+        for kp in kmers {
+            let w = &r[kp.pos..kp.pos + k as usize];
+            assert_eq!(kp.km, Kmer::from(w));
+        }
+    }
+
+    #[test]
+    fn test_shorter_than_k() {
+        let mut it = Kmer::iter_from_bytes(b"ACGTACG".iter().copied(), 8);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert!(it.next().is_none());
+
+        let mut it = Kmer::iter_from_bytes(b"ACGTNACGT".iter().copied(), 5);
+        assert!(it.next().is_none());
+
+        let mut it = Kmer::iter_from_bytes(std::iter::empty(), 3);
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let mut it = Kmer::iter_from_bytes(b"ACGTACGT".iter().copied(), 3);
+        assert_eq!(it.size_hint(), (0, Some(6)));
+        it.next();
+        assert_eq!(it.size_hint(), (0, Some(5)));
+        assert_eq!(it.count(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_k_too_large() {
+        let _ = Kmer::iter_from_bytes(SEQ.iter().copied(), 33);
+    }
+
+    #[test]
+    fn test_canonical() {
+        let r = b"ACGTNACGTACNNGTACGTAnTTTTGGCCATTTTTCCTG";
+        let k = 5u8;
+        let fw: Vec<KmerPos> = Kmer::iter_from_bytes(r.iter().copied(), k).collect();
+        let canonical: Vec<KmerPos<CanonicalKmer>> = Kmer::iter_from_bytes(r.iter().copied(), k)
+            .canonical()
+            .collect();
+        assert_eq!(canonical.len(), fw.len());
+        for (ckp, kp) in canonical.iter().zip(&fw) {
+            assert_eq!(ckp.pos, kp.pos);
+            assert_eq!(ckp.km, CanonicalKmer::from(kp.km.clone()));
+        }
+
+        // switching mid-stream
+        for skip in [1, 3] {
+            let mut it = Kmer::iter_from_bytes(SEQ.iter().copied(), 11);
+            let first: Vec<KmerPos> = it.by_ref().take(skip).collect();
+            assert_eq!(first.len(), skip);
+            let rest: Vec<usize> = it.canonical().map(|ckp| ckp.pos).collect();
+            assert_eq!(rest, (skip..=SEQ.len() - 11).collect::<Vec<_>>());
+        }
+        let mut it = Kmer::iter_from_bytes(b"ACGTACGTAC".iter().copied(), 4).canonical();
+        assert_eq!(it.size_hint(), (0, Some(7)));
+        assert_eq!(
+            it.next().unwrap().km.get_canonical_kmer(),
+            Kmer::from("ACGT")
+        );
+    }
+}
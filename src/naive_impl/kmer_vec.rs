@@ -0,0 +1,219 @@
+// A vector of k-mers of the same length, stored as their bare words: a
+// `Vec<Kmer>` takes 16 bytes per k-mer for the `k` field and the padding,
+// a `KmerVec` takes 8.
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::Kmer;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KmerVec {
+    k: u8,
+    words: Vec<u64>,
+}
+
+impl KmerVec {
+    pub fn new(k: u8) -> Self {
+        Self::with_capacity(k, 0)
+    }
+
+    pub fn with_capacity(k: u8, capacity: usize) -> Self {
+        assert!(k <= 32, "k-mers longer than 32 bases not supported");
+        Self {
+            k,
+            words: Vec::with_capacity(capacity),
+        }
+    }
+
+    // `words` must be sorted, e.g. to load back the words of a sorted
+    // `KmerVec`, and hold at most `k` bases each
+    pub fn from_sorted_words(words: Vec<u64>, k: u8) -> Self {
+        assert!(k <= 32, "k-mers longer than 32 bases not supported");
+        debug_assert!(words.windows(2).all(|w| w[0] <= w[1]), "unsorted words");
+        debug_assert!(
+            words.iter().all(|&w| Kmer::from_u64(w, k).data == w),
+            "words longer than {} bases",
+            k
+        );
+        Self { k, words }
+    }
+
+    pub fn k(&self) -> u8 {
+        self.k
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn push(&mut self, km: &Kmer) {
+        assert_eq!(
+            km.k, self.k,
+            "cannot push a {}-mer in a vector of {}-mers",
+            km.k, self.k
+        );
+        self.words.push(km.data);
+    }
+
+    pub fn get(&self, i: usize) -> Kmer {
+        Kmer {
+            k: self.k,
+            data: self.words[i],
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Kmer> + '_ {
+        let k = self.k;
+        self.words.iter().map(move |&data| Kmer { k, data })
+    }
+
+    // replaces every k-mer by its canonical form, `Kmer::to_canonical`,
+    // working on the bare words
+    pub fn canonicalize_in_place(&mut self) {
+        let k = self.k as usize;
+        for w in self.words.iter_mut() {
+            *w = Kmer::canonical_word(*w, k);
+        }
+    }
+
+    // sorts in the order of `Kmer`, i.e. of the words
+    pub fn sort_unstable(&mut self) {
+        self.words.sort_unstable();
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn par_sort(&mut self) {
+        self.words.par_sort_unstable();
+    }
+
+    pub fn dedup(&mut self) {
+        self.words.dedup();
+    }
+
+    // same as `slice::binary_search`, the vector must be sorted
+    pub fn binary_search(&self, km: &Kmer) -> Result<usize, usize> {
+        // `Kmer`s are ordered by length first
+        if km.k != self.k {
+            return Err(if km.k < self.k { 0 } else { self.len() });
+        }
+        self.words.binary_search(&km.data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::xorshift;
+
+    fn random_kmers(n: usize, k: u8, mut seed: u64) -> Vec<Kmer> {
+        (0..n)
+            .map(|_| {
+                // few distinct k-mers, to have duplicates
+                Kmer::from_u64(xorshift(&mut seed) % 5000, k)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_as_vec_of_kmers() {
+        for k in [7, 31, 32] {
+            let mut expected = random_kmers(10_000, k, k as u64);
+            let mut kv = KmerVec::new(k);
+            for km in &expected {
+                kv.push(km);
+            }
+            assert_eq!(kv.len(), expected.len());
+            assert_eq!(kv.get(17), expected[17]);
+            assert!(kv.iter().eq(expected.iter().cloned()));
+
+            kv.sort_unstable();
+            kv.dedup();
+            expected.sort_unstable();
+            expected.dedup();
+            assert!(kv.iter().eq(expected.iter().cloned()));
+
+            for (i, km) in expected.iter().enumerate() {
+                assert_eq!(kv.binary_search(km), Ok(i));
+            }
+            for w in [5000, 5001, u64::MAX] {
+                let km = Kmer::from_u64(w, k);
+                assert_eq!(kv.binary_search(&km), expected.binary_search(&km));
+                assert!(kv.binary_search(&km).is_err());
+            }
+            // a k-mer of another length is never found
+            let km = Kmer::from_u64(expected[3].data, k - 1);
+            assert_eq!(kv.binary_search(&km), expected.binary_search(&km));
+
+            let loaded = KmerVec::from_sorted_words(kv.words().to_vec(), k);
+            assert_eq!(loaded, kv);
+        }
+    }
+
+    #[test]
+    fn canonicalize_in_place() {
+        for k in [1, 2, 7, 31, 32] {
+            let kmers = random_kmers(10_000, k, k as u64 + 1);
+            let mut kv = KmerVec::new(k);
+            for km in &kmers {
+                kv.push(km);
+            }
+            kv.canonicalize_in_place();
+            assert!(kv.iter().eq(kmers.iter().map(|km| km.to_canonical())));
+            assert!(kv.iter().all(|km| km.is_canonical()));
+
+            // canonical k-mers are left as they are
+            let canonical = kv.clone();
+            kv.canonicalize_in_place();
+            assert_eq!(kv, canonical);
+        }
+
+        // palindromes are their own reverse complement
+        let mut kv = KmerVec::new(4);
+        kv.push(&Kmer::from("ACGT"));
+        kv.push(&Kmer::from("TTTT"));
+        kv.canonicalize_in_place();
+        assert!(kv.iter().eq([Kmer::from("ACGT"), Kmer::from("AAAA")]));
+    }
+
+    #[test]
+    fn kmer_vec_serde() {
+        let mut kv = KmerVec::new(11);
+        for km in random_kmers(100, 11, 3) {
+            kv.push(&km);
+        }
+        let json = serde_json::to_string(&kv).unwrap();
+        let loaded: KmerVec = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, kv);
+        assert_eq!(loaded.k(), 11);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_sort() {
+        let mut kv = KmerVec::new(21);
+        for km in random_kmers(100_000, 21, 5) {
+            kv.push(&km);
+        }
+        let mut expected = kv.clone();
+        expected.sort_unstable();
+        kv.par_sort();
+        assert_eq!(kv, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_other_k() {
+        let mut kv = KmerVec::new(11);
+        kv.push(&Kmer::from("ACGT"));
+    }
+}
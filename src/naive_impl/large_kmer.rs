@@ -0,0 +1,408 @@
+use std::hash::{Hash, Hasher};
+
+use super::kmer::{Orientation, BASE_TABLE};
+use super::prelude::*;
+use super::Kmer;
+
+// A k-mer for 1 <= k <= 64, stored 2 bits per base across two words.
+// As with `Kmer`, the sequence is read "left to right" from "lower to
+// higher" order bits: bases 0..32 live in `data[0]` and bases 32..64
+// live in `data[1]`.
+#[derive(Eq, Default, Debug, Clone)]
+pub struct LargeKmer {
+    pub k: u8,
+    pub(crate) data: [u64; 2],
+}
+
+const MAX_LARGE_K: u8 = 64;
+
+impl PartialEq for LargeKmer {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.k == other.k
+    }
+}
+
+// Order by k, then by the 128-bit value of the k-mer (high word first),
+// so that comparisons between k-mers of the same length agree with
+// the ordering used by `Kmer`.
+impl Ord for LargeKmer {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.k
+            .cmp(&other.k)
+            .then(self.data[1].cmp(&other.data[1]))
+            .then(self.data[0].cmp(&other.data[0]))
+    }
+}
+
+impl PartialOrd for LargeKmer {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for LargeKmer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.data[0]);
+        state.write_u64(self.data[1]);
+    }
+}
+
+// returns the pair of masks keeping the lowest k*2 bits of a two word k-mer
+#[inline]
+const fn word_masks(k: usize) -> [u64; 2] {
+    if k >= 64 {
+        [u64::MAX, u64::MAX]
+    } else if k >= 32 {
+        [u64::MAX, (1 << (2 * (k - 32))) - 1]
+    } else {
+        [(1 << (2 * k)) - 1, 0]
+    }
+}
+
+// shift a two word k-mer towards the low order bits by `s` bits, shifting
+// out all of them for s >= 128 (e.g. for the reverse complement of k = 0)
+#[inline]
+fn shr_words(w: [u64; 2], s: usize) -> [u64; 2] {
+    if s == 0 {
+        w
+    } else if s < 64 {
+        [(w[0] >> s) | (w[1] << (64 - s)), w[1] >> s]
+    } else if s < 128 {
+        [w[1] >> (s - 64), 0]
+    } else {
+        [0, 0]
+    }
+}
+
+// shift a two word k-mer towards the high order bits by `s` bits, shifting
+// out all of them for s >= 128
+#[inline]
+fn shl_words(w: [u64; 2], s: usize) -> [u64; 2] {
+    if s == 0 {
+        w
+    } else if s < 64 {
+        [w[0] << s, (w[1] << s) | (w[0] >> (64 - s))]
+    } else if s < 128 {
+        [0, w[0] << (s - 64)]
+    } else {
+        [0, 0]
+    }
+}
+
+impl LargeKmer {
+    pub fn len(&self) -> usize {
+        self.k as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.k == 0
+    }
+
+    pub fn from_words(data: [u64; 2], k: u8) -> Self {
+        assert!(
+            k <= MAX_LARGE_K,
+            "kmers longer than {MAX_LARGE_K} bases not supported"
+        );
+        let mask = word_masks(k as usize);
+        Self {
+            data: [data[0] & mask[0], data[1] & mask[1]],
+            k,
+        }
+    }
+
+    pub fn into_words(&self) -> [u64; 2] {
+        self.data
+    }
+
+    #[inline]
+    pub fn get_base(&self, pos: usize) -> Base {
+        assert!(pos < self.len());
+        (self.data[pos / 32] >> (2 * (pos % 32))) & 0x03
+    }
+
+    #[inline]
+    fn set_base(&mut self, pos: usize, c: Base) {
+        let shift = 2 * (pos % 32);
+        let w = &mut self.data[pos / 32];
+        *w = (*w & !(0x03 << shift)) | (c << shift);
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        let rc = self.to_reverse_complement();
+        *self <= rc
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.is_canonical() {
+            Orientation::IsCanonical
+        } else {
+            Orientation::NotCanonical
+        }
+    }
+
+    pub fn to_canonical(&self) -> Self {
+        if self.is_canonical() {
+            self.clone()
+        } else {
+            self.to_reverse_complement()
+        }
+    }
+
+    #[inline]
+    pub fn prepend_base_u8(&mut self, c: u8) -> Base {
+        self.prepend_base(encode_binary_u8(c))
+    }
+
+    #[inline]
+    pub fn append_base_u8(&mut self, c: u8) -> Base {
+        self.append_base(encode_binary_u8(c))
+    }
+
+    #[inline]
+    pub fn prepend_base(&mut self, c: Base) -> Base {
+        let r = self.get_base(self.len() - 1);
+        let mask = word_masks(self.len());
+        let w = shl_words(self.data, 2);
+        self.data = [(w[0] | c) & mask[0], w[1] & mask[1]];
+        r
+    }
+
+    #[inline]
+    pub fn append_base(&mut self, c: Base) -> Base {
+        let r = self.data[0] & 0x03;
+        self.data = shr_words(self.data, 2);
+        self.set_base(self.len() - 1, c);
+        r
+    }
+
+    // The reverse complement of both words is computed independently
+    // (see `Kmer::get_reverse_complement_word`), the words are swapped and
+    // the result is then shifted down across the word boundary, since 2*k
+    // need not be a multiple of 64.
+    pub fn to_reverse_complement(&self) -> Self {
+        let rc = [
+            Kmer::get_reverse_complement_word(self.data[1], 32),
+            Kmer::get_reverse_complement_word(self.data[0], 32),
+        ];
+
+        Self {
+            data: shr_words(rc, 2 * (MAX_LARGE_K - self.k) as usize),
+            k: self.k,
+        }
+    }
+
+    pub fn sub_kmer(&self, pos: usize, width: usize) -> Self {
+        assert!(pos < self.len());
+        assert!(pos + width <= self.len());
+
+        let w = shr_words(self.data, pos * 2); // shift out low order bits
+        Self::from_words(w, width as u8) // mask out high order bits
+    }
+}
+
+// Converting to and from LargeKmers
+impl From<LargeKmer> for String {
+    fn from(kmer: LargeKmer) -> Self {
+        (0..kmer.len())
+            .map(|i| BASE_TABLE[kmer.get_base(i) as usize])
+            .collect()
+    }
+}
+
+impl From<&[u8]> for LargeKmer {
+    fn from(s: &[u8]) -> Self {
+        if s.len() > MAX_LARGE_K as usize {
+            panic!("kmers longer than {MAX_LARGE_K} bases not supported");
+        }
+
+        let k = s.len() as u8;
+
+        let mut w = [0_u64; 2];
+        // read sequence "left to right" from "lower to higher" order bits
+        for c in s.iter().rev() {
+            w = shl_words(w, 2);
+            w[0] |= encode_binary(*c as char);
+        }
+        Self { data: w, k }
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for LargeKmer {
+    fn from(bytes: &[u8; N]) -> Self {
+        Self::from(bytes.as_slice())
+    }
+}
+
+impl From<&str> for LargeKmer {
+    fn from(s: &str) -> Self {
+        Self::from(s.as_bytes())
+    }
+}
+
+impl From<String> for LargeKmer {
+    fn from(s: String) -> Self {
+        Self::from(s.as_bytes())
+    }
+}
+
+impl From<Kmer> for LargeKmer {
+    fn from(kmer: Kmer) -> Self {
+        Self {
+            data: [kmer.data, 0],
+            k: kmer.k,
+        }
+    }
+}
+
+// `{}` emits lowercase bases, while the alternate form `{:#}` emits uppercase
+impl std::fmt::Display for LargeKmer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let msg: String = if f.alternate() {
+            (0..self.len())
+                .map(|i| decode_binary_u8(self.get_base(i)) as char)
+                .collect()
+        } else {
+            self.clone().into()
+        };
+        write!(f, "{msg}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+    use crate::test_utils::rc_string;
+
+    const LARGE_KS: [u8; 3] = [33, 47, 63];
+
+    const SEQ: &str = "GATACATAGGATGGACTTGATCCAGTTACGGATCAAGTCCATCCTATGTATCAAAAGGGGCCTTT";
+
+    #[quickcheck]
+    fn rc_identity(lo: u64, hi: u64) -> bool {
+        LARGE_KS.iter().all(|&k| {
+            let km = LargeKmer::from_words([lo, hi], k);
+            km == km.to_reverse_complement().to_reverse_complement()
+        })
+    }
+
+    #[quickcheck]
+    fn to_canonical_is_canonical(lo: u64, hi: u64) -> bool {
+        LARGE_KS.iter().all(|&k| {
+            let km = LargeKmer::from_words([lo, hi], k);
+            let canon = km.to_canonical();
+            canon.is_canonical() && canon == canon.to_canonical()
+        })
+    }
+
+    #[quickcheck]
+    fn agrees_with_kmer(word: u64) -> bool {
+        let km = Kmer::from_u64(word, 31);
+        let lkm = LargeKmer::from(km.clone());
+        LargeKmer::from(km.to_reverse_complement()) == lkm.to_reverse_complement()
+            && km.is_canonical() == lkm.is_canonical()
+    }
+
+    #[test]
+    fn test_rc() {
+        for k in [1, 31, 32, 33, 47, 63, 64] {
+            let s = &SEQ[..k];
+            let expected = rc_string(s).to_ascii_lowercase();
+
+            let km = LargeKmer::from(s).to_reverse_complement();
+            assert_eq!(km.to_string(), expected);
+            assert_eq!(km, LargeKmer::from(expected.as_str()));
+        }
+    }
+
+    #[test]
+    fn empty_kmer() {
+        let km = LargeKmer::from(&b""[..]);
+        assert_eq!(km.to_reverse_complement(), km);
+        assert_eq!(km.to_canonical(), km);
+        assert!(km.is_canonical());
+        assert_eq!(shr_words([u64::MAX, u64::MAX], 128), [0, 0]);
+        assert_eq!(shl_words([u64::MAX, u64::MAX], 128), [0, 0]);
+    }
+
+    #[test]
+    fn test_str_repr() {
+        let s = &SEQ[..63];
+        let km = LargeKmer::from(s);
+        assert_eq!(km.len(), 63);
+        assert_eq!(km.to_string(), s.to_lowercase());
+
+        let s = &SEQ[..64];
+        assert_eq!(LargeKmer::from(s).to_string(), s.to_lowercase());
+        assert_eq!(format!("{:#}", LargeKmer::from(s)), s);
+    }
+
+    #[test]
+    fn test_is_canon() {
+        let km = LargeKmer::from(&SEQ[..47]);
+        let rc = km.to_reverse_complement();
+        assert_ne!(km.is_canonical(), rc.is_canonical());
+        assert_eq!(km.to_canonical(), rc.to_canonical());
+        assert!(km.to_canonical() <= km && km.to_canonical() <= rc);
+    }
+
+    #[test]
+    fn test_append_prepend() {
+        for k in [33, 47, 63, 64] {
+            let mut km = LargeKmer::from(&SEQ[..k]);
+            let shift_off = km.append_base_u8(SEQ.as_bytes()[k]);
+            assert_eq!(km, LargeKmer::from(&SEQ[1..k + 1]));
+            assert_eq!(shift_off, encode_binary_u8(SEQ.as_bytes()[0]));
+
+            let shift_off = km.prepend_base(encode_binary_u8(SEQ.as_bytes()[0]));
+            assert_eq!(km, LargeKmer::from(&SEQ[..k]));
+            assert_eq!(shift_off, encode_binary_u8(SEQ.as_bytes()[k]));
+        }
+    }
+
+    #[test]
+    fn test_sub_kmer() {
+        let s = &SEQ[..47];
+        let km = LargeKmer::from(s);
+
+        for i in 0..s.len() {
+            for j in i..s.len() {
+                let ss = &s[i..j];
+                assert_eq!(LargeKmer::from(ss), km.sub_kmer(i, j - i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash() {
+        let km = LargeKmer::from(&SEQ[..47]);
+
+        let mut h1 = DefaultHasher::new();
+        km.hash(&mut h1);
+
+        let mut h2 = DefaultHasher::new();
+        km.clone().hash(&mut h2);
+        assert_eq!(h1.finish(), h2.finish());
+
+        let mut h3 = DefaultHasher::new();
+        km.to_reverse_complement().hash(&mut h3);
+        assert_ne!(h1.finish(), h3.finish());
+    }
+
+    #[test]
+    fn test_from_words_masks() {
+        let km = LargeKmer::from_words([u64::MAX, u64::MAX], 33);
+        assert_eq!(km.into_words(), [u64::MAX, 0b11]);
+        assert_eq!(km.to_string(), "t".repeat(33));
+
+        let km = LargeKmer::from_words([u64::MAX, u64::MAX], 64);
+        assert_eq!(km.into_words(), [u64::MAX, u64::MAX]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_long() {
+        let _ = LargeKmer::from("a".repeat(65));
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::VecDeque;
+use std::hash::BuildHasher;
+
+use super::hash::hash_one;
+
+// Panics unless `1 <= w <= k <= 32`: the minimizers of k-mers are chosen
+// among their l-mers of width `w`, and both must fit in a word. With
+// `w == k`, the minimizer of a k-mer is the k-mer itself.
+pub(crate) fn check_window(k: usize, w: usize) {
+    assert!(
+        (1..=k).contains(&w) && k <= 32,
+        "minimizers need 1 <= w <= k <= 32, got k = {} and w = {}",
+        k,
+        w
+    );
+}
+
+// An l-mer (or anything standing for one, e.g. both of its strands) at
+// position `pos`, with the hash it is ranked by.
+#[derive(Clone, Debug)]
+pub struct HashedLmer<V = u64> {
+    pub lmer: V,
+    pub pos: usize,
+    pub hash: u64,
+}
+
+impl<V> HashedLmer<V> {
+    pub fn new(lmer: V, pos: usize, hash: u64) -> Self {
+        Self { lmer, pos, hash }
+    }
+}
+
+// How the minimizer of a window is selected among its l-mers: `better(a, b)`
+// says whether `a` ranks strictly before `b`. It must be a strict weak
+// order; l-mers that rank equally are ties, broken by position.
+pub trait MinimizerScheme: Clone {
+    fn better<V>(&self, a: &HashedLmer<V>, b: &HashedLmer<V>) -> bool;
+
+    // keep the rightmost of tied l-mers rather than the leftmost
+    fn prefer_rightmost(&self) -> bool {
+        false
+    }
+}
+
+// The usual scheme: the smallest hash wins, the leftmost one on ties.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LeftMin;
+
+impl MinimizerScheme for LeftMin {
+    #[inline]
+    fn better<V>(&self, a: &HashedLmer<V>, b: &HashedLmer<V>) -> bool {
+        a.hash < b.hash
+    }
+}
+
+// The ranking of `S`, but the rightmost l-mer wins ties.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RightMin<S = LeftMin>(pub S);
+
+impl<S: MinimizerScheme> MinimizerScheme for RightMin<S> {
+    #[inline]
+    fn better<V>(&self, a: &HashedLmer<V>, b: &HashedLmer<V>) -> bool {
+        self.0.better(a, b)
+    }
+
+    fn prefer_rightmost(&self) -> bool {
+        !self.0.prefer_rightmost()
+    }
+}
+
+// Prefers l-mers whose hash is a multiple of `r`, then the smallest hash:
+// a window containing such an l-mer picks one of them, which makes the
+// selected l-mers sparser and more stable when `r` is a small power of 2.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModMinimizer {
+    r: u64,
+}
+
+impl ModMinimizer {
+    pub fn new(r: u64) -> Self {
+        assert!(r > 0);
+        Self { r }
+    }
+
+    #[inline]
+    fn key<V>(&self, a: &HashedLmer<V>) -> (bool, u64) {
+        (a.hash % self.r != 0, a.hash)
+    }
+}
+
+impl MinimizerScheme for ModMinimizer {
+    #[inline]
+    fn better<V>(&self, a: &HashedLmer<V>, b: &HashedLmer<V>) -> bool {
+        self.key(a) < self.key(b)
+    }
+}
+
+// Monotone queue giving the minimizer of a window sliding over l-mers.
+//
+// Queue invariant, for l-mers pushed in increasing position:
+// Q = [ (Li, pi) ... (Lj, pj) ]
+//   front                    back
+// - Li ranks before Lj and pi < pj for i < j (or ties, when the leftmost of
+//   tied l-mers is kept)
+// - each Li is the best of the l-mers from pi up to the last pushed one,
+//   so it could still be the minimizer of a window starting after it.
+//
+// Evicting the l-mers left of the window and reading the front then gives
+// the window's minimizer in amortized O(1). The ranking is given by the
+// `MinimizerScheme`, smallest hash first and leftmost on ties by default.
+#[derive(Clone, Debug)]
+pub struct HashedMinimizerQueue<V = u64, S = LeftMin> {
+    dq: VecDeque<HashedLmer<V>>,
+    scheme: S,
+}
+
+impl<V, S: MinimizerScheme + Default> Default for HashedMinimizerQueue<V, S> {
+    fn default() -> Self {
+        Self::with_scheme(S::default(), 0)
+    }
+}
+
+impl<V, S: MinimizerScheme + Default> HashedMinimizerQueue<V, S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_scheme(S::default(), capacity)
+    }
+}
+
+impl<V, S: MinimizerScheme> HashedMinimizerQueue<V, S> {
+    pub fn with_scheme(scheme: S, capacity: usize) -> Self {
+        Self {
+            dq: VecDeque::with_capacity(capacity),
+            scheme,
+        }
+    }
+
+    pub fn scheme(&self) -> &S {
+        &self.scheme
+    }
+
+    // drop the l-mers starting before `pos`
+    #[inline]
+    pub fn evict_before(&mut self, pos: usize) {
+        while self.dq.front().map_or(false, |f| f.pos < pos) {
+            self.dq.pop_front();
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, lmer: HashedLmer<V>) {
+        let scheme = &self.scheme;
+        let rightmost = scheme.prefer_rightmost();
+        // pop what `lmer` beats, or ties with if the rightmost tie wins
+        while self.dq.back().map_or(false, |b| {
+            scheme.better(&lmer, b) || (rightmost && !scheme.better(b, &lmer))
+        }) {
+            self.dq.pop_back();
+        }
+        self.dq.push_back(lmer);
+    }
+
+    // the minimizer of the l-mers pushed since the last eviction point
+    #[inline]
+    pub fn front(&self) -> Option<&HashedLmer<V>> {
+        self.dq.front()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &HashedLmer<V>> + '_ {
+        self.dq.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dq.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dq.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.dq.clear()
+    }
+}
+
+// Minimizers of a sequence given by its consecutive l-mer words (`words[i]`
+// is the l-mer of length `w` starting at `i`): yields the word and offset in
+// the k-mer of the leftmost minimal l-mer of every window of `k - w + 1`
+// l-mers, i.e. of every k-mer, hashing each l-mer once.
+pub fn minimizers_of_word_seq<'a, I, T>(
+    words: I,
+    k: usize,
+    w: usize,
+    state: &'a T,
+) -> impl Iterator<Item = (u64, usize)> + 'a
+where
+    I: IntoIterator<Item = u64>,
+    I::IntoIter: 'a,
+    T: BuildHasher,
+{
+    assert!(
+        (1..=k).contains(&w),
+        "minimizers need 1 <= w <= k, got k = {} and w = {}",
+        k,
+        w
+    );
+    let n_lmers = k - w + 1;
+    let mut q: HashedMinimizerQueue = HashedMinimizerQueue::with_capacity(n_lmers);
+
+    words
+        .into_iter()
+        .enumerate()
+        .filter_map(move |(pos, lmer)| {
+            q.push(HashedLmer::new(lmer, pos, hash_one(state, lmer)));
+            // the window of the k-mer ending with this l-mer
+            let start = (pos + 1).checked_sub(n_lmers)?;
+            q.evict_before(start);
+            q.front().map(|min| (min.lmer, min.pos - start))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::naive_impl::hash::LexHasherState;
+
+    fn hashed(hashes: &[u64]) -> Vec<HashedLmer> {
+        hashes
+            .iter()
+            .enumerate()
+            .map(|(i, h)| HashedLmer::new(0, i, *h))
+            .collect()
+    }
+
+    fn positions<S: MinimizerScheme>(q: &HashedMinimizerQueue<u64, S>) -> Vec<usize> {
+        q.iter().map(|x| x.pos).collect()
+    }
+
+    #[test]
+    fn ties() {
+        let lmers = hashed(&[1, 0, 0, 2]);
+        let mut leftmost: HashedMinimizerQueue = HashedMinimizerQueue::new();
+        let mut rightmost: HashedMinimizerQueue<u64, RightMin> = HashedMinimizerQueue::new();
+        for lmer in lmers {
+            leftmost.push(lmer.clone());
+            rightmost.push(lmer);
+        }
+        assert_eq!(positions(&leftmost), vec![1, 2, 3]);
+        assert_eq!(positions(&rightmost), vec![2, 3]);
+
+        leftmost.evict_before(2);
+        assert_eq!(leftmost.front().unwrap().pos, 2);
+    }
+
+    #[test]
+    fn mod_scheme() {
+        let lmers = hashed(&[1, 6, 0, 4, 8, 2]);
+        let mut q = HashedMinimizerQueue::with_scheme(ModMinimizer::new(4), 0);
+        for lmer in lmers {
+            q.push(lmer);
+        }
+        // 0, 4 and 8 are multiples of 4; then 2 is the smallest of the rest
+        assert_eq!(positions(&q), vec![2, 3, 4, 5]);
+        q.evict_before(3);
+        assert_eq!(q.front().unwrap().hash, 4);
+
+        let mut q = HashedMinimizerQueue::with_scheme(RightMin(ModMinimizer::new(4)), 0);
+        for lmer in hashed(&[4, 1, 4]) {
+            q.push(lmer);
+        }
+        assert_eq!(positions(&q), vec![2]);
+    }
+
+    #[test]
+    fn word_seq() {
+        // lex hashing: the minimizer is the smallest word
+        let state = LexHasherState::new(1);
+        let words = [3, 1, 2, 0, 0, 3, 3, 3];
+        let mmers: Vec<(u64, usize)> = minimizers_of_word_seq(words, 3, 1, &state).collect();
+        assert_eq!(mmers, vec![(1, 1), (0, 2), (0, 1), (0, 0), (0, 0), (3, 0)]);
+
+        let mmers: Vec<(u64, usize)> = minimizers_of_word_seq([3, 1], 3, 1, &state).collect();
+        assert!(mmers.is_empty());
+    }
+}
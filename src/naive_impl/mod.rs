@@ -1,19 +1,39 @@
 mod canonical_kmer;
 pub mod canonical_kmer_iterator;
+pub mod checked;
+pub mod counting;
+pub mod dbg;
+pub mod hpc_kmer_iterator;
 mod kmer;
+mod kmer128;
+pub mod kmer_iterator;
+pub mod kmer_vec;
+mod large_kmer;
+pub mod minimizer_queue;
+pub mod overlap;
+pub mod partition;
 
 pub mod hash;
 
-// Simple-sds does not compile on windows, so we make seq_vector an optional feature
-#[cfg(feature = "seq-vector")]
+// Simple-sds does not compile on windows, so it is an optional (default) feature;
+// without it, seq_vector falls back to a pure-Rust bit vector with the same API.
 pub mod seq_vector;
 
 // re-exports
-pub use canonical_kmer::{CanonicalKmer, MatchType};
+pub use canonical_kmer::{count_matches, CanonicalKey, CanonicalKmer, CanonicalSet, MatchType};
 pub use canonical_kmer_iterator::CanonicalKmerIterator;
-pub use kmer::Kmer;
+pub use checked::{string_to_word, AmbiguityPolicy, BufferTooSmall, EncodeError, KmerError};
+pub use counting::{DenseKmerCounter, KmerCounter};
+pub use hpc_kmer_iterator::HpcKmerIterator;
+pub use kmer::{Kmer, Orientation};
+pub use kmer128::{CanonicalKmer128, Kmer128};
+pub use kmer_iterator::{CanonicalKmerFromBytesIter, KmerFromBytesIter};
+pub use kmer_vec::KmerVec;
+pub use large_kmer::LargeKmer;
+pub use overlap::MergeError;
 
 pub use prelude::Base;
+pub use prelude::{decode_word, word_to_string};
 pub use prelude::{A, C, G, T};
 
 pub mod prelude {
@@ -77,6 +97,40 @@ pub mod prelude {
         }
     }
 
+    const DECODE_TABLE: [u8; 4] = [b'A', b'C', b'G', b'T'];
+
+    #[inline]
+    pub fn decode_binary_u8(b: Base) -> u8 {
+        DECODE_TABLE[(b & 0x03) as usize]
+    }
+
+    // decode the k bases packed in `word` as uppercase ASCII into
+    // the first k bytes of `buf`, without allocating.
+    pub fn decode_word(word: u64, k: u8, buf: &mut [u8]) {
+        let k = k as usize;
+        assert!(k <= 32, "kmers longer than 32 bases not supported");
+        assert!(
+            buf.len() >= k,
+            "buffer of length {} cannot hold {k} bases",
+            buf.len()
+        );
+
+        let mut w = word;
+        for c in buf[..k].iter_mut() {
+            *c = decode_binary_u8(w);
+            w >>= 2;
+        }
+    }
+
+    // the k bases packed in `word` as an uppercase string, the same as
+    // `Kmer::from_u64(word, k).to_string_upper()`
+    pub fn word_to_string(word: u64, k: u8) -> String {
+        let mut buf = vec![0; k as usize];
+        decode_word(word, k, &mut buf);
+        // only ACGT
+        String::from_utf8(buf).unwrap()
+    }
+
     #[inline]
     pub fn complement_base(b: Base) -> Base {
         // this is cool
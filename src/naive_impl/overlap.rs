@@ -0,0 +1,240 @@
+// Joining k-mers that overlap, e.g. along a unitig of a de Bruijn graph
+// where consecutive k-mers overlap by k - 1 bases.
+
+use super::seq_vector::SeqVector;
+use super::Kmer;
+
+// The error returned when two sequences cannot be joined on an overlap
+// of `o` bases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeError {
+    // the overlap is longer than one of the sequences
+    OverlapTooLong { o: usize, len: usize },
+    // the overlapping bases differ
+    Mismatch { o: usize },
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::OverlapTooLong { o, len } => {
+                write!(f, "overlap of {} bases longer than sequence of {}", o, len)
+            }
+            Self::Mismatch { o } => write!(f, "sequences do not overlap on {} bases", o),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+// the lowest `o` bases of `word`
+#[inline]
+fn prefix_word(word: u64, o: usize) -> u64 {
+    if o >= 32 {
+        word
+    } else {
+        word & ((1 << (2 * o)) - 1)
+    }
+}
+
+impl Kmer {
+    // number of leading bases shared by `self` and `other`, k-mers of
+    // different lengths are compared on their first min(k) bases. The
+    // first base is in the low bits, so this counts trailing zeros.
+    pub fn common_prefix_len(&self, other: &Kmer) -> usize {
+        let m = self.len().min(other.len());
+        let x = prefix_word(self.data ^ other.data, m);
+        if x == 0 {
+            m
+        } else {
+            x.trailing_zeros() as usize / 2
+        }
+    }
+
+    // number of trailing bases shared by `self` and `other`, k-mers of
+    // different lengths are compared on their last min(k) bases
+    pub fn common_suffix_len(&self, other: &Kmer) -> usize {
+        let m = self.len().min(other.len());
+        let a = self.data >> (2 * (self.len() - m));
+        let b = other.data >> (2 * (other.len() - m));
+        let x = prefix_word(a ^ b, m);
+        if x == 0 {
+            m
+        } else {
+            m - 1 - (63 - x.leading_zeros() as usize) / 2
+        }
+    }
+
+    // whether the last `o` bases of `self` are the first `o` bases of `other`
+    pub fn overlaps(&self, other: &Kmer, o: usize) -> bool {
+        if o > self.len() || o > other.len() {
+            return false;
+        }
+        if o == 0 {
+            return true;
+        }
+        let suffix = self.data >> (2 * (self.len() - o));
+        suffix == prefix_word(other.data, o)
+    }
+
+    // `self` followed by `other` without its first `o` bases, which must
+    // be the last `o` bases of `self`
+    pub fn merge(&self, other: &Kmer, o: usize) -> Result<SeqVector, MergeError> {
+        let len = self.len().min(other.len());
+        if o > len {
+            return Err(MergeError::OverlapTooLong { o, len });
+        }
+        if !self.overlaps(other, o) {
+            return Err(MergeError::Mismatch { o });
+        }
+
+        let mut sv = SeqVector::with_capacity(self.len() + other.len() - o);
+        sv.push_kmer(self);
+        if o < other.len() {
+            sv.push_word(other.data >> (2 * o), other.len() - o);
+        }
+        Ok(sv)
+    }
+}
+
+impl SeqVector {
+    // Appends `km` without its first `o` bases, which must be the last `o`
+    // bases of the vector. Nothing is appended on error.
+    pub fn extend_with_overlap(&mut self, km: &Kmer, o: usize) -> Result<(), MergeError> {
+        let len = self.len().min(km.len());
+        if o > len {
+            return Err(MergeError::OverlapTooLong { o, len });
+        }
+        if o > 0 && self.get_kmer_u64(self.len() - o, o) != prefix_word(km.data, o) {
+            return Err(MergeError::Mismatch { o });
+        }
+
+        if o < km.len() {
+            self.push_word(km.data >> (2 * o), km.len() - o);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::random_string;
+
+    #[test]
+    fn overlaps() {
+        let a = Kmer::from("acgta");
+        assert!(a.overlaps(&Kmer::from("cgtac"), 4));
+        assert!(a.overlaps(&Kmer::from("tacc"), 2));
+        assert!(a.overlaps(&Kmer::from("ggg"), 0));
+        assert!(a.overlaps(&a, 5));
+        assert!(!a.overlaps(&Kmer::from("cgaaa"), 4));
+        assert!(!a.overlaps(&Kmer::from("acgta"), 6));
+
+        let a = Kmer::from(random_string(32, 1).as_str());
+        let b = Kmer::from(&random_string(33, 1).as_bytes()[1..]);
+        assert!(a.overlaps(&b, 31));
+    }
+
+    // a k-mer of 1 to 32 bases, from a random word and length
+    fn kmer_of(x: u64, k: u8) -> Kmer {
+        Kmer::from_u64(x, k % 32 + 1)
+    }
+
+    #[quickcheck]
+    fn common_prefix_suffix_len(x: u64, y: u64, kx: u8, ky: u8, shared: u8) -> bool {
+        let a = kmer_of(x, kx);
+        let s = a.to_string();
+        // share some bases with `a` at both ends, not to only test short matches
+        let shared = shared as usize % (s.len() + 1);
+        let t = kmer_of(y, ky).to_string();
+        let t = &t[..t.len().min(32 - shared)];
+        let b = Kmer::from(format!("{}{}", &s[..shared], t).as_str());
+        let c = Kmer::from(format!("{}{}", t, &s[s.len() - shared..]).as_str());
+
+        let (sa, sb, sc) = (s.as_bytes(), b.to_string(), c.to_string());
+        let prefix = sa
+            .iter()
+            .zip(sb.bytes())
+            .take_while(|(p, q)| **p == *q)
+            .count();
+        let suffix = sa
+            .iter()
+            .rev()
+            .zip(sc.bytes().rev())
+            .take_while(|(p, q)| **p == *q)
+            .count();
+
+        a.common_prefix_len(&b) == prefix
+            && b.common_prefix_len(&a) == prefix
+            && a.common_suffix_len(&c) == suffix
+            && c.common_suffix_len(&a) == suffix
+            && a.common_prefix_len(&a) == a.len()
+            && a.common_suffix_len(&a) == a.len()
+    }
+
+    #[quickcheck]
+    fn overlaps_matches_strings(x: u64, y: u64, kx: u8, ky: u8, o: u8) -> bool {
+        let (a, b) = (kmer_of(x, kx), kmer_of(y, ky));
+        let (sa, sb) = (a.to_string(), b.to_string());
+        let o = o as usize % (a.len().min(b.len()) + 1);
+        // and a k-mer overlapping `a` on `o` bases
+        let next = Kmer::from(format!("{}{}", &sa[sa.len() - o..], &sb[o..]).as_str());
+
+        a.overlaps(&b, o) == (sa[sa.len() - o..] == sb[..o]) && a.overlaps(&next, o)
+    }
+
+    #[test]
+    fn common_prefix_suffix() {
+        let a = Kmer::from("GATTACA");
+        assert_eq!(a.common_prefix_len(&Kmer::from("GATCACA")), 3);
+        assert_eq!(a.common_suffix_len(&Kmer::from("GATCACA")), 3);
+        assert_eq!(a.common_suffix_len(&Kmer::from("TACA")), 4);
+        assert_eq!(a.common_prefix_len(&Kmer::from("GA")), 2);
+        assert_eq!(a.common_prefix_len(&Kmer::from("TATTACA")), 0);
+        assert_eq!(a.common_suffix_len(&Kmer::from("GATTACC")), 0);
+    }
+
+    #[test]
+    fn chain_kmers() {
+        for k in [2, 5, 21, 31, 32] {
+            let s = random_string(k + 99, k as u64);
+            let kmers: Vec<Kmer> = s
+                .as_bytes()
+                .windows(k)
+                .map(|w| Kmer::from_bytes_checked(w).unwrap())
+                .collect();
+            assert_eq!(kmers.len(), 100);
+
+            let mut sv = kmers[0].merge(&kmers[1], k - 1).unwrap();
+            assert_eq!(sv.to_string(), s[..k + 1]);
+            for km in &kmers[2..] {
+                assert!(sv.get_kmer(sv.len() - k + 1, k - 1).overlaps(km, k - 1));
+                sv.extend_with_overlap(km, k - 1).unwrap();
+            }
+            assert_eq!(sv.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn merge_errors() {
+        let a = Kmer::from("acgta");
+        let b = Kmer::from("gtacc");
+        assert_eq!(a.merge(&b, 4), Err(MergeError::Mismatch { o: 4 }));
+        assert_eq!(
+            a.merge(&Kmer::from("ta"), 3),
+            Err(MergeError::OverlapTooLong { o: 3, len: 2 })
+        );
+        assert_eq!(a.merge(&b, 0).unwrap().to_string(), "ACGTAGTACC");
+        assert_eq!(a.merge(&b, 3).unwrap().to_string(), "ACGTACC");
+
+        let mut sv = SeqVector::from("ACGTA".to_string());
+        assert_eq!(
+            sv.extend_with_overlap(&b, 4),
+            Err(MergeError::Mismatch { o: 4 })
+        );
+        assert_eq!(sv.to_string(), "ACGTA");
+        sv.extend_with_overlap(&b, 3).unwrap();
+        assert_eq!(sv.to_string(), "ACGTACC");
+    }
+}
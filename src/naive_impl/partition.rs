@@ -0,0 +1,207 @@
+// Partitioning the canonical k-mers of a sequence into buckets by their
+// minimizer, as done by KMC-like counters: every super-k-mer goes to the
+// bucket of its minimizer, `hash(mmer_word) % n_buckets`, so each k-mer of
+// the sequence lands in exactly one bucket, and equal canonical k-mers
+// (which have the same canonical minimizer) land in the same bucket. The
+// buckets can then be counted independently, e.g. after spilling them to
+// one file each.
+
+use std::hash::BuildHasher;
+
+use super::hash::hash_one;
+use super::seq_vector::minimizers::{CanonicalSuperKmerIterator, CanonicalSuperKmerOcc};
+use super::seq_vector::SeqVectorSlice;
+
+// Stream the canonical super-k-mers of `sv` together with the bucket of
+// their minimizer, `hash(mmer_word) % n_buckets`, e.g. to spill them to one
+// file per bucket when building an index in external memory. The bucket only
+// depends on the minimizer word and `hash_seed`, so sequences processed
+// independently with the same (deterministic) hasher agree on it.
+pub fn for_each_super_kmer_bucketed<T, F>(
+    sv: &SeqVectorSlice,
+    k: usize,
+    w: usize,
+    hash_seed: T,
+    n_buckets: usize,
+    mut f: F,
+) where
+    T: BuildHasher + Clone,
+    F: FnMut(usize, CanonicalSuperKmerOcc),
+{
+    assert!(n_buckets > 0, "need at least one bucket");
+    let n_buckets = n_buckets as u64;
+    for occ in CanonicalSuperKmerIterator::new(sv.clone(), k, w, hash_seed.clone()) {
+        let bucket = hash_one(&hash_seed, occ.mmer_word()) % n_buckets;
+        f(bucket as usize, occ);
+    }
+}
+
+// Same as `for_each_super_kmer_bucketed`, collecting each bucket in order.
+pub fn partition_super_kmers<T>(
+    sv: &SeqVectorSlice,
+    k: usize,
+    w: usize,
+    hash_seed: T,
+    n_buckets: usize,
+) -> Vec<Vec<CanonicalSuperKmerOcc>>
+where
+    T: BuildHasher + Clone,
+{
+    let mut buckets = vec![Vec::new(); n_buckets];
+    for_each_super_kmer_bucketed(sv, k, w, hash_seed, n_buckets, |bucket, occ| {
+        buckets[bucket].push(occ)
+    });
+    buckets
+}
+
+// Calls `f` on the bucket, occurrence and bases of every canonical
+// super-k-mer of `sv`, in sequence order. The bases are a slice of `sv`,
+// not a copy of them.
+pub fn partition_by_minimizer<'a, T, F>(
+    sv: &SeqVectorSlice<'a>,
+    k: usize,
+    w: usize,
+    hash_seed: T,
+    n_buckets: usize,
+    mut f: F,
+) where
+    T: BuildHasher + Clone,
+    F: FnMut(usize, &CanonicalSuperKmerOcc, &SeqVectorSlice<'a>),
+{
+    for_each_super_kmer_bucketed(sv, k, w, hash_seed, n_buckets, |bucket, occ| {
+        let seq = sv.slice(occ.start_pos(), occ.end_pos(k));
+        f(bucket, &occ, &seq)
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::super::hash::{LexHasherState, NtHashState, SeededState};
+    use super::super::seq_vector::SeqVector;
+    use super::*;
+    use crate::test_utils::random_seq;
+
+    fn check_partition<T>(sv: &SeqVector, k: usize, w: usize, hash_seed: T, n_buckets: usize)
+    where
+        T: BuildHasher + Clone,
+    {
+        let sv = sv.as_slice();
+        let buckets = partition_super_kmers(&sv, k, w, hash_seed, n_buckets);
+        assert_eq!(buckets.len(), n_buckets);
+
+        // re-expanding the buckets gives every k-mer of the sequence once
+        let mut covered = vec![0; (sv.len() + 1).saturating_sub(k)];
+        let mut expanded = Vec::new();
+        let mut bucket_of = HashMap::new();
+        for (b, bucket) in buckets.iter().enumerate() {
+            for occ in bucket {
+                let seq = sv.slice(occ.start_pos(), occ.end_pos(k));
+                assert_eq!(seq.iter_canonical_kmers(k).len(), occ.n_kmers() as usize);
+                for (i, km) in seq.iter_canonical_kmers(k).enumerate() {
+                    covered[occ.start_pos() + i] += 1;
+                    let word = km.get_canonical_word();
+                    expanded.push(word);
+                    // equal k-mers share a bucket
+                    assert_eq!(*bucket_of.entry(word).or_insert(b), b);
+                }
+            }
+        }
+        assert!(covered.iter().all(|&n| n == 1));
+
+        let mut expected: Vec<u64> = sv
+            .iter_canonical_kmers(k)
+            .map(|km| km.get_canonical_word())
+            .collect();
+        expected.sort_unstable();
+        expanded.sort_unstable();
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn every_kmer_in_one_bucket() {
+        // with repeats, so that the same k-mers show up in several super-k-mers
+        let unit = random_seq(700, 3);
+        let mut seq = unit.clone();
+        seq.extend_from_slice(&random_seq(3000, 4));
+        seq.extend_from_slice(&unit);
+        let sv = SeqVector::from(seq.as_slice());
+
+        for (k, w) in [(11, 5), (21, 11), (31, 19), (32, 32)] {
+            for n_buckets in [1, 7, 64] {
+                check_partition(&sv, k, w, SeededState(k as u64), n_buckets);
+            }
+            check_partition(&sv, k, w, NtHashState::new(w), 16);
+        }
+    }
+
+    #[test]
+    fn slices_of_the_sequence() {
+        let seq = random_seq(2000, 8);
+        let sv = SeqVector::from(seq.as_slice());
+        let (k, w) = (21, 9);
+        let mut n_kmers = 0;
+        let mut last_end = 0;
+        partition_by_minimizer(&sv.as_slice(), k, w, SeededState(1), 5, |b, occ, slice| {
+            assert!(b < 5);
+            // in sequence order, consecutive super-k-mers overlap by k - 1 bases
+            assert!(n_kmers == 0 || occ.start_pos() + k - 1 == last_end);
+            last_end = occ.end_pos(k);
+            n_kmers += occ.n_kmers() as usize;
+            assert_eq!(
+                slice.to_string().as_bytes(),
+                &seq[occ.start_pos()..occ.end_pos(k)]
+            );
+        });
+        assert_eq!(n_kmers, seq.len() - k + 1);
+
+        // a slice of a sequence is partitioned on its own positions
+        let part = sv.slice(100, 600);
+        let mut n = 0;
+        partition_by_minimizer(&part, k, w, SeededState(1), 5, |_, occ, slice| {
+            n += occ.n_kmers() as usize;
+            assert_eq!(
+                slice.to_string().as_bytes(),
+                &seq[100 + occ.start_pos()..100 + occ.end_pos(k)]
+            );
+        });
+        assert_eq!(n, 500 - k + 1);
+    }
+
+    #[test]
+    fn bucketed_super_kmers() {
+        let s = b"ACGTTGCAAGGCTTACAGATTTAGGACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+        let sv = SeqVector::from(s);
+        let (k, w) = (11, 5);
+
+        for n_buckets in [1, 3, 16] {
+            let state = NtHashState::new(w);
+            let buckets = partition_super_kmers(&sv.as_slice(), k, w, state.clone(), n_buckets);
+            assert_eq!(buckets.len(), n_buckets);
+
+            for (b, bucket) in buckets.iter().enumerate() {
+                for occ in bucket {
+                    let h = hash_one(&state, occ.mmer_word());
+                    assert_eq!(h % n_buckets as u64, b as u64);
+                }
+            }
+
+            let mut union: Vec<CanonicalSuperKmerOcc> = buckets.into_iter().flatten().collect();
+            union.sort_by_key(|occ| occ.start_pos());
+            let occs: Vec<CanonicalSuperKmerOcc> =
+                CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, state).collect();
+            assert_eq!(union, occs);
+        }
+
+        // the same minimizer lands in the same bucket from another sequence
+        let state = LexHasherState::new(w);
+        let mut seen = HashMap::new();
+        for seq in [&s[..40], &s[20..]] {
+            let sv = SeqVector::from(seq);
+            for_each_super_kmer_bucketed(&sv.as_slice(), k, w, state.clone(), 7, |b, occ| {
+                assert_eq!(*seen.entry(occ.mmer_word()).or_insert(b), b);
+            });
+        }
+    }
+}
@@ -1,31 +1,111 @@
 use std::hash::BuildHasher;
+use std::io::{Read, Write};
 
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "seq-vector")]
 use simple_sds::int_vector::IntVector;
+#[cfg(feature = "seq-vector")]
 use simple_sds::ops::Vector;
+#[cfg(feature = "seq-vector")]
 use simple_sds::raw_vector::{AccessRaw, PushRaw, RawVector};
+#[cfg(feature = "seq-vector")]
+use simple_sds::serialize::Serialize as SdsSerialize;
 
-use crate::naive_impl::Kmer;
+use crate::naive_impl::checked::{
+    check_buffer, encode_word_lossy, find_invalid, resolve_ambiguous,
+};
+use crate::naive_impl::hash::SeqVecCanonicalHashIterator;
+use crate::naive_impl::kmer::{entropy_of_counts, MASK_TABLE};
+use crate::naive_impl::prelude::{encode_binary_u8, is_valid_nuc};
+use crate::naive_impl::{
+    decode_word, AmbiguityPolicy, Base, BufferTooSmall, CanonicalKmer, Kmer, KmerError,
+};
+#[cfg(feature = "seq-vector")]
 use simple_sds::serde_compat;
 
-use self::minimizers::SeqVecMinimizerIter;
+// without simple-sds, fall back to a Vec<u64> backed bit vector
+#[cfg(not(feature = "seq-vector"))]
+use self::raw_vector::{serde_compat, AccessRaw, PushRaw, RawVector, Serialize as SdsSerialize};
 
+use self::minimizers::{
+    CanonicalMinimizerIter, CanonicalSuperKmerIterator, MappedMinimizer, SeqVecMinimizerIter,
+    SuperKmerIterator,
+};
+use self::simd::encode_word_simd;
+
+pub mod distinct;
 pub mod minimizers;
+#[cfg(feature = "rayon")]
+pub mod par_super_kmers;
+#[cfg(not(feature = "seq-vector"))]
+mod raw_vector;
+pub mod rc_slice;
+pub mod seq_vector_n;
+mod simd;
+
+pub use self::rc_slice::RcSeqVectorSlice;
+pub use self::seq_vector_n::SeqVectorN;
 
 #[allow(non_camel_case_types)]
 type km_size_t = usize;
 
+// bases decoded at a time when writing out ASCII
+const ASCII_CHUNK_LEN: usize = 1 << 16;
+
+// Bases are packed 32 per u64 word, with the same 2-bit codes as `Kmer`
+// (A = 0, C = 1, G = 2, T = 3): base i lives at bits 2i % 64..2i % 64 + 2 of
+// word i / 32, the first base of a word in its lowest bits. This is the
+// layout of sdsl-lite's int vectors of width 2, e.g. pufferfish's sequences.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct SeqVector {
     #[serde(with = "serde_compat")]
     data: RawVector,
 }
 
+// A view of `len` bases from `start_pos` on, over the words of a
+// `SeqVector` or over words packed the same way that are owned elsewhere,
+// e.g. memory mapped from an index.
 #[derive(Clone, Debug)]
 pub struct SeqVectorSlice<'a> {
     len: usize,
     start_pos: usize,
-    slice: &'a SeqVector,
+    words: &'a [u64],
+}
+
+// A `SeqVector` borrowed from its words, see `from_word_slice`
+pub type SeqVectorBorrowed<'a> = SeqVectorSlice<'a>;
+
+// the `width` <= 64 bits at `bit_offset` of `words`, lowest order bits first
+#[inline]
+fn bits_of_words(words: &[u64], bit_offset: usize, width: usize) -> u64 {
+    let (i, o) = (bit_offset / 64, bit_offset % 64);
+    let mut bits = words[i] >> o;
+    if o + width > 64 {
+        bits |= words[i + 1] << (64 - o);
+    }
+    if width < 64 {
+        bits & ((1 << width) - 1)
+    } else {
+        bits
+    }
+}
+
+impl<'a> SeqVectorSlice<'a> {
+    // A view of the first `len_bases` bases packed in `words` (see
+    // `SeqVector` for the layout), without copying them.
+    pub fn from_word_slice(words: &'a [u64], len_bases: usize) -> Self {
+        assert!(
+            len_bases <= words.len() * 32,
+            "{} words cannot hold {} bases",
+            words.len(),
+            len_bases
+        );
+        Self {
+            len: len_bases,
+            start_pos: 0,
+            words,
+        }
+    }
 }
 
 impl SeqVectorSlice<'_> {
@@ -45,31 +125,223 @@ impl SeqVectorSlice<'_> {
     pub fn get_kmer_u64(&self, pos: usize, k: km_size_t) -> u64 {
         assert!(pos < self.len());
         let pos = pos + self.start_pos;
-        self.slice.get_kmer_u64(pos, k)
+        bits_of_words(self.words, pos * 2, k * 2)
     }
 
     pub fn get_base(&self, pos: usize) -> u64 {
         self.get_kmer_u64(pos, 1)
     }
 
+    // `start` and `end` are relative to this slice, not to the underlying
+    // vector
     pub fn slice(&self, start: usize, end: usize) -> Self {
+        assert!(start <= end);
         assert!(end <= self.len());
         Self {
             len: end - start,
-            start_pos: start,
-            slice: self.slice,
+            start_pos: self.start_pos + start,
+            words: self.words,
+        }
+    }
+
+    // A new vector holding a copy of the bases of the slice
+    pub fn to_owned(&self) -> SeqVector {
+        let mut sv = SeqVector::with_capacity(self.len());
+        sv.extend_from_slice(self);
+        sv
+    }
+
+    // A new vector holding the reverse complement of the slice, built 32
+    // bases at a time from its end
+    pub fn reverse_complement(&self) -> SeqVector {
+        let mut sv = SeqVector::with_capacity(self.len());
+        let mut end = self.len();
+        while end > 0 {
+            let width = end.min(32);
+            let word = self.get_kmer_u64(end - width, width);
+            unsafe {
+                sv.data.push_int(
+                    Kmer::get_reverse_complement_word(word, width as u8),
+                    width * 2,
+                );
+            }
+            end -= width;
         }
+        sv
     }
 
     pub fn iter_kmers(&self, k: km_size_t) -> SeqVecKmerIterator {
-        SeqVecKmerIterator {
-            k,
-            len: self.len - k + 1,
-            pos: 0,
-            seq: self.clone(),
+        SeqVecKmerIterator::new(self.clone(), k)
+    }
+
+    // Appends the words of all the k-mers of the slice to `out`, in order:
+    // the same as `get_kmer_u64(pos, k)` for every `pos`, rolling the k-mer
+    // over the bases read 32 at a time.
+    pub fn fill_kmer_words(&self, k: km_size_t, out: &mut Vec<u64>) {
+        assert!(
+            (1..=32).contains(&k),
+            "k must be between 1 and 32 (inclusive), got {}",
+            k
+        );
+        if self.len() < k {
+            return;
+        }
+        out.reserve(self.len() - k + 1);
+
+        let hi = 2 * (k - 1);
+        let mut km = self.get_kmer_u64(0, k);
+        out.push(km);
+        let mut pos = k;
+        while pos < self.len() {
+            let n = (self.len() - pos).min(32);
+            let mut bases = self.get_kmer_u64(pos, n);
+            for _ in 0..n {
+                km = (km >> 2) | ((bases & 0x03) << hi);
+                bases >>= 2;
+                out.push(km);
+            }
+            pos += n;
+        }
+    }
+
+    // Calls `f` on consecutive chunks of the uppercase ASCII sequence, of
+    // `ASCII_CHUNK_LEN` bases except for the last one, decoding a word of 32
+    // bases at a time.
+    fn for_each_ascii_chunk<F>(&self, mut f: F) -> std::io::Result<()>
+    where
+        F: FnMut(&[u8]) -> std::io::Result<()>,
+    {
+        let mut buf = vec![0u8; ASCII_CHUNK_LEN.min(self.len())];
+        let mut pos = 0;
+        while pos < self.len() {
+            let chunk_len = buf.len().min(self.len() - pos);
+            let n = self
+                .slice(pos, pos + chunk_len)
+                .decode_into(&mut buf)
+                .unwrap();
+            f(&buf[..n])?;
+            pos += chunk_len;
+        }
+        Ok(())
+    }
+
+    // Writes the uppercase bases of the slice at the start of `buf`, a word
+    // of 32 bases at a time, and returns how many were written. Fails if
+    // `buf` is shorter than the slice, leaving it untouched.
+    pub fn decode_into(&self, buf: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        check_buffer(buf, self.len())?;
+        for (i, out) in buf[..self.len()].chunks_mut(32).enumerate() {
+            let word = self.get_kmer_u64(i * 32, out.len());
+            decode_word(word, out.len() as u8, out);
+        }
+        Ok(self.len())
+    }
+
+    pub fn write_ascii<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.for_each_ascii_chunk(|chunk| w.write_all(chunk))
+    }
+
+    // the number of `A`, `C`, `G` and `T`, counted a word at a time
+    pub fn base_counts(&self) -> [u64; 4] {
+        let mut counts = [0; 4];
+        for pos in (0..self.len()).step_by(32) {
+            let n = 32.min(self.len() - pos);
+            let word_counts = Kmer::base_counts_word(self.get_kmer_u64(pos, n), n);
+            for (c, wc) in counts.iter_mut().zip(word_counts) {
+                *c += wc as u64;
+            }
+        }
+        counts
+    }
+
+    // the fraction of `C`s and `G`s, 0 for an empty slice
+    pub fn gc_content(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let [_, c, g, _] = self.base_counts();
+        (c + g) as f64 / self.len() as f64
+    }
+
+    pub fn shannon_entropy(&self) -> f64 {
+        entropy_of_counts(&self.base_counts())
+    }
+
+    pub fn to_ascii_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.len());
+        self.for_each_ascii_chunk(|chunk| {
+            out.extend_from_slice(chunk);
+            Ok(())
+        })
+        .unwrap();
+        out
+    }
+
+    // A FASTA record `>name` with the sequence wrapped at `line_width` bases
+    pub fn write_fasta<W: Write>(
+        &self,
+        name: &str,
+        w: &mut W,
+        line_width: usize,
+    ) -> std::io::Result<()> {
+        assert!(line_width > 0, "line width must be positive");
+        writeln!(w, ">{}", name)?;
+
+        // bases written on the current line
+        let mut col = 0;
+        self.for_each_ascii_chunk(|mut chunk| {
+            while !chunk.is_empty() {
+                let n = chunk.len().min(line_width - col);
+                w.write_all(&chunk[..n])?;
+                chunk = &chunk[n..];
+                col += n;
+                if col == line_width {
+                    w.write_all(b"\n")?;
+                    col = 0;
+                }
+            }
+            Ok(())
+        })?;
+
+        if col > 0 {
+            w.write_all(b"\n")?;
         }
+        Ok(())
+    }
+
+    pub fn iter_canonical_kmers(&self, k: km_size_t) -> SeqVecCanonicalKmerIterator {
+        SeqVecCanonicalKmerIterator::new(self.clone(), k)
+    }
+
+    // `hash_one(state, km.get_canonical_word())` for the canonical k-mers
+    // `km` of the slice, rolling the words instead of building the k-mers
+    #[inline]
+    pub fn iter_canonical_hashes<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        state: T,
+    ) -> SeqVecCanonicalHashIterator<'_, T> {
+        SeqVecCanonicalHashIterator::new(self.clone(), k, state)
+    }
+
+    // k-mers with their position in the underlying SeqVector
+    pub fn iter_kmers_with_pos(&self, k: km_size_t) -> impl Iterator<Item = (Kmer, usize)> + '_ {
+        let start = self.start_pos;
+        self.iter_kmers_with_local_pos(k)
+            .map(move |(km, pos)| (km, start + pos))
+    }
+
+    // k-mers with their position in this slice
+    pub fn iter_kmers_with_local_pos(
+        &self,
+        k: km_size_t,
+    ) -> impl Iterator<Item = (Kmer, usize)> + '_ {
+        self.iter_kmers(k).enumerate().map(|(pos, km)| (km, pos))
     }
 
+    // minimizer positions are relative to the slice, see
+    // `iter_minimizers_with_global_pos` for positions in the SeqVector.
+    // Use `LexHasherState::new(w)` for lexicographic minimizers.
     pub fn iter_minimizers<T: BuildHasher>(
         &self,
         k: km_size_t,
@@ -78,6 +350,61 @@ impl SeqVectorSlice<'_> {
     ) -> SeqVecMinimizerIter<T> {
         SeqVecMinimizerIter::new(self.clone(), k, w, build_hasher)
     }
+
+    pub fn iter_minimizers_with_global_pos<'s, T: BuildHasher + 's>(
+        &'s self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> impl Iterator<Item = MappedMinimizer> + 's {
+        let start = self.start_pos;
+        self.iter_minimizers(k, w, build_hasher).map(move |mmer| {
+            MappedMinimizer::new_stranded(mmer.as_u64(), start + mmer.pos, mmer.is_fw())
+        })
+    }
+
+    pub fn iter_canonical_minimizers<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> CanonicalMinimizerIter<T> {
+        CanonicalMinimizerIter::new(self.clone(), k, w, build_hasher)
+    }
+
+    // runs of consecutive k-mers sharing their forward strand minimizer,
+    // positions relative to the slice
+    pub fn iter_super_kmers<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> SuperKmerIterator<T> {
+        SuperKmerIterator::new(self.clone(), k, w, build_hasher)
+    }
+
+    // runs of consecutive k-mers sharing their canonical minimizer
+    pub fn iter_canonical_super_kmers<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> CanonicalSuperKmerIterator<T> {
+        CanonicalSuperKmerIterator::new(self.clone(), k, w, build_hasher)
+    }
+
+    pub fn iter_canonical_minimizers_with_global_pos<'s, T: BuildHasher + 's>(
+        &'s self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> impl Iterator<Item = MappedMinimizer> + 's {
+        let start = self.start_pos;
+        self.iter_canonical_minimizers(k, w, build_hasher)
+            .map(move |mmer| {
+                MappedMinimizer::new_stranded(mmer.as_u64(), start + mmer.pos, mmer.is_fw())
+            })
+    }
 }
 
 impl SeqVector {
@@ -106,7 +433,32 @@ impl SeqVector {
         SeqVectorSlice {
             start_pos: 0,
             len: self.len(),
-            slice: self,
+            words: self.as_words(),
+        }
+    }
+
+    // The packed words of the vector, see `SeqVector` for the layout. The
+    // unused bits of the last word are 0.
+    pub fn as_words(&self) -> &[u64] {
+        self.data.as_ref()
+    }
+
+    // The first `len_bases` bases packed in `words`, see `SeqVector` for the
+    // layout. Words past those bases are dropped, and the unused bits of the
+    // last word cleared.
+    pub fn from_words(mut words: Vec<u64>, len_bases: usize) -> Self {
+        assert!(
+            len_bases <= words.len() * 32,
+            "{} words cannot hold {} bases",
+            words.len(),
+            len_bases
+        );
+        words.truncate((len_bases + 31) / 32);
+        if len_bases % 32 != 0 {
+            *words.last_mut().unwrap() &= (1 << (len_bases % 32 * 2)) - 1;
+        }
+        Self {
+            data: RawVector::from_parts(len_bases * 2, words),
         }
     }
 
@@ -115,14 +467,80 @@ impl SeqVector {
     }
 
     pub fn iter_kmers(&self, k: km_size_t) -> SeqVecKmerIterator {
-        SeqVecKmerIterator {
-            k,
-            len: self.len() - k + 1,
-            pos: 0,
-            seq: self.as_slice(),
-        }
+        SeqVecKmerIterator::new(self.as_slice(), k)
+    }
+
+    // Streams the sequence as uppercase ASCII, without building a `String`
+    // of the whole sequence.
+    pub fn write_ascii<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.as_slice().write_ascii(w)
+    }
+
+    // `decode_into` on the bases in `start..end`, which must be in the vector
+    pub fn decode_range_into(
+        &self,
+        start: usize,
+        end: usize,
+        buf: &mut [u8],
+    ) -> Result<usize, BufferTooSmall> {
+        self.slice(start, end).decode_into(buf)
+    }
+
+    pub fn to_ascii_vec(&self) -> Vec<u8> {
+        self.as_slice().to_ascii_vec()
+    }
+
+    pub fn base_counts(&self) -> [u64; 4] {
+        self.as_slice().base_counts()
+    }
+
+    pub fn gc_content(&self) -> f64 {
+        self.as_slice().gc_content()
+    }
+
+    // the number of `A`, `C`, `G` and `T` in `start..end`, counted a word
+    // at a time, see `SeqVectorSlice::base_counts`
+    pub fn count_bases_in_range(&self, start: usize, end: usize) -> [u64; 4] {
+        self.slice(start, end).base_counts()
+    }
+
+    // the fraction of `C`s and `G`s in `start..end`, 0 for an empty range
+    pub fn gc_fraction(&self, start: usize, end: usize) -> f64 {
+        self.slice(start, end).gc_content()
+    }
+
+    pub fn shannon_entropy(&self) -> f64 {
+        self.as_slice().shannon_entropy()
+    }
+
+    pub fn write_fasta<W: Write>(
+        &self,
+        name: &str,
+        w: &mut W,
+        line_width: usize,
+    ) -> std::io::Result<()> {
+        self.as_slice().write_fasta(name, w, line_width)
+    }
+
+    pub fn iter_kmers_with_pos(&self, k: km_size_t) -> impl Iterator<Item = (Kmer, usize)> + '_ {
+        self.iter_kmers(k).enumerate().map(|(pos, km)| (km, pos))
+    }
+
+    pub fn iter_canonical_kmers(&self, k: km_size_t) -> SeqVecCanonicalKmerIterator {
+        SeqVecCanonicalKmerIterator::new(self.as_slice(), k)
     }
 
+    pub fn iter_canonical_hashes<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        state: T,
+    ) -> SeqVecCanonicalHashIterator<'_, T> {
+        SeqVecCanonicalHashIterator::new(self.as_slice(), k, state)
+    }
+
+    // `build_hasher` ranks the w-mers, e.g. `LexHasherState::new(w)` for
+    // lexicographic minimizers, see `SeqVecMinimizerIter::with_nthash` for
+    // rolling ntHash
     pub fn iter_minimizers<T: BuildHasher>(
         &self,
         k: km_size_t,
@@ -132,18 +550,173 @@ impl SeqVector {
         SeqVecMinimizerIter::new(self.as_slice(), k, w, build_hasher)
     }
 
+    pub fn iter_canonical_minimizers<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> CanonicalMinimizerIter<T> {
+        CanonicalMinimizerIter::new(self.as_slice(), k, w, build_hasher)
+    }
+
+    pub fn iter_super_kmers<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> SuperKmerIterator<T> {
+        SuperKmerIterator::new(self.as_slice(), k, w, build_hasher)
+    }
+
+    pub fn fill_kmer_words(&self, k: km_size_t, out: &mut Vec<u64>) {
+        self.as_slice().fill_kmer_words(k, out)
+    }
+
+    pub fn iter_canonical_super_kmers<T: BuildHasher>(
+        &self,
+        k: km_size_t,
+        w: km_size_t,
+        build_hasher: T,
+    ) -> CanonicalSuperKmerIterator<T> {
+        CanonicalSuperKmerIterator::new(self.as_slice(), k, w, build_hasher)
+    }
+
     pub fn with_capacity(len: usize) -> Self {
         Self {
             data: RawVector::with_capacity(len * 2),
         }
     }
 
+    // `bytes` must only hold `ACGTacgt`, see `push_chars_checked` and
+    // `push_chars_lossy` for other input
     pub fn push_chars(&mut self, bytes: &[u8]) {
+        #[cfg(debug_assertions)]
+        if let Err(e) = find_invalid(bytes) {
+            panic!("{}", e);
+        }
+        self.push_chars_with(bytes, encode_word_simd)
+    }
+
+    // Like `push_chars`, but returns an error holding the position (in `bytes`)
+    // of the first invalid base rather than panicking. Nothing is pushed on error.
+    pub fn push_chars_checked(&mut self, bytes: &[u8]) -> Result<(), KmerError> {
+        find_invalid(bytes)?;
+        self.push_chars(bytes);
+        Ok(())
+    }
+
+    // Like `push_chars`, but any invalid base is deliberately encoded as `A`.
+    // Returns the number of bases so replaced.
+    pub fn push_chars_lossy(&mut self, bytes: &[u8]) -> usize {
+        self.push_chars_with(bytes, encode_word_lossy);
+        bytes
+            .iter()
+            .filter(|&&c| !is_valid_nuc(encode_binary_u8(c)))
+            .count()
+    }
+
+    // Append already encoded bases, without going through ASCII.
+    pub fn push_base(&mut self, b: Base) {
+        assert!(b < 4, "invalid base {}", b);
+        unsafe {
+            self.data.push_int(b, 2);
+        }
+    }
+
+    pub fn push_kmer(&mut self, km: &Kmer) {
+        self.push_word(km.data, km.len());
+    }
+
+    // append the `n_bases` lowest order bases of `word`
+    pub fn push_word(&mut self, word: u64, n_bases: usize) {
+        assert!(n_bases <= 32);
+        unsafe {
+            self.data.push_int(word, n_bases * 2);
+        }
+    }
+
+    // overwrite the bases at `pos..pos + km.len()` with `km`
+    pub fn set_kmer(&mut self, pos: usize, km: &Kmer) {
+        self.set_kmer_u64(pos, km.data, km.len());
+    }
+
+    // write the k bases packed in `word` at `pos`, the inverse of
+    // `get_kmer_u64`; the bits of `word` above the k bases are ignored
+    pub fn set_kmer_u64(&mut self, pos: usize, word: u64, k: km_size_t) {
+        assert!(k <= 32, "kmers longer than 32 bases not supported");
+        assert!(pos + k <= self.len());
+        unsafe {
+            self.data.set_int(pos * 2, word & MASK_TABLE[k], k * 2);
+        }
+    }
+
+    pub fn set_base(&mut self, pos: usize, b: Base) {
+        assert!(b < 4, "invalid base {}", b);
+        assert!(pos < self.len());
+        unsafe {
+            self.data.set_int(pos * 2, b, 2);
+        }
+    }
+
+    pub fn reverse_complement(&self) -> SeqVector {
+        self.as_slice().reverse_complement()
+    }
+
+    // Reverse complement the bases in `start..end` in place. Up to 32 bases
+    // are read at once from both ends of the range, reverse complemented as
+    // words and swapped, so this costs O((end - start) / 32) word operations.
+    pub fn reverse_complement_range(&mut self, start: usize, end: usize) {
+        assert!(start <= end && end <= self.len());
+        let (mut lo, mut hi) = (start, end);
+
+        while lo < hi {
+            let n = hi - lo;
+            if n <= 32 {
+                // what is left fits in a word
+                let w = self.get_kmer_u64(lo, n);
+                unsafe {
+                    self.data
+                        .set_int(lo * 2, Kmer::get_reverse_complement_word(w, n as u8), n * 2);
+                }
+                break;
+            }
+
+            // the left and right parts swapped at this step
+            let (a, b) = if n >= 64 {
+                (32, 32)
+            } else {
+                (n / 2, n - n / 2)
+            };
+
+            let left = self.get_kmer_u64(lo, a);
+            let right = self.get_kmer_u64(hi - b, b);
+            unsafe {
+                self.data.set_int(
+                    (hi - a) * 2,
+                    Kmer::get_reverse_complement_word(left, a as u8),
+                    a * 2,
+                );
+                self.data.set_int(
+                    lo * 2,
+                    Kmer::get_reverse_complement_word(right, b as u8),
+                    b * 2,
+                );
+            }
+
+            lo += b;
+            hi -= a;
+        }
+    }
+
+    fn push_chars_with<F>(&mut self, bytes: &[u8], encode_word: F)
+    where
+        F: Fn(&[u8]) -> u64,
+    {
         let first_word_len = bytes.len() % 32; // chars remaining
         let (first, rest) = bytes.split_at(first_word_len);
 
         if !first.is_empty() {
-            let first = Kmer::from(first).into_u64();
+            let first = encode_word(first);
             // push the first
             unsafe {
                 self.data.push_int(first, first_word_len * 2);
@@ -153,18 +726,180 @@ impl SeqVector {
         // push the rest that is u64 aligned.
         let chunks = rest.chunks(32);
         for chunk in chunks {
-            let word = Kmer::from(chunk).into_u64();
+            let word = encode_word(chunk);
             unsafe {
                 self.data.push_int(word, chunk.len() * 2);
             }
         }
     }
+
+    // Append the bases of `other` by copying its 2-bit words directly,
+    // without decoding to ASCII. The words are shifted across the word
+    // boundary as needed when `self.len()` is not a multiple of 32.
+    pub fn extend_from_seqvec(&mut self, other: &SeqVector) {
+        self.extend_from_slice(&other.as_slice())
+    }
+
+    pub fn extend_from_slice(&mut self, other: &SeqVectorSlice) {
+        self.data.reserve(other.len() * 2);
+        for pos in (0..other.len()).step_by(32) {
+            let width = (other.len() - pos).min(32);
+            let word = other.get_kmer_u64(pos, width);
+            unsafe {
+                self.data.push_int(word, width * 2);
+            }
+        }
+    }
+
+    pub fn from_bytes_checked(data: &[u8]) -> Result<Self, KmerError> {
+        find_invalid(data)?;
+        Ok(Self::from(data))
+    }
+
+    // Like `from_bytes_checked`, but bytes other than `ACGTacgt` are
+    // handled according to `policy` rather than always rejected.
+    pub fn from_bytes_with_policy(data: &[u8], policy: AmbiguityPolicy) -> Result<Self, KmerError> {
+        Ok(Self::from(resolve_ambiguous(data, policy)?.as_ref()))
+    }
+
+    // Write the underlying `RawVector` in the `simple_sds` on-disk format,
+    // which is what the C++ sdsl-lite based tools (e.g. pufferfish) read.
+    pub fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        SdsSerialize::serialize(&self.data, writer)
+    }
+
+    pub fn load<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let data = RawVector::load(reader)?;
+        if data.len() % 2 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "SeqVector must contain an even number of bits",
+            ));
+        }
+        Ok(Self { data })
+    }
+
+    // the number of bytes written by `serialize`
+    pub fn size_in_bytes(&self) -> usize {
+        self.data.size_in_bytes()
+    }
+
+    // Write a collection of `SeqVector`s, prefixed by their number (as a u64 word).
+    pub fn serialize_vec<W: Write>(seqs: &[SeqVector], writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&(seqs.len() as u64).to_le_bytes())?;
+        for sv in seqs {
+            sv.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_vec<R: Read>(reader: &mut R) -> std::io::Result<Vec<SeqVector>> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        let n = u64::from_le_bytes(buf) as usize;
+        (0..n).map(|_| Self::load(reader)).collect()
+    }
+
+    // Write the vector in the crate's own format, which does not depend on
+    // simple-sds (see `SAVED_MAGIC`), to be read back with `load_saved`.
+    pub fn save<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(SAVED_MAGIC)?;
+        writer.write_all(&SAVED_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.len() as u64).to_le_bytes())?;
+        let mut buf = Vec::with_capacity(8 * 1024);
+        for chunk in self.as_words().chunks(1024) {
+            buf.clear();
+            for w in chunk {
+                buf.extend_from_slice(&w.to_le_bytes());
+            }
+            writer.write_all(&buf)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_saved<R: Read>(reader: &mut R) -> Result<Self, LoadError> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != *SAVED_MAGIC {
+            return Err(LoadError::BadMagic(magic));
+        }
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        let version = u64::from_le_bytes(buf);
+        if version != SAVED_VERSION {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+        reader.read_exact(&mut buf)?;
+        let len = u64::from_le_bytes(buf) as usize;
+
+        // read in chunks, not to trust a corrupted length with an allocation
+        let n_words = (len + 31) / 32;
+        let mut words = Vec::new();
+        let mut bytes = vec![0u8; 8 * 1024];
+        while words.len() < n_words {
+            let n = (n_words - words.len()).min(1024);
+            reader.read_exact(&mut bytes[..8 * n])?;
+            words.extend(
+                bytes[..8 * n]
+                    .chunks_exact(8)
+                    .map(|b| u64::from_le_bytes(b.try_into().unwrap())),
+            );
+        }
+        Ok(Self::from_words(words, len))
+    }
+}
+
+// The format of `SeqVector::save`, all integers little-endian:
+// - the 8 bytes of `SAVED_MAGIC`
+// - the format version, `SAVED_VERSION`, as a u64
+// - the length in bases, as a u64
+// - the bases packed in u64 words (see `SeqVector`), the unused bits of the
+//   last word set to 0
+pub const SAVED_MAGIC: &[u8; 8] = b"KMSEQVEC";
+pub const SAVED_VERSION: u64 = 1;
+
+// Why `SeqVector::load_saved` failed
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    // not a saved `SeqVector`, holds the bytes found instead of the magic
+    BadMagic([u8; 8]),
+    // saved by a version of the format this one cannot read
+    UnsupportedVersion(u64),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot read SeqVector: {}", e),
+            Self::BadMagic(magic) => write!(f, "not a saved SeqVector, starts with {:?}", magic),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported SeqVector format version {}, expected {}",
+                v, SAVED_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
 }
 
 impl std::fmt::Display for SeqVector {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // write!(f, "SeqVector[ {} ]", String::from(self))
-        write!(f, "{}", String::from(self))
+        std::fmt::Display::fmt(&self.as_slice(), f)
     }
 }
 
@@ -181,10 +916,18 @@ impl From<&SeqVector> for String {
     }
 }
 
+// Decodes through a buffer on the stack, so that e.g. `write!(out, "{}",
+// sv.slice(a, b))` builds no intermediate `String`.
 impl std::fmt::Display for SeqVectorSlice<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // write!(f, "SeqVector[ {} ]", String::from(self))
-        write!(f, "{}", String::from(self))
+        let mut buf = [0u8; 256];
+        for start in (0..self.len()).step_by(buf.len()) {
+            let end = (start + buf.len()).min(self.len());
+            let n = self.slice(start, end).decode_into(&mut buf).unwrap();
+            // only ACGT
+            f.write_str(std::str::from_utf8(&buf[..n]).unwrap())?;
+        }
+        Ok(())
     }
 }
 
@@ -201,6 +944,12 @@ impl From<&SeqVectorSlice<'_>> for String {
     }
 }
 
+impl From<&SeqVector> for Vec<u8> {
+    fn from(data: &SeqVector) -> Self {
+        data.to_ascii_vec()
+    }
+}
+
 impl From<SeqVector> for String {
     fn from(data: SeqVector) -> Self {
         Self::from(&data)
@@ -233,14 +982,14 @@ impl From<&[u8]> for SeqVector {
         let chunks = data.chunks(32);
         let mut words = Vec::with_capacity(chunks.len());
         for chunk in chunks {
-            let word = Kmer::from(chunk);
-            words.push(word.into_u64());
+            words.push(encode_word_simd(chunk));
         }
         let rv = RawVector::from_parts(len, words);
         Self { data: rv }
     }
 }
 
+#[cfg(feature = "seq-vector")]
 impl From<RawVector> for SeqVector {
     fn from(data: RawVector) -> Self {
         assert_eq!(data.len() % 2, 0);
@@ -248,6 +997,7 @@ impl From<RawVector> for SeqVector {
     }
 }
 
+#[cfg(feature = "seq-vector")]
 impl From<IntVector> for SeqVector {
     fn from(data: IntVector) -> Self {
         assert_eq!(data.width(), 2);
@@ -257,30 +1007,37 @@ impl From<IntVector> for SeqVector {
     }
 }
 
+impl FromIterator<SeqVector> for SeqVector {
+    fn from_iter<I: IntoIterator<Item = SeqVector>>(iter: I) -> Self {
+        let mut sv = SeqVector::with_capacity(0);
+        for other in iter {
+            sv.extend_from_seqvec(&other);
+        }
+        sv
+    }
+}
+
 pub struct SeqVecKmerIterator<'a> {
     k: km_size_t,
-    len: usize,
-    pos: usize,
+    pos: usize, // next k-mer from the front
+    end: usize, // one past the next k-mer from the back
     seq: SeqVectorSlice<'a>,
 }
 
 impl<'a> SeqVecKmerIterator<'a> {
     pub fn new(slice: SeqVectorSlice<'a>, k: km_size_t) -> Self {
+        // a slice shorter than k has no k-mers
+        let end = (slice.len() + 1).saturating_sub(k);
         Self {
             k,
-            len: slice.len() - k + 1,
             pos: 0,
+            end,
             seq: slice,
         }
     }
 }
 
 impl SeqVecKmerIterator<'_> {
-    pub fn len(&self) -> usize {
-        // warn!{"len() to be changed to ExactSizeIterator::len(...)"}
-        self.len
-    }
-
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -289,7 +1046,7 @@ impl SeqVecKmerIterator<'_> {
 impl Iterator for SeqVecKmerIterator<'_> {
     type Item = Kmer;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.pos < self.len() {
+        if self.pos < self.end {
             let km = self.seq.get_kmer(self.pos, self.k);
             self.pos += 1;
             Some(km)
@@ -297,14 +1054,89 @@ impl Iterator for SeqVecKmerIterator<'_> {
             None
         }
     }
-}
 
-#[cfg(test)]
-mod test {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.pos;
+        (len, Some(len))
+    }
+}
 
-    use super::super::hash::LexHasherState;
-    use super::*;
+impl DoubleEndedIterator for SeqVecKmerIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.pos < self.end {
+            self.end -= 1;
+            Some(self.seq.get_kmer(self.end, self.k))
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for SeqVecKmerIterator<'_> {}
+
+impl std::iter::FusedIterator for SeqVecKmerIterator<'_> {}
+
+// Canonical k-mers of a slice, rolled one base at a time rather than
+// read from the vector at every position.
+pub struct SeqVecCanonicalKmerIterator<'a> {
+    k: km_size_t,
+    pos: usize,
+    end: usize,
+    km: CanonicalKmer,
+    seq: SeqVectorSlice<'a>,
+}
+
+impl<'a> SeqVecCanonicalKmerIterator<'a> {
+    pub fn new(slice: SeqVectorSlice<'a>, k: km_size_t) -> Self {
+        let end = (slice.len() + 1).saturating_sub(k);
+        Self {
+            k,
+            pos: 0,
+            end,
+            km: CanonicalKmer::blank_of_size(k as u8),
+            seq: slice,
+        }
+    }
+}
+
+impl Iterator for SeqVecCanonicalKmerIterator<'_> {
+    type Item = CanonicalKmer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.end {
+            return None;
+        }
+
+        if self.pos == 0 {
+            let word = self.seq.get_kmer_u64(0, self.k);
+            self.km = CanonicalKmer::from_u64(word, self.k as u8);
+        } else {
+            self.km
+                .append_base(self.seq.get_base(self.pos + self.k - 1));
+        }
+        self.pos += 1;
+        Some(self.km.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.pos;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for SeqVecCanonicalKmerIterator<'_> {}
+
+impl std::iter::FusedIterator for SeqVecCanonicalKmerIterator<'_> {}
+
+#[cfg(test)]
+mod test {
 
+    use super::super::hash::{LexHasherState, SeededState};
+    use super::super::prelude::encode_binary;
+    use super::*;
+    use crate::test_utils::{random_bases, random_string, rc_string, xorshift};
+
+    #[cfg(feature = "seq-vector")]
     #[test]
     fn seq_slice_test() {
         let bytes = vec![1u64, 2, 3];
@@ -324,6 +1156,18 @@ mod test {
         assert_eq!(slice.get_kmer_u64(0, 7), sv.get_kmer_u64(75, 7));
     }
 
+    #[test]
+    fn seq_slice_from_parts() {
+        let rv = RawVector::from_parts(3 * 64, vec![1u64, 2, 3]);
+        let sv = SeqVector { data: rv };
+
+        assert_eq!(sv.len(), 32 * 3);
+        assert_eq!(sv.get_kmer_u64(0, 32), 1);
+        assert_eq!(sv.get_kmer_u64(32, 32), 2);
+        assert_eq!(sv.get_kmer_u64(16, 32), 2 << 32);
+        assert_eq!(sv.slice(75, 96).get_kmer_u64(0, 7), sv.get_kmer_u64(75, 7));
+    }
+
     #[test]
     fn push_chars() {
         let mut sv = SeqVector::with_capacity(64);
@@ -338,6 +1182,670 @@ mod test {
         assert_eq!(sv.to_string(), first_a30 + &last_c40);
     }
 
+    #[test]
+    fn push_kmers() {
+        let mut sv = SeqVector::with_capacity(0);
+        let mut s = String::new();
+        let kmers = [
+            "ACGTTGCA",
+            "TTTTTTTTTTTTTTTTTTTTTTTTTTTTTTT",
+            "G",
+            "CAGTACGATCA",
+        ];
+        for (i, km) in kmers.iter().cycle().take(12).enumerate() {
+            if i % 3 == 0 {
+                sv.push_chars(km.as_bytes());
+            } else {
+                sv.push_kmer(&Kmer::from(*km));
+            }
+            s.push_str(km);
+            assert_eq!(sv.to_string(), s);
+        }
+
+        sv.push_word(Kmer::from("GATTACA").into_u64(), 7);
+        s.push_str("GATTACA");
+        assert_eq!(sv.to_string(), s);
+
+        sv.set_kmer(30, &Kmer::from("CCCCCCCCCC"));
+        s.replace_range(30..40, "CCCCCCCCCC");
+        assert_eq!(sv.to_string(), s);
+    }
+
+    #[test]
+    fn push_bases() {
+        // across the 32 base word boundary
+        let mut sv = SeqVector::from(b"ACG".repeat(10).as_slice());
+        let mut s = "ACG".repeat(10);
+        for (i, c) in "TGCATGCA".chars().enumerate() {
+            sv.push_base(encode_binary(c));
+            s.push(c);
+            assert_eq!(sv.len(), 31 + i);
+            assert_eq!(sv.to_string(), s);
+        }
+    }
+
+    #[quickcheck]
+    fn fill_kmer_words(seq: Vec<u8>, k: u8, start: usize) -> bool {
+        let seq: Vec<u8> = seq.iter().map(|b| b"ACGT"[(b % 4) as usize]).collect();
+        let k = (k % 32 + 1) as usize;
+        let sv = SeqVector::from(seq.as_slice());
+        let slice = sv.slice(start % (seq.len() + 1), seq.len());
+
+        // appends to what is already there
+        let mut words = vec![7];
+        slice.fill_kmer_words(k, &mut words);
+        let expected: Vec<u64> = (0..(slice.len() + 1).saturating_sub(k))
+            .map(|pos| slice.get_kmer_u64(pos, k))
+            .collect();
+
+        let mut all = Vec::new();
+        sv.fill_kmer_words(k, &mut all);
+        let all_expected: Vec<u64> = sv.iter_kmers(k).map(|km| km.into_u64()).collect();
+        words[0] == 7 && words[1..] == expected && all == all_expected
+    }
+
+    #[quickcheck]
+    fn push_then_get_kmer(prefix: Vec<bool>, word: u64, k: u8) -> bool {
+        let k = (k % 31 + 1) as usize;
+        let km = Kmer::from_u64(word, k as u8);
+
+        let mut sv = SeqVector::with_capacity(0);
+        for b in &prefix {
+            sv.push_base(*b as Base);
+        }
+        sv.push_kmer(&km);
+
+        let pos = prefix.len();
+        sv.len() == pos + k && sv.get_kmer(pos, k) == km
+    }
+
+    #[test]
+    fn write_ascii() {
+        let s = random_string(3_000_017, 42);
+        let sv = SeqVector::from(&s);
+
+        let mut out = Vec::new();
+        sv.write_ascii(&mut out).unwrap();
+        assert_eq!(out, s.as_bytes());
+        assert_eq!(Vec::<u8>::from(&sv), s.as_bytes());
+
+        // a slice starting mid-word
+        let slice = sv.slice(45, 200_045);
+        assert_eq!(slice.to_ascii_vec(), &s.as_bytes()[45..200_045]);
+        assert_eq!(slice.to_ascii_vec(), slice.to_string().as_bytes());
+
+        assert!(sv.slice(7, 7).to_ascii_vec().is_empty());
+    }
+
+    #[quickcheck]
+    fn count_bases_in_range(seq: Vec<u8>, start: usize, len: usize) -> bool {
+        let seq: Vec<u8> = seq.iter().map(|b| b"ACGT"[*b as usize % 4]).collect();
+        let sv = SeqVector::from(seq.as_slice());
+        let start = start % (seq.len() + 1);
+        // mostly short ranges, within one word or across two
+        let end = start + len % (seq.len() - start + 1).min(40);
+
+        let mut expected = [0; 4];
+        for pos in start..end {
+            expected[sv.get_base(pos) as usize] += 1;
+        }
+        let gc = if start == end {
+            0.0
+        } else {
+            (expected[1] + expected[2]) as f64 / (end - start) as f64
+        };
+        sv.count_bases_in_range(start, end) == expected
+            && sv.count_bases_in_range(0, seq.len()) == sv.base_counts()
+            && (sv.gc_fraction(start, end) - gc).abs() < 1e-12
+    }
+
+    #[test]
+    fn slice_base_counts() {
+        let s = random_string(1000, 3);
+        let sv = SeqVector::from(&s);
+        let counts = |s: &str| ['A', 'C', 'G', 'T'].map(|b| s.matches(b).count() as u64);
+
+        assert_eq!(sv.base_counts(), counts(&s));
+        for (start, end) in [(0, 0), (1, 2), (3, 40), (31, 97), (45, 1000), (333, 334)] {
+            let slice = sv.slice(start, end);
+            let expected = counts(&s[start..end]);
+            assert_eq!(slice.base_counts(), expected, "{start}..{end}");
+
+            let gc = if start == end {
+                0.0
+            } else {
+                (expected[1] + expected[2]) as f64 / (end - start) as f64
+            };
+            assert!((slice.gc_content() - gc).abs() < 1e-12);
+        }
+
+        assert_eq!(sv.count_bases_in_range(31, 97), counts(&s[31..97]));
+        assert_eq!(sv.gc_fraction(5, 5), 0.0);
+
+        let sv = SeqVector::from(&"ACGT".repeat(100));
+        assert!((sv.shannon_entropy() - 2.0).abs() < 1e-12);
+        assert_eq!(sv.slice(1, 2).shannon_entropy(), 0.0);
+    }
+
+    #[test]
+    fn write_fasta() {
+        let s = random_string(200_003, 7);
+        let sv = SeqVector::from(&s);
+
+        for line_width in [1, 7, 32, 60, 61, 80, 200_003, 300_000] {
+            let mut out = Vec::new();
+            sv.write_fasta("chr1 test", &mut out, line_width).unwrap();
+
+            let mut expected = ">chr1 test\n".to_string();
+            for line in s.as_bytes().chunks(line_width) {
+                expected += std::str::from_utf8(line).unwrap();
+                expected.push('\n');
+            }
+            assert_eq!(String::from_utf8(out).unwrap(), expected);
+        }
+
+        let slice = sv.slice(13, 113);
+        let mut out = Vec::new();
+        slice.write_fasta("s", &mut out, 60).unwrap();
+        let expected = format!(">s\n{}\n{}\n", &s[13..73], &s[73..113]);
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        SeqVector::with_capacity(0)
+            .write_fasta("empty", &mut out, 60)
+            .unwrap();
+        assert_eq!(out, b">empty\n");
+    }
+
+    #[test]
+    fn set_base() {
+        let mut sv = SeqVector::from(b"A".repeat(40).as_slice());
+        sv.set_base(0, encode_binary('T'));
+        sv.set_base(31, encode_binary('C'));
+        sv.set_base(32, encode_binary('G'));
+        sv.set_base(39, encode_binary('T'));
+        assert_eq!(
+            sv.to_string(),
+            format!("T{}CG{}T", "A".repeat(30), "A".repeat(6))
+        );
+    }
+
+    #[test]
+    fn set_kmer_u64_word_boundaries() {
+        let mut sv = SeqVector::from(b"A".repeat(70).as_slice());
+        let gta = Kmer::from("GTA").into_u64();
+        for pos in [31, 32, 33] {
+            sv.set_kmer_u64(pos, gta, 3);
+            assert_eq!(sv.get_kmer_u64(pos, 3), gta);
+        }
+        // only the last write is intact, and the bits above k are ignored
+        sv.set_kmer_u64(0, u64::MAX, 2);
+        assert_eq!(
+            sv.to_string(),
+            format!("TT{}GGGTA{}", "A".repeat(29), "A".repeat(34))
+        );
+
+        let word = 0x9E3779B97F4A7C15;
+        sv.set_kmer_u64(30, word, 32);
+        assert_eq!(sv.get_kmer_u64(30, 32), word);
+        assert_eq!(
+            sv.get_kmer(0, 30).to_string(),
+            format!("tt{}", "a".repeat(28))
+        );
+        assert_eq!(sv.get_kmer(62, 8).to_string(), "aaaaaaaa");
+    }
+
+    #[quickcheck]
+    fn set_kmer_u64_round_trip(seq: Vec<u8>, pos: usize, word: u64, k: u8) -> bool {
+        let mut seq: Vec<u8> = seq.iter().map(|b| b"ACGT"[*b as usize % 4]).collect();
+        let k = k as usize % 32 + 1;
+        if seq.len() < k {
+            return true;
+        }
+        let pos = pos % (seq.len() - k + 1);
+        let mut sv = SeqVector::from(seq.as_slice());
+        let km = Kmer::from_u64(word, k as u8);
+
+        sv.set_kmer_u64(pos, word, k);
+        seq[pos..pos + k].copy_from_slice(km.to_string_upper().as_bytes());
+        sv.get_kmer_u64(pos, k) == km.into_u64() && sv == SeqVector::from(seq.as_slice())
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_kmer_u64_out_of_bounds() {
+        let mut sv = SeqVector::from(b"ACGTACGT");
+        sv.set_kmer_u64(5, 0, 4);
+    }
+
+    #[test]
+    fn reverse_complement_range() {
+        let mut seed = 0x9E3779B97F4A7C15_u64;
+        let s = String::from_utf8(random_bases(250, &mut seed, b"ACGT")).unwrap();
+        let mut rand = move || xorshift(&mut seed);
+
+        for len in 0..100 {
+            for _ in 0..4 {
+                let start = (rand() % (s.len() - len + 1) as u64) as usize;
+                let end = start + len;
+
+                let mut sv = SeqVector::from(&s);
+                sv.reverse_complement_range(start, end);
+
+                let expected = s[..start].to_string() + &rc_string(&s[start..end]) + &s[end..];
+                assert_eq!(sv.to_string(), expected, "{start}..{end}");
+            }
+        }
+
+        let mut sv = SeqVector::from(&s);
+        sv.reverse_complement_range(0, s.len());
+        assert_eq!(sv.to_string(), rc_string(&s));
+        sv.reverse_complement_range(0, s.len());
+        assert_eq!(sv.to_string(), s);
+    }
+
+    #[test]
+    fn reverse_complement() {
+        let s = random_string(1000, 5);
+        let sv = SeqVector::from(&s);
+        let rc = sv.reverse_complement();
+        assert_eq!(rc.to_string(), rc_string(&s));
+        assert_eq!(rc.reverse_complement(), sv);
+
+        for (start, end) in [(0, 0), (3, 4), (1, 33), (31, 97), (64, 128), (45, 1000)] {
+            let rc = sv.slice(start, end).reverse_complement();
+            assert_eq!(rc.to_string(), rc_string(&s[start..end]), "{start}..{end}");
+
+            let mut in_place = sv.clone();
+            in_place.reverse_complement_range(start, end);
+            assert_eq!(in_place.slice(start, end).to_owned(), rc);
+        }
+    }
+
+    #[test]
+    fn from_bytes_with_policy() {
+        // ambiguous bases in the first partial word, across a word
+        // boundary and in the last word
+        let mut s = random_string(100, 17).into_bytes();
+        let iupac = b"RYSWKMBDHVNrysw";
+        let positions = [1, 31, 32, 33, 50, 63, 64, 70, 80, 90, 95, 96, 97, 98, 99];
+        for (pos, c) in positions.iter().zip(iupac) {
+            s[*pos] = *c;
+        }
+
+        let e = SeqVector::from_bytes_with_policy(&s, AmbiguityPolicy::Reject).unwrap_err();
+        assert_eq!(e, KmerError::InvalidBase { pos: 1, byte: b'R' });
+
+        let mut as_a = s.clone();
+        for pos in positions {
+            as_a[pos] = b'A';
+        }
+        assert_eq!(
+            SeqVector::from_bytes_with_policy(&s, AmbiguityPolicy::MapToA),
+            Ok(SeqVector::from(as_a.as_slice()))
+        );
+
+        let policy = AmbiguityPolicy::Randomize(11);
+        let sv = SeqVector::from_bytes_with_policy(&s, policy).unwrap();
+        assert_eq!(sv.len(), s.len());
+        assert_eq!(
+            SeqVector::from_bytes_with_policy(&s, policy),
+            Ok(sv.clone())
+        );
+        // the same draws as for a k-mer over the same bytes
+        assert_eq!(
+            sv.get_kmer(0, 32),
+            Kmer::from_bytes_with_policy(&s[..32], policy).unwrap()
+        );
+        let resolved = sv.to_string().into_bytes();
+        for (pos, (r, c)) in resolved.iter().zip(&s).enumerate() {
+            if !positions.contains(&pos) {
+                assert_eq!(r, c);
+            }
+        }
+
+        s[40] = b'X';
+        assert_eq!(
+            SeqVector::from_bytes_with_policy(&s, policy),
+            Err(KmerError::InvalidBase {
+                pos: 40,
+                byte: b'X'
+            })
+        );
+    }
+
+    #[test]
+    fn decode_into_buffer() {
+        let s = random_string(1000, 19);
+        let sv = SeqVector::from(&s);
+        let mut buf = vec![b'-'; 1100];
+        for (start, end) in [(0, 0), (0, 1000), (1, 33), (31, 97), (64, 128), (45, 1000)] {
+            let n = sv.decode_range_into(start, end, &mut buf).unwrap();
+            assert_eq!(n, end - start);
+            assert_eq!(&buf[..n], &s.as_bytes()[start..end]);
+            assert_eq!(&buf[..n], String::from(&sv.slice(start, end)).as_bytes());
+
+            // exactly the range length is enough
+            let n = sv.decode_range_into(start, end, &mut buf[..end - start]);
+            assert_eq!(n, Ok(end - start));
+        }
+
+        let mut buf = vec![b'-'; 10];
+        let e = sv.decode_range_into(5, 16, &mut buf).unwrap_err();
+        assert_eq!(
+            e,
+            BufferTooSmall {
+                needed: 11,
+                len: 10
+            }
+        );
+        assert_eq!(buf, vec![b'-'; 10]);
+        assert_eq!(
+            sv.as_slice().decode_into(&mut buf).unwrap_err().needed,
+            1000
+        );
+    }
+
+    #[test]
+    fn display_slices() {
+        use std::fmt::Write;
+
+        let s = random_string(1000, 21);
+        let sv = SeqVector::from(&s);
+        assert_eq!(format!("{}", sv), s);
+        assert_eq!(sv.to_string(), String::from(&sv));
+
+        let mut out = String::new();
+        // around the 256 bases decoded at once
+        for (start, end) in [(0, 0), (3, 4), (0, 256), (1, 257), (10, 600), (0, 1000)] {
+            out.clear();
+            write!(out, "{}", sv.slice(start, end)).unwrap();
+            assert_eq!(out, &s[start..end]);
+            assert_eq!(out, String::from(&sv.slice(start, end)));
+        }
+    }
+
+    #[test]
+    fn push_chars_checked() {
+        // 70 = 6 bases in the first partial word + 2 aligned words
+        let good = "ACGTAC".to_string() + &"ACGT".repeat(16);
+
+        let mut sv = SeqVector::with_capacity(good.len());
+        assert_eq!(sv.push_chars_checked(good.as_bytes()), Ok(()));
+        assert_eq!(sv.to_string(), good);
+
+        for pos in [0, 3, 5, 6, 37, 38, 69] {
+            let mut bad = good.clone().into_bytes();
+            bad[pos] = b'N';
+
+            let mut sv = SeqVector::from(b"TT");
+            let e = sv.push_chars_checked(&bad).unwrap_err();
+            assert_eq!(e, KmerError::InvalidBase { pos, byte: b'N' });
+            // nothing is pushed on error
+            assert_eq!(sv.to_string(), "TT");
+
+            let e = SeqVector::from_bytes_checked(&bad).unwrap_err();
+            assert_eq!(e, KmerError::InvalidBase { pos, byte: b'N' });
+        }
+
+        assert_eq!(
+            SeqVector::from_bytes_checked(good.as_bytes()),
+            Ok(SeqVector::from(&good))
+        );
+    }
+
+    #[test]
+    fn push_chars_lossy() {
+        let seq = "ACGTNC".to_string() + &"ACGR".repeat(16);
+        let expected = seq.replace(['N', 'R'], "A");
+
+        let mut sv = SeqVector::with_capacity(seq.len());
+        assert_eq!(sv.push_chars_lossy(b"ACG"), 0);
+        assert_eq!(sv.push_chars_lossy(seq.as_bytes()), 17);
+        assert_eq!(sv.to_string(), "ACG".to_string() + &expected);
+
+        // invalid bases on both sides of the word boundaries
+        for len in [31, 32, 33, 64, 65] {
+            let mut bad = "ACGT".repeat(17).into_bytes();
+            bad.truncate(len);
+            for pos in [0, 30, 31, 32, len - 1] {
+                if pos < len {
+                    bad[pos] = b'n';
+                }
+            }
+            let mut sv = SeqVector::from(b"T");
+            let replaced = sv.push_chars_lossy(&bad);
+            assert_eq!(replaced, bad.iter().filter(|&&c| c == b'n').count());
+            let expected = String::from_utf8(bad.to_ascii_uppercase()).unwrap();
+            assert_eq!(
+                sv.to_string(),
+                "T".to_string() + &expected.replace('N', "A")
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "cannot encode 'N' (0x4e) at position 33")]
+    fn push_chars_invalid() {
+        let mut bad = "ACGT".repeat(10).into_bytes();
+        bad[33] = b'N';
+        SeqVector::from(b"A").push_chars(&bad);
+    }
+
+    #[test]
+    fn extend_from_seqvec() {
+        let s = "TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGGGGCTGCCAGAACTAGTGGGAAGCTCCCTAGAAATGGTGACATCGCCCACCAAACAGACC";
+
+        // piece sizes chosen to hit aligned, unaligned and empty boundaries
+        let sizes = [5, 27, 32, 0, 1, 64, 33, 17, 31];
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        for size in sizes {
+            let end = (start + size).min(s.len());
+            pieces.push(&s[start..end]);
+            start = end;
+        }
+        pieces.push(&s[start..]);
+
+        let mut sv = SeqVector::with_capacity(s.len());
+        let mut reference = String::new();
+        for piece in pieces.iter() {
+            sv.extend_from_seqvec(&SeqVector::from(piece.as_bytes()));
+            reference.push_str(piece);
+            assert_eq!(sv.len(), reference.len());
+            assert_eq!(sv.to_string(), reference);
+        }
+        assert_eq!(sv, SeqVector::from(s.as_bytes()));
+
+        let collected: SeqVector = pieces
+            .iter()
+            .map(|piece| SeqVector::from(piece.as_bytes()))
+            .collect();
+        assert_eq!(collected.to_string(), s);
+    }
+
+    #[test]
+    fn extend_from_slice() {
+        let s = "TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGACTAGAGAATGGGGCTGCCAGAACTAGTGGG";
+        let other = SeqVector::from(s.as_bytes());
+
+        for prefix in 0..40 {
+            let mut sv = SeqVector::from("A".repeat(prefix).as_bytes());
+            let slice = other.slice(3, 3 + 45);
+            sv.extend_from_slice(&slice);
+            assert_eq!(sv.to_string(), "A".repeat(prefix) + &s[3..48]);
+        }
+    }
+
+    #[test]
+    fn nested_slices() {
+        let s = random_string(300, 7);
+        let sv = SeqVector::from(s.as_bytes());
+
+        for (a, b, c) in [(0, 0, 0), (1, 2, 3), (5, 33, 17), (31, 1, 64), (63, 65, 2)] {
+            let s1 = sv.slice(a, 300);
+            let s2 = s1.slice(b, s1.len() - 3);
+            let s3 = s2.slice(c, s2.len());
+            assert_eq!(String::from(&s1), s[a..]);
+            assert_eq!(String::from(&s2), s[a + b..297]);
+            assert_eq!(String::from(&s3), s[a + b + c..297]);
+
+            let s4 = s3.slice(4, 37);
+            let start = a + b + c + 4;
+            assert_eq!(String::from(&s4), s[start..start + 33]);
+            assert_eq!(
+                s4.get_kmer(1, 5).to_string(),
+                s[start + 1..start + 6].to_lowercase()
+            );
+            assert_eq!(s4.to_owned().to_string(), s[start..start + 33]);
+        }
+        assert!(sv.slice(10, 10).to_owned().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_start_after_end() {
+        SeqVector::from(b"ACGTACGT").slice(3, 8).slice(2, 1);
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let sv = SeqVector::from(b"TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGGAGATAACTAGAAGGAC");
+
+        let path = std::env::temp_dir().join(format!("kmers-seqvec-{}.bin", std::process::id()));
+        let mut f = std::fs::File::create(&path).unwrap();
+        sv.serialize(&mut f).unwrap();
+        drop(f);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), sv.size_in_bytes());
+
+        let mut f = std::fs::File::open(&path).unwrap();
+        let loaded = SeqVector::load(&mut f).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, sv);
+    }
+
+    #[test]
+    fn serialize_matches_simple_sds() {
+        let sv = SeqVector::from(b"ACGTTGCAAGGCTTACGATCGATCGGGATCCAGATTACAG");
+
+        let mut ours = Vec::new();
+        sv.serialize(&mut ours).unwrap();
+
+        // the bytes simple-sds (and sdsl-lite) write for a RawVector of
+        // 80 bits: the length in bits, the number of words, then the words,
+        // the unused high bits of the last one zeroed
+        #[rustfmt::skip]
+        let golden: [u8; 32] = [
+            0x50, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0xe4, 0x1b, 0x68, 0x4f, 0x72, 0x72, 0x2a, 0x17,
+            0xf2, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(ours, golden);
+        assert_eq!(SeqVector::load(&mut &golden[..]).unwrap(), sv);
+    }
+
+    // the serde form is the same with and without the `seq-vector` feature,
+    // so data written by one backend can be read by the other
+    #[test]
+    fn serde_same_for_both_backends() {
+        let sv = SeqVector::from(b"ACGTTGCAAGGCTTACGATCGATCGGGATCCAGATTACAG");
+        let json = r#"{"data":{"len":80,"data":[1669272447178251236,34034]}}"#;
+        assert_eq!(serde_json::to_string(&sv).unwrap(), json);
+        assert_eq!(serde_json::from_str::<SeqVector>(json).unwrap(), sv);
+
+        let empty = r#"{"data":{"len":0,"data":[]}}"#;
+        assert_eq!(serde_json::to_string(&SeqVector::from(b"")).unwrap(), empty);
+        assert_eq!(
+            serde_json::from_str::<SeqVector>(empty).unwrap(),
+            SeqVector::from(b"")
+        );
+    }
+
+    // with the fallback, words inconsistent with the length are rejected
+    #[cfg(not(feature = "seq-vector"))]
+    #[test]
+    fn serde_rejects_bad_raw_vector() {
+        for json in [
+            r#"{"data":{"len":80,"data":[1669272447178251236]}}"#,
+            r#"{"data":{"len":4,"data":[255]}}"#,
+        ] {
+            assert!(serde_json::from_str::<SeqVector>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn serialize_vec_round_trip() {
+        let seqs: Vec<SeqVector> = ["ACGT", "", "TTTTGGCCATTTTTCCTGTTCTTCAAGAAAACAGG", "G"]
+            .iter()
+            .map(|s| SeqVector::from(s.as_bytes()))
+            .collect();
+
+        let mut buf = Vec::new();
+        SeqVector::serialize_vec(&seqs, &mut buf).unwrap();
+        let loaded = SeqVector::load_vec(&mut buf.as_slice()).unwrap();
+        assert_eq!(loaded, seqs);
+
+        // truncated input is an error rather than a panic
+        assert!(SeqVector::load_vec(&mut &buf[..buf.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn save_load_round_trip() {
+        for len in [0, 1, 31, 32, 33, 1000, 3_000_017] {
+            let sv = SeqVector::from(&random_string(len, len as u64 + 1));
+            let mut buf = Vec::new();
+            sv.save(&mut buf).unwrap();
+            assert_eq!(buf.len(), 24 + 8 * ((len + 31) / 32));
+            assert_eq!(&buf[..8], b"KMSEQVEC");
+            assert_eq!(buf[8..16], 1u64.to_le_bytes());
+            assert_eq!(buf[16..24], (len as u64).to_le_bytes());
+
+            let loaded = SeqVector::load_saved(&mut buf.as_slice()).unwrap();
+            assert_eq!(loaded, sv);
+        }
+
+        // the words are written as is, lowest bases first
+        let mut buf = Vec::new();
+        SeqVector::from(b"CA").save(&mut buf).unwrap();
+        assert_eq!(buf[24..], 1u64.to_le_bytes());
+    }
+
+    #[test]
+    fn load_saved_errors() {
+        let mut buf = Vec::new();
+        SeqVector::from(b"ACGTACGT").save(&mut buf).unwrap();
+
+        let mut bad = buf.clone();
+        bad[0] = b'X';
+        let e = SeqVector::load_saved(&mut bad.as_slice()).unwrap_err();
+        assert!(matches!(e, LoadError::BadMagic(m) if &m == b"XMSEQVEC"));
+
+        let mut bad = buf.clone();
+        bad[8] = 2;
+        let e = SeqVector::load_saved(&mut bad.as_slice()).unwrap_err();
+        assert!(matches!(e, LoadError::UnsupportedVersion(2)));
+        assert_eq!(
+            e.to_string(),
+            "unsupported SeqVector format version 2, expected 1"
+        );
+
+        for len in [0, 5, 20, buf.len() - 1] {
+            let e = SeqVector::load_saved(&mut &buf[..len]).unwrap_err();
+            assert!(
+                matches!(e, LoadError::Io(ref e) if e.kind() == std::io::ErrorKind::UnexpectedEof)
+            );
+        }
+
+        // neither is the simple-sds format
+        let mut sds = Vec::new();
+        SeqVector::from(b"ACGTACGT").serialize(&mut sds).unwrap();
+        assert!(SeqVector::load_saved(&mut sds.as_slice()).is_err());
+    }
+
     #[test]
     fn iter_kmers() {
         let s = b"ACTTGAT";
@@ -377,4 +1885,176 @@ mod test {
             .collect();
         assert_eq!(kmers, mers[1..mers.len() - 1]);
     }
+
+    #[test]
+    fn iter_kmers_both_ends() {
+        let s = "ACTTGATCCA";
+        let sv = SeqVector::from(s.as_bytes());
+        let k = 4;
+        let mers: Vec<String> = (0..=s.len() - k)
+            .map(|i| s[i..i + k].to_lowercase())
+            .collect();
+
+        let fw: Vec<String> = sv.iter_kmers(k).map(|km| km.to_string()).collect();
+        assert_eq!(fw, mers);
+
+        let rev: Vec<String> = sv.iter_kmers(k).rev().map(|km| km.to_string()).collect();
+        let mut mers_rev = mers.clone();
+        mers_rev.reverse();
+        assert_eq!(rev, mers_rev);
+
+        // mixing both ends
+        let mut iter = sv.iter_kmers(k);
+        assert_eq!(iter.len(), 7);
+        assert_eq!(iter.next().unwrap().to_string(), mers[0]);
+        assert_eq!(iter.next_back().unwrap().to_string(), mers[6]);
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next_back().unwrap().to_string(), mers[5]);
+        assert_eq!(iter.next().unwrap().to_string(), mers[1]);
+        assert_eq!(iter.len(), 3);
+        let rest: Vec<String> = iter.by_ref().map(|km| km.to_string()).collect();
+        assert_eq!(rest, mers[2..5]);
+        assert!(iter.is_empty());
+        assert!(iter.next().is_none() && iter.next_back().is_none());
+    }
+
+    #[test]
+    fn iter_kmers_exactly_k() {
+        let sv = SeqVector::from(b"ACTTG");
+
+        let mut iter = sv.iter_kmers(5);
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back().unwrap().to_string(), "acttg");
+        assert_eq!(iter.len(), 0);
+        assert!(iter.next().is_none());
+
+        assert_eq!(sv.iter_kmers(6).len(), 0);
+        assert_eq!(sv.slice(1, 5).iter_kmers(5).count(), 0);
+    }
+
+    #[test]
+    fn iter_with_pos() {
+        let s = b"ACTTGATCCAGGTACAGATTACA";
+        let sv = SeqVector::from(s);
+        let k = 5;
+
+        let kmers: Vec<(Kmer, usize)> = sv.iter_kmers_with_pos(k).collect();
+        assert_eq!(kmers.len(), s.len() - k + 1);
+        for (km, pos) in kmers {
+            assert_eq!(km, Kmer::from(&s[pos..pos + k]));
+        }
+
+        let slice = sv.slice(6, 20);
+        let global: Vec<(Kmer, usize)> = slice.iter_kmers_with_pos(k).collect();
+        let local: Vec<(Kmer, usize)> = slice.iter_kmers_with_local_pos(k).collect();
+        assert_eq!(global.len(), 20 - 6 - k + 1);
+        for ((km, g), (km_local, l)) in global.into_iter().zip(local) {
+            assert_eq!(km, km_local);
+            assert_eq!(g, l + 6);
+            assert_eq!(km, Kmer::from(&s[g..g + k]));
+        }
+
+        let w = 3;
+        let state = LexHasherState::new(w);
+        let local: Vec<MappedMinimizer> = slice.iter_minimizers(k, w, state.clone()).collect();
+        let global: Vec<MappedMinimizer> = slice
+            .iter_minimizers_with_global_pos(k, w, state.clone())
+            .collect();
+        for (l, g) in local.iter().zip(global.iter()) {
+            assert_eq!(g.pos, l.pos + 6);
+            assert_eq!(g.as_u64(), Kmer::from(&s[g.pos..g.pos + w]).into_u64());
+        }
+
+        let local: Vec<MappedMinimizer> = slice
+            .iter_canonical_minimizers(k, w, state.clone())
+            .collect();
+        let global: Vec<MappedMinimizer> = slice
+            .iter_canonical_minimizers_with_global_pos(k, w, state)
+            .collect();
+        for (l, g) in local.iter().zip(global.iter()) {
+            assert_eq!(g.pos, l.pos + 6);
+            assert_eq!(g.as_u64(), l.as_u64());
+        }
+    }
+
+    #[test]
+    fn iter_canonical_kmers() {
+        let s = b"ACTTGATCCAGGTACAGATTACAGGCATTTACGATCGGATCAGTTACCAGT";
+        let sv = SeqVector::from(s);
+
+        for k in 1..32 {
+            let ckmers: Vec<CanonicalKmer> = sv.iter_canonical_kmers(k).collect();
+            assert_eq!(ckmers.len(), s.len() - k + 1);
+            for (i, ckm) in ckmers.into_iter().enumerate() {
+                assert_eq!(ckm, CanonicalKmer::from_u64(sv.get_kmer_u64(i, k), k as u8));
+            }
+
+            let slice = sv.slice(5, 40);
+            for (i, ckm) in slice.iter_canonical_kmers(k).enumerate() {
+                assert_eq!(
+                    ckm,
+                    CanonicalKmer::from_u64(sv.get_kmer_u64(5 + i, k), k as u8)
+                );
+            }
+        }
+
+        assert_eq!(sv.iter_canonical_kmers(s.len() + 1).count(), 0);
+    }
+
+    #[test]
+    fn words_round_trip() {
+        let s = random_string(1000, 11);
+        let sv = SeqVector::from(&s);
+        let words = sv.as_words();
+        assert_eq!(words.len(), (1000 + 31) / 32);
+        // base i at bits 2i % 64 of word i / 32
+        assert_eq!(words[1] & 0b11, sv.get_kmer_u64(32, 1));
+        assert_eq!(words[31] >> 16, 0);
+
+        let borrowed = SeqVectorBorrowed::from_word_slice(words, sv.len());
+        assert_eq!(borrowed.len(), sv.len());
+        assert_eq!(borrowed.to_string(), s);
+        assert_eq!(borrowed.to_owned(), sv);
+        assert_eq!(SeqVector::from_words(words.to_vec(), sv.len()), sv);
+
+        // k-mers across word boundaries
+        for (pos, k) in [(0, 32), (1, 32), (30, 5), (63, 2), (968, 32), (999, 1)] {
+            assert_eq!(borrowed.get_kmer_u64(pos, k), sv.get_kmer_u64(pos, k));
+        }
+        assert!(borrowed.iter_kmers(31).eq(sv.iter_kmers(31)));
+        assert!(borrowed
+            .iter_minimizers_with_global_pos(31, 19, SeededState(7))
+            .eq(sv
+                .as_slice()
+                .iter_minimizers_with_global_pos(31, 19, SeededState(7))));
+        assert_eq!(
+            borrowed.slice(45, 900).to_string(),
+            sv.slice(45, 900).to_string()
+        );
+    }
+
+    #[test]
+    fn words_past_len() {
+        let s = random_string(100, 13);
+        let sv = SeqVector::from(&s);
+        // garbage after the last base, and a word too many
+        let mut words = sv.as_words().to_vec();
+        words[3] |= u64::MAX << 8;
+        words.push(u64::MAX);
+
+        let borrowed = SeqVectorSlice::from_word_slice(&words, 100);
+        assert_eq!(borrowed.to_string(), s);
+        assert_eq!(borrowed.get_kmer_u64(96, 4), sv.get_kmer_u64(96, 4));
+
+        let owned = SeqVector::from_words(words, 100);
+        assert_eq!(owned, sv);
+        assert_eq!(owned.as_words(), sv.as_words());
+        assert!(SeqVector::from_words(Vec::new(), 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_few_words() {
+        SeqVectorSlice::from_word_slice(&[0, 0], 65);
+    }
 }
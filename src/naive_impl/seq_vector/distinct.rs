@@ -0,0 +1,200 @@
+// The distinct canonical k-mers of a sequence as sorted words, e.g. the
+// keys of a minimal perfect hash function. The rolling canonical words are
+// sorted and deduplicated by blocks of k-mers, then the sorted runs are
+// merged, so that memory follows the number of distinct k-mers rather than
+// the number of positions on repetitive sequences.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::*;
+
+// k-mers per block in `SeqVector::distinct_canonical_kmers`
+pub const DEFAULT_BLOCK_LEN: usize = 1 << 20;
+
+// Runs are merged by levels, as in a log-structured merge: once a level
+// holds this many runs, they are merged into one run of the next level.
+// A k-mer is so merged O(log(#blocks) / log(MAX_RUNS)) times, while the
+// k-mers kept in several runs at once stay bounded.
+const MAX_RUNS: usize = 16;
+
+// The canonical words of the k-mers of `sv`, sorted and without
+// duplicates, collected `block_len` k-mers at a time.
+pub fn distinct_canonical_kmers(sv: &SeqVectorSlice, k: km_size_t, block_len: usize) -> Vec<u64> {
+    assert!(block_len > 0, "blocks must hold at least one k-mer");
+    let mut words = sv.iter_canonical_kmers(k).map(|km| km.get_canonical_word());
+
+    // levels[i] holds the runs merged from MAX_RUNS^i blocks each
+    let mut levels: Vec<Vec<Vec<u64>>> = Vec::new();
+    let mut block = Vec::with_capacity(block_len.min(words.len()));
+    loop {
+        block.extend(words.by_ref().take(block_len));
+        if block.is_empty() {
+            break;
+        }
+        block.sort_unstable();
+        block.dedup();
+        // copied to fit its length, the block buffer is reused
+        let mut run = block.to_vec();
+        block.clear();
+
+        for level in 0.. {
+            if level == levels.len() {
+                levels.push(Vec::new());
+            }
+            levels[level].push(run);
+            if levels[level].len() < MAX_RUNS {
+                break;
+            }
+            run = merge_runs(std::mem::take(&mut levels[level]));
+        }
+    }
+    merge_runs(levels.into_iter().flatten().collect())
+}
+
+// k-way merge of sorted runs of distinct words, keeping each word once
+fn merge_runs(mut runs: Vec<Vec<u64>>) -> Vec<u64> {
+    if runs.len() <= 1 {
+        return runs.pop().unwrap_or_default();
+    }
+
+    let mut heads = vec![0; runs.len()];
+    let mut heap: BinaryHeap<_> = runs
+        .iter()
+        .enumerate()
+        .filter_map(|(r, run)| run.first().map(|&w| Reverse((w, r))))
+        .collect();
+
+    let mut merged = Vec::with_capacity(runs.iter().map(Vec::len).max().unwrap_or(0));
+    while let Some(Reverse((w, r))) = heap.pop() {
+        if merged.last() != Some(&w) {
+            merged.push(w);
+        }
+        heads[r] += 1;
+        if let Some(&next) = runs[r].get(heads[r]) {
+            heap.push(Reverse((next, r)));
+        }
+    }
+    merged
+}
+
+impl SeqVectorSlice<'_> {
+    // The distinct canonical k-mer words, in increasing order, collected
+    // by blocks of `DEFAULT_BLOCK_LEN` k-mers
+    pub fn distinct_canonical_kmers(&self, k: km_size_t) -> Vec<u64> {
+        distinct_canonical_kmers(self, k, DEFAULT_BLOCK_LEN)
+    }
+
+    // The same, by blocks of `block_len` k-mers: smaller blocks use less
+    // memory on sequences with few repeats, at the cost of more merging.
+    pub fn distinct_canonical_kmers_by_blocks(&self, k: km_size_t, block_len: usize) -> Vec<u64> {
+        distinct_canonical_kmers(self, k, block_len)
+    }
+}
+
+impl SeqVector {
+    pub fn distinct_canonical_kmers(&self, k: km_size_t) -> Vec<u64> {
+        self.as_slice().distinct_canonical_kmers(k)
+    }
+
+    pub fn distinct_canonical_kmers_by_blocks(&self, k: km_size_t, block_len: usize) -> Vec<u64> {
+        self.as_slice()
+            .distinct_canonical_kmers_by_blocks(k, block_len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::test_utils::random_seq;
+
+    // the distinct canonical words from the k-mer strings
+    fn reference(seq: &[u8], k: usize) -> Vec<u64> {
+        let s = std::str::from_utf8(seq).unwrap();
+        let set: HashSet<u64> = (0..(seq.len() + 1).saturating_sub(k))
+            .map(|pos| CanonicalKmer::from(&s[pos..pos + k]).get_canonical_word())
+            .collect();
+        let mut words: Vec<u64> = set.into_iter().collect();
+        words.sort_unstable();
+        words
+    }
+
+    fn check_blocks(seq: &[u8], k: usize) {
+        let sv = SeqVector::from(seq);
+        let expected = reference(seq, k);
+        assert_eq!(sv.distinct_canonical_kmers(k), expected, "k = {}", k);
+        // down to one k-mer per block, with runs on several levels
+        for block_len in [1, 3, 64, 1000] {
+            assert_eq!(
+                sv.distinct_canonical_kmers_by_blocks(k, block_len),
+                expected,
+                "k = {}, blocks of {}",
+                k,
+                block_len
+            );
+        }
+    }
+
+    #[test]
+    fn heavy_repeats() {
+        // 40 copies of a 250 bases unit, with a few mutated copies
+        let unit = random_seq(250, 3);
+        let mut seq = Vec::new();
+        for copy in 0..40 {
+            let mut unit = unit.clone();
+            if copy % 7 == 3 {
+                unit[copy] = b"ACGT"[(copy / 7) % 4];
+            }
+            seq.extend_from_slice(&unit);
+        }
+        for k in [1, 2, 11, 21, 31] {
+            check_blocks(&seq, k);
+        }
+
+        // a homopolymer has a single canonical k-mer
+        let seq = vec![b'T'; 5000];
+        let sv = SeqVector::from(seq.as_slice());
+        assert_eq!(sv.distinct_canonical_kmers_by_blocks(21, 100), vec![0]);
+        assert_eq!(sv.distinct_canonical_kmers(21), reference(&seq, 21));
+
+        // a tandem repeat of a dinucleotide has two k-mers, both canonical
+        let seq = b"AC".repeat(3000);
+        assert_eq!(reference(&seq, 20).len(), 2);
+        check_blocks(&seq, 20);
+    }
+
+    #[test]
+    fn boundary_k() {
+        let seq = random_seq(5000, 8);
+        check_blocks(&seq, 32);
+
+        // words using the top bits of the u64
+        let seq = b"TTTTGGGGCCCCAAAATTTTGGGGCCCCAAAATGTG".to_vec();
+        check_blocks(&seq, 32);
+        let sv = SeqVector::from(&seq[..32]);
+        assert_eq!(sv.distinct_canonical_kmers(32), reference(&seq[..32], 32));
+        assert_eq!(sv.distinct_canonical_kmers(32).len(), 1);
+
+        // too short for a single k-mer
+        let sv = SeqVector::from(&seq[..31]);
+        assert!(sv.distinct_canonical_kmers(32).is_empty());
+        assert!(SeqVector::from(&seq[..0])
+            .distinct_canonical_kmers(21)
+            .is_empty());
+    }
+
+    #[test]
+    fn slices() {
+        let seq = random_seq(3000, 5).repeat(3);
+        let sv = SeqVector::from(seq.as_slice());
+        for (start, end) in [(0, 100), (17, 2000), (2999, 9000)] {
+            assert_eq!(
+                sv.slice(start, end)
+                    .distinct_canonical_kmers_by_blocks(25, 200),
+                reference(&seq[start..end], 25)
+            );
+        }
+    }
+}
@@ -1,91 +1,94 @@
-use std::collections::VecDeque;
 use std::hash::BuildHasher;
+use std::iter::Peekable;
 
-use super::super::hash::hash_one;
+use super::super::hash::{hash_one, NtHashIterator, NtHashState};
+use super::super::minimizer_queue::{
+    check_window, HashedLmer, HashedMinimizerQueue, LeftMin, MinimizerScheme, RightMin,
+};
+use super::super::prelude::{encode_binary_u8, is_valid_nuc};
 use super::*;
 
-#[derive(Clone, Debug)]
-struct DQMer {
-    pub lmer: u64,
-    pub pos: usize,
-    pub hash: u64,
-}
+pub use crate::naive_impl::partition::{for_each_super_kmer_bucketed, partition_super_kmers};
 
-impl DQMer {
-    fn new(lmer: u64, pos: usize, hash: u64) -> Self {
-        Self { lmer, pos, hash }
-    }
-}
+type DQMer = HashedLmer;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MappedMinimizer {
     word: u64,      // u64 representation
     pub pos: usize, // position in sequence
+    is_fw: bool,    // word read off the forward strand of the sequence
 }
 
 impl MappedMinimizer {
     pub fn as_u64(&self) -> u64 {
         self.word
     }
+
+    // true if the word is the lmer of the sequence at `pos`, false if it is
+    // its reverse complement
+    pub fn is_fw(&self) -> bool {
+        self.is_fw
+    }
 }
 
 impl MappedMinimizer {
     pub fn new(lmer: u64, pos: usize) -> Self {
-        Self { word: lmer, pos }
+        Self::new_stranded(lmer, pos, true)
+    }
+
+    pub fn new_stranded(lmer: u64, pos: usize, is_fw: bool) -> Self {
+        Self {
+            word: lmer,
+            pos,
+            is_fw,
+        }
     }
 }
 
-pub struct SeqVecMinimizerIter<'a, T: BuildHasher> {
-    dq: VecDeque<DQMer>,
+fn check_len(sv: &SeqVectorSlice, k: usize) {
+    assert!(
+        sv.len() >= k,
+        "sequence of length {} holds no {}-mer",
+        sv.len(),
+        k
+    );
+}
+
+// Yields the minimizer of every k-mer of a sequence, as selected by the
+// `MinimizerScheme` `S` (smallest hash, leftmost on ties, by default).
+pub struct SeqVecMinimizerIter<'a, T: BuildHasher, S = LeftMin> {
+    dq: HashedMinimizerQueue<u64, S>,
     k: usize,
     w: usize, // or "L"
     curr_km_i: usize,
     sv: SeqVectorSlice<'a>,
     hash_seed: T,
+    rolling: Option<NtHashIterator<'a>>, // lmer hashes, see `with_nthash`
 }
 
-impl<'a, T: BuildHasher> SeqVecMinimizerIter<'a, T> {
-    // Dequeue invariant:
-    // Q = [ (Li, pi) ... (Lj, pj) ] at iteration ii
-    //   front                    back
-
-    // - each L_i is an L-mer in the (k-1)-prefix of kmer_ii
-    // - where each L_i can be a candidate minimizer in any suffix of kmer_ii
-    //   and thus could be a minimizer in any kmer succeeding kmer_ii with at least a L-mer overlap
-    // - i.e.:
-    //    - h(L_i) < h(L_j) iff i < j
-    //    - pj - pi
-    //
-
+impl<'a, T: BuildHasher, S: MinimizerScheme> SeqVecMinimizerIter<'a, T, S> {
+    // At iteration ii, the queue holds the candidate minimizers among the
+    // L-mers of the (k-1)-prefix of kmer_ii (see `HashedMinimizerQueue`).
     #[inline]
     fn enqueue_dqmer(&mut self, dqmer: DQMer) {
-        // Could be a while loop to be safe...
-        // but we should never have more than one minimizer fall out of the window
-        // since only one successive lmer is added to the queue
-
-        if let Some(frontmer) = self.dq.front() {
-            if frontmer.pos < self.curr_km_i {
-                self.dq.pop_front();
-            }
-        }
-
-        while let Some(backmer) = self.dq.back() {
-            if backmer.hash <= dqmer.hash {
-                break;
-            } else {
-                self.dq.pop_back();
-            }
-        }
-
-        self.dq.push_back(dqmer);
+        self.dq.evict_before(self.curr_km_i);
+        self.dq.push(dqmer);
     }
 
     #[inline]
     fn next_dqmer(&mut self) -> DQMer {
         // return last dqmer of curr_km_ii-th kmer
         let pos = self.curr_km_i + self.k - self.w;
+        self.dqmer_at(pos)
+    }
+
+    #[inline]
+    fn dqmer_at(&mut self, pos: usize) -> DQMer {
         let lmer = self.sv.get_kmer_u64(pos, self.w);
-        let hash = hash_one(&self.hash_seed, lmer);
+        let hash = match self.rolling.as_mut() {
+            Some(rolling) => rolling.next_pair().unwrap().0,
+            None => hash_one(&self.hash_seed, lmer),
+        };
         DQMer::new(lmer, pos, hash)
     }
 
@@ -94,26 +97,42 @@ impl<'a, T: BuildHasher> SeqVecMinimizerIter<'a, T> {
         self.sv.len() - self.k + 1
     }
 
-    pub fn new(sv: SeqVectorSlice<'a>, k: usize, w: usize, hash_seed: T) -> Self {
+    pub fn with_scheme(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        scheme: S,
+    ) -> Self {
+        Self::build(sv, k, w, hash_seed, scheme, false)
+    }
+
+    // `rolling` must only be set when `hash_seed` is `NtHashState::new(w)`
+    fn build(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        scheme: S,
+        rolling: bool,
+    ) -> Self {
+        check_window(k, w);
+        check_len(&sv, k);
         // Insert lmers of the k-1 prefix
-        assert!(sv.len() >= k);
-        let dq = VecDeque::with_capacity(k - w + 1);
+        let dq = HashedMinimizerQueue::with_scheme(scheme, k - w + 1);
 
         let mut iter = Self {
             dq,
             k,
             w,
+            rolling: rolling.then(|| NtHashIterator::new(&sv, w)),
             hash_seed,
-            sv: sv.clone(),
+            sv,
             curr_km_i: 0,
         };
 
         for i in 0..(k - w) {
-            let lmer = sv.get_kmer_u64(i, w);
-            let hash = hash_one(&iter.hash_seed, lmer);
-
-            let dqmer = DQMer { lmer, pos: i, hash };
-
+            let dqmer = iter.dqmer_at(i);
             iter.enqueue_dqmer(dqmer)
         }
 
@@ -121,7 +140,34 @@ impl<'a, T: BuildHasher> SeqVecMinimizerIter<'a, T> {
     }
 }
 
-impl<T: BuildHasher> Iterator for SeqVecMinimizerIter<'_, T> {
+impl<'a, T: BuildHasher> SeqVecMinimizerIter<'a, T> {
+    pub fn new(sv: SeqVectorSlice<'a>, k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(sv, k, w, hash_seed, LeftMin)
+    }
+}
+
+impl<'a> SeqVecMinimizerIter<'a, NtHashState> {
+    // Same as `new(sv, k, w, NtHashState::new(w))`, rolling the ntHash of
+    // the lmers along the sequence instead of hashing each from scratch
+    pub fn with_nthash(sv: SeqVectorSlice<'a>, k: usize, w: usize) -> Self {
+        Self::build(sv, k, w, NtHashState::new(w), LeftMin, true)
+    }
+}
+
+impl<T: BuildHasher, S: MinimizerScheme> SeqVecMinimizerIter<'_, T, S> {
+    // Like `next`, along with the k-mer the minimizer was selected for
+    pub fn next_with_kmer(&mut self) -> Option<FwKmerMinimizer> {
+        let pos = self.curr_km_i;
+        let mmer = self.next()?;
+        Some(FwKmerMinimizer {
+            km: self.sv.get_kmer(pos, self.k),
+            pos,
+            mmer,
+        })
+    }
+}
+
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for SeqVecMinimizerIter<'_, T, S> {
     type Item = MappedMinimizer;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -129,163 +175,2088 @@ impl<T: BuildHasher> Iterator for SeqVecMinimizerIter<'_, T> {
             let dqmer = self.next_dqmer();
             self.enqueue_dqmer(dqmer);
             let dqmer = self.dq.front().unwrap();
-            let mmer = MappedMinimizer {
-                word: dqmer.lmer,
-                pos: dqmer.pos,
-            };
+            let mmer = MappedMinimizer::new(dqmer.lmer, dqmer.pos);
             self.curr_km_i += 1;
             Some(mmer)
         } else {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.n_kmers() - self.curr_km_i;
+        (len, Some(len))
+    }
 }
 
-// Minimizer iterator does not know how many minimizers there are or its length
-// impl<T: BuildHasher> ExactSizeIterator for SeqVecMinimizerIter<'_, T> {
-//     fn len(&self) -> usize {
-//         self.sv.len() - self.curr_km_i - self.k + 1
-//     }
-// }
+impl<T: BuildHasher, S: MinimizerScheme> ExactSizeIterator for SeqVecMinimizerIter<'_, T, S> {}
 
-#[cfg(test)]
-mod test {
-    use std::collections::hash_map::RandomState;
-    use std::collections::VecDeque;
+// Both strands of an l-mer, ranked by the better of the two
+#[derive(Clone, Debug)]
+struct CanonLmer {
+    pub fw_lmer: u64,
+    pub rc_lmer: u64,
+    pub fw_hash: u64,
+    pub rc_hash: u64,
+}
 
-    use crate::naive_impl::hash::LexHasherState;
+type CanonDQMer = HashedLmer<CanonLmer>;
 
-    use super::*;
+// Yields the canonical minimizer of every k-mer of a sequence, following the
+// same rules as `Kmer::canonical_minimizer_word`: forward wins hash ties at
+// the same position, and the leftmost position on the *canonical* k-mer wins
+// ties between positions. That is the leftmost position in the sequence when
+// the k-mer is canonical, and the rightmost one when its reverse complement is.
+// The minimizer word is the l-mer on the winning strand, and its position is
+// where the l-mer starts in the sequence. With another `MinimizerScheme`,
+// "smaller hash" above becomes "ranks before" in that scheme.
+pub struct CanonicalMinimizerIter<'a, T: BuildHasher, S = LeftMin> {
+    fwq: HashedMinimizerQueue<CanonLmer, S>, // leftmost minima, used when the k-mer is canonical
+    rcq: HashedMinimizerQueue<CanonLmer, RightMin<S>>, // rightmost minima, used when its rc is canonical
+    k: usize,
+    w: usize,
+    curr_km_i: usize,
+    sv: SeqVectorSlice<'a>,
+    hash_seed: T,
+    rolling: Option<NtHashIterator<'a>>, // lmer hashes, see `with_nthash`
+}
 
-    fn dqmers_from_hashes(hashes: &[u64]) -> Vec<DQMer> {
-        hashes
-            .iter()
-            .enumerate()
-            .map(|(i, h)| DQMer::new(0, i, *h))
-            .collect()
+impl<'a, T: BuildHasher, S: MinimizerScheme> CanonicalMinimizerIter<'a, T, S> {
+    // Same as `SeqVecMinimizerIter`, on the better of L and rc(L), except
+    // that `rcq` keeps the rightmost of equal minima.
+    #[inline]
+    fn enqueue_dqmer(&mut self, dqmer: CanonDQMer) {
+        self.fwq.evict_before(self.curr_km_i);
+        self.rcq.evict_before(self.curr_km_i);
+        self.fwq.push(dqmer.clone());
+        self.rcq.push(dqmer);
     }
 
-    fn iter_dq_hashes<T: BuildHasher>(iter: &SeqVecMinimizerIter<T>) -> Vec<u64> {
-        iter.dq.iter().map(|x| x.hash).collect()
+    #[inline]
+    fn dqmer_at(&mut self, pos: usize) -> CanonDQMer {
+        let fw_lmer = self.sv.get_kmer_u64(pos, self.w);
+        let rc_lmer = Kmer::get_reverse_complement_word(fw_lmer, self.w as u8);
+        let (fw_hash, rc_hash) = match self.rolling.as_mut() {
+            Some(rolling) => rolling.next_pair().unwrap(),
+            None => (
+                hash_one(&self.hash_seed, fw_lmer),
+                hash_one(&self.hash_seed, rc_lmer),
+            ),
+        };
+        let lmer = CanonLmer {
+            fw_lmer,
+            rc_lmer,
+            fw_hash,
+            rc_hash,
+        };
+        let hash = if self.rc_better(&lmer) {
+            rc_hash
+        } else {
+            fw_hash
+        };
+        CanonDQMer::new(lmer, pos, hash)
     }
 
-    #[test]
-    fn enqueue_dqmer() {
-        let sv = SeqVector::from(b"");
-        let sv = sv.as_slice();
+    // whether the reverse complement of an l-mer ranks strictly before it
+    #[inline]
+    fn rc_better(&self, lmer: &CanonLmer) -> bool {
+        rc_better(self.fwq.scheme(), lmer)
+    }
+
+    #[inline]
+    fn n_kmers(&self) -> usize {
+        self.sv.len() - self.k + 1
+    }
 
-        let dq = VecDeque::new();
+    pub fn with_scheme(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        scheme: S,
+    ) -> Self {
+        Self::build(sv, k, w, hash_seed, scheme, false)
+    }
 
-        let (k, w) = (4, 2);
-        let hashes = vec![2, 1, 0, 0, 3, 4, 2];
+    // `rolling` must only be set when `hash_seed` is `NtHashState::new(w)`
+    fn build(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        scheme: S,
+        rolling: bool,
+    ) -> Self {
+        check_window(k, w);
+        check_len(&sv, k);
 
-        let mut iter = SeqVecMinimizerIter {
-            sv,
-            dq,
+        let mut iter = Self {
+            fwq: HashedMinimizerQueue::with_scheme(scheme.clone(), k - w + 1),
+            rcq: HashedMinimizerQueue::with_scheme(RightMin(scheme), k - w + 1),
             k,
             w,
             curr_km_i: 0,
-            hash_seed: RandomState::new(),
+            rolling: rolling.then(|| NtHashIterator::new(&sv, w)),
+            sv,
+            hash_seed,
         };
 
-        let dqmers = dqmers_from_hashes(&hashes);
+        // Insert lmers of the k-1 prefix
+        for i in 0..(k - w) {
+            let dqmer = iter.dqmer_at(i);
+            iter.enqueue_dqmer(dqmer);
+        }
 
-        iter.enqueue_dqmer(dqmers[0].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![2]);
+        iter
+    }
+}
 
-        iter.enqueue_dqmer(dqmers[1].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![1]);
+impl<'a, T: BuildHasher> CanonicalMinimizerIter<'a, T> {
+    pub fn new(sv: SeqVectorSlice<'a>, k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(sv, k, w, hash_seed, LeftMin)
+    }
+}
 
-        iter.enqueue_dqmer(dqmers[2].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![0]);
-        iter.curr_km_i = 1;
+impl<'a> CanonicalMinimizerIter<'a, NtHashState> {
+    // Same as `new(sv, k, w, NtHashState::new(w))`, rolling the ntHash of
+    // both strands of the lmers along the sequence
+    pub fn with_nthash(sv: SeqVectorSlice<'a>, k: usize, w: usize) -> Self {
+        Self::build(sv, k, w, NtHashState::new(w), LeftMin, true)
+    }
+}
 
-        iter.enqueue_dqmer(dqmers[3].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![0, 0]);
-        iter.curr_km_i = 2;
+impl<T: BuildHasher, S: MinimizerScheme> CanonicalMinimizerIter<'_, T, S> {
+    // the word of the next k-mer, read on the forward strand, and its
+    // canonical minimizer
+    #[inline]
+    fn next_word_and_minimizer(&mut self) -> Option<(u64, MappedMinimizer)> {
+        if self.curr_km_i < self.n_kmers() {
+            let dqmer = self.dqmer_at(self.curr_km_i + self.k - self.w);
+            self.enqueue_dqmer(dqmer);
 
-        iter.enqueue_dqmer(dqmers[4].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![0, 0, 3]);
-        iter.curr_km_i = 3;
+            let km = self.sv.get_kmer_u64(self.curr_km_i, self.k);
+            let rc = Kmer::get_reverse_complement_word(km, self.k as u8);
 
-        iter.enqueue_dqmer(dqmers[5].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![0, 3, 4]);
-        iter.curr_km_i = 4;
+            self.curr_km_i += 1;
+            Some((km, canonical_front(&self.fwq, &self.rcq, km <= rc)))
+        } else {
+            None
+        }
+    }
 
-        iter.enqueue_dqmer(dqmers[6].clone());
-        assert_eq!(iter_dq_hashes(&iter), vec![2]);
-        iter.curr_km_i = 5;
+    // Like `next`, along with the k-mer the minimizer was selected for
+    pub fn next_with_kmer(&mut self) -> Option<KmerMinimizer> {
+        let pos = self.curr_km_i;
+        let (km, mmer) = self.next_word_and_minimizer()?;
+        Some(KmerMinimizer {
+            km: CanonicalKmer::from_u64(km, self.k as u8),
+            pos,
+            mmer,
+        })
     }
+}
 
-    #[test]
-    fn leftmost_mmer() {
-        let sv = SeqVector::from(b"AAAAAAA");
-        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 5, 3, RandomState::new());
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for CanonicalMinimizerIter<'_, T, S> {
+    type Item = MappedMinimizer;
 
-        let mmers: Vec<MappedMinimizer> = iter.collect();
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_word_and_minimizer().map(|(_, mmer)| mmer)
+    }
 
-        assert_eq!(
-            mmers,
-            vec![
-                MappedMinimizer::new(0, 0),
-                MappedMinimizer::new(0, 1),
-                MappedMinimizer::new(0, 2),
-            ]
-        )
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.n_kmers() - self.curr_km_i;
+        (len, Some(len))
     }
+}
 
-    #[test]
-    fn mmers0() {
-        let sv = SeqVector::from(b"AAACAAA");
-        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 6, 3, LexHasherState::new(6));
+impl<T: BuildHasher, S: MinimizerScheme> ExactSizeIterator for CanonicalMinimizerIter<'_, T, S> {}
 
-        let mmers: Vec<MappedMinimizer> = iter.collect();
+// whether the reverse complement of an l-mer ranks strictly before it
+#[inline]
+fn rc_better<S: MinimizerScheme>(scheme: &S, lmer: &CanonLmer) -> bool {
+    let fw = HashedLmer::new((), 0, lmer.fw_hash);
+    let rc = HashedLmer::new((), 0, lmer.rc_hash);
+    scheme.better(&rc, &fw)
+}
 
-        assert_eq!(
-            mmers,
-            vec![MappedMinimizer::new(0, 0), MappedMinimizer::new(0, 4),]
-        )
+// the canonical minimizer of a k-mer whose l-mers are queued in `fwq` and
+// `rcq`: the forward strand of the canonical k-mer is the forward strand of
+// the sequence iff the k-mer is canonical
+#[inline]
+fn canonical_front<S: MinimizerScheme>(
+    fwq: &HashedMinimizerQueue<CanonLmer, S>,
+    rcq: &HashedMinimizerQueue<CanonLmer, RightMin<S>>,
+    km_is_canonical: bool,
+) -> MappedMinimizer {
+    let scheme = fwq.scheme();
+    let (word, pos, is_fw) = if km_is_canonical {
+        let HashedLmer { lmer, pos, .. } = fwq.front().unwrap();
+        if !rc_better(scheme, lmer) {
+            (lmer.fw_lmer, *pos, true)
+        } else {
+            (lmer.rc_lmer, *pos, false)
+        }
+    } else {
+        let HashedLmer { lmer, pos, .. } = rcq.front().unwrap();
+        // the forward l-mer ranks strictly before its reverse complement
+        let fw = HashedLmer::new((), 0, lmer.fw_hash);
+        let rc = HashedLmer::new((), 0, lmer.rc_hash);
+        if !scheme.better(&fw, &rc) {
+            (lmer.rc_lmer, *pos, false)
+        } else {
+            (lmer.fw_lmer, *pos, true)
+        }
+    };
+    MappedMinimizer::new_stranded(word, pos, is_fw)
+}
+
+// A canonical k-mer of a sequence, starting at `pos`, with its canonical
+// minimizer; `mmer.is_fw()` tells which strand of the sequence it was read on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KmerMinimizer {
+    pub km: CanonicalKmer,
+    pub pos: usize,
+    pub mmer: MappedMinimizer,
+}
+
+// A k-mer of a sequence, starting at `pos`, with its forward strand
+// minimizer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FwKmerMinimizer {
+    pub km: Kmer,
+    pub pos: usize,
+    pub mmer: MappedMinimizer,
+}
+
+// Yields every valid k-mer of an ASCII sequence along with its canonical
+// minimizer, the same as `CanonicalMinimizerIter` would select, in a single
+// pass over the bytes: the k-mer and l-mer strands are rolled base by base,
+// and each l-mer hashed once. Bases other than `ACGTacgt` (e.g. `N`s)
+// restart the window and empty the minimizer queues. Positions are those
+// in `seq`.
+pub struct CanonicalKmerMinimizerIter<'a, T: BuildHasher, S = LeftMin> {
+    seq: &'a [u8],
+    k: usize,
+    w: usize,
+    next_i: usize, // the next byte of `seq` to read
+    valid: usize,  // consecutive valid bases up to `next_i`
+    km: CanonicalKmer,
+    lmer: CanonicalKmer,
+    fwq: HashedMinimizerQueue<CanonLmer, S>,
+    rcq: HashedMinimizerQueue<CanonLmer, RightMin<S>>,
+    hash_seed: T,
+}
+
+impl<'a, T: BuildHasher> CanonicalKmerMinimizerIter<'a, T> {
+    pub fn new(seq: &'a [u8], k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(seq, k, w, hash_seed, LeftMin)
     }
+}
 
-    #[test]
-    fn mmers1() {
-        let sv = SeqVector::from(b"AACCAAA");
-        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 5, 3, LexHasherState::new(5));
+impl<'a, T: BuildHasher, S: MinimizerScheme> CanonicalKmerMinimizerIter<'a, T, S> {
+    pub fn with_scheme(seq: &'a [u8], k: usize, w: usize, hash_seed: T, scheme: S) -> Self {
+        check_window(k, w);
+        Self {
+            seq,
+            k,
+            w,
+            next_i: 0,
+            valid: 0,
+            km: CanonicalKmer::from_u64(0, k as u8),
+            lmer: CanonicalKmer::from_u64(0, w as u8),
+            fwq: HashedMinimizerQueue::with_scheme(scheme.clone(), k - w + 1),
+            rcq: HashedMinimizerQueue::with_scheme(RightMin(scheme), k - w + 1),
+            hash_seed,
+        }
+    }
 
-        let mmers: Vec<MappedMinimizer> = iter.collect();
+    // push the l-mer ending at the last read base
+    #[inline]
+    fn enqueue_lmer(&mut self) {
+        let pos = self.next_i - self.w;
+        let lmer = CanonLmer {
+            fw_lmer: self.lmer.get_fw_word(),
+            rc_lmer: self.lmer.get_rc_word(),
+            fw_hash: hash_one(&self.hash_seed, self.lmer.get_fw_word()),
+            rc_hash: hash_one(&self.hash_seed, self.lmer.get_rc_word()),
+        };
+        let hash = if rc_better(self.fwq.scheme(), &lmer) {
+            lmer.rc_hash
+        } else {
+            lmer.fw_hash
+        };
+        // the l-mers left of the k-mer ending here
+        let km_start = self.next_i.saturating_sub(self.k);
+        self.fwq.evict_before(km_start);
+        self.rcq.evict_before(km_start);
+        self.fwq.push(CanonDQMer::new(lmer.clone(), pos, hash));
+        self.rcq.push(CanonDQMer::new(lmer, pos, hash));
+    }
+}
 
-        let aac = 0b010000;
-        let acc = 0b010100;
-        let aaa = 0b000000;
-        assert_eq!(
-            mmers,
-            vec![
-                MappedMinimizer::new(aac, 0),
-                MappedMinimizer::new(acc, 1),
-                MappedMinimizer::new(aaa, 4),
-            ]
-        )
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for CanonicalKmerMinimizerIter<'_, T, S> {
+    type Item = KmerMinimizer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_i < self.seq.len() {
+            let b = encode_binary_u8(self.seq[self.next_i]);
+            self.next_i += 1;
+
+            if !is_valid_nuc(b) {
+                self.valid = 0;
+                self.fwq.clear();
+                self.rcq.clear();
+                continue;
+            }
+            self.km.append_base(b);
+            self.lmer.append_base(b);
+            self.valid += 1;
+
+            if self.valid >= self.w {
+                self.enqueue_lmer();
+            }
+            if self.valid >= self.k {
+                let is_canonical = self.km.get_fw_word() <= self.km.get_rc_word();
+                let mmer = canonical_front(&self.fwq, &self.rcq, is_canonical);
+                return Some(KmerMinimizer {
+                    km: self.km.clone(),
+                    pos: self.next_i - self.k,
+                    mmer,
+                });
+            }
+        }
+        None
     }
+}
 
-    #[test]
-    fn mmers2() {
-        let sv = SeqVector::from(b"CACACACCAC");
-        // let bh = RandomState::new();
-        let bh = LexHasherState::new(3);
-        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 7, 3, bh);
+// Yields the minimizer of every valid k-mer of an ASCII sequence, the same as
+// `SeqVecMinimizerIter` would select on the packed sequence, without packing
+// it first: the l-mers are rolled base by base and each is hashed once.
+// Bases other than `ACGTacgt` (e.g. `N`s) restart the window and empty the
+// minimizer queue, so k-mers containing them are skipped. Positions are
+// those in `seq`.
+pub struct MinimizerIter<'a, T: BuildHasher, S = LeftMin> {
+    seq: &'a [u8],
+    k: usize,
+    w: usize,
+    next_i: usize, // the next byte of `seq` to read
+    valid: usize,  // consecutive valid bases up to `next_i`
+    km: Kmer,
+    lmer: Kmer,
+    dq: HashedMinimizerQueue<u64, S>,
+    hash_seed: T,
+}
 
-        let mmers: Vec<MappedMinimizer> = iter.collect();
+impl<'a, T: BuildHasher> MinimizerIter<'a, T> {
+    pub fn from_bytes(seq: &'a [u8], k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(seq, k, w, hash_seed, LeftMin)
+    }
+}
 
-        // let aac = 0b010000;
-        let aca = 0b000100;
-        assert_eq!(
-            mmers,
-            vec![
-                MappedMinimizer::new(aca, 1),
-                MappedMinimizer::new(aca, 1),
-                MappedMinimizer::new(aca, 3),
-                MappedMinimizer::new(aca, 3),
-            ]
-        )
+impl<'a, T: BuildHasher, S: MinimizerScheme> MinimizerIter<'a, T, S> {
+    pub fn with_scheme(seq: &'a [u8], k: usize, w: usize, hash_seed: T, scheme: S) -> Self {
+        check_window(k, w);
+        Self {
+            seq,
+            k,
+            w,
+            next_i: 0,
+            valid: 0,
+            km: Kmer::from_u64(0, k as u8),
+            lmer: Kmer::from_u64(0, w as u8),
+            dq: HashedMinimizerQueue::with_scheme(scheme, k - w + 1),
+            hash_seed,
+        }
+    }
+
+    // Like `next`, along with the k-mer the minimizer was selected for
+    pub fn next_with_kmer(&mut self) -> Option<FwKmerMinimizer> {
+        let mmer = self.next()?;
+        Some(FwKmerMinimizer {
+            km: self.km.clone(),
+            pos: self.next_i - self.k,
+            mmer,
+        })
+    }
+}
+
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for MinimizerIter<'_, T, S> {
+    type Item = MappedMinimizer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_i < self.seq.len() {
+            let b = encode_binary_u8(self.seq[self.next_i]);
+            self.next_i += 1;
+
+            if !is_valid_nuc(b) {
+                self.valid = 0;
+                self.dq.clear();
+                continue;
+            }
+            self.km.append_base(b);
+            self.lmer.append_base(b);
+            self.valid += 1;
+
+            if self.valid >= self.w {
+                let lmer = self.lmer.into_u64();
+                let hash = hash_one(&self.hash_seed, lmer);
+                // the l-mers left of the k-mer ending here
+                self.dq.evict_before(self.next_i.saturating_sub(self.k));
+                self.dq.push(DQMer::new(lmer, self.next_i - self.w, hash));
+            }
+            if self.valid >= self.k {
+                let front = self.dq.front().unwrap();
+                return Some(MappedMinimizer::new(front.lmer, front.pos));
+            }
+        }
+        None
+    }
+}
+
+// Empirical minimizer statistics of a sequence, e.g. to choose (k, w).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MinimizerStats {
+    pub n_kmers: usize,
+    // distinct positions selected as minimizers
+    pub n_minimizers: usize,
+    pub n_super_kmers: usize,
+    // the longest super-k-mer, in k-mers
+    pub max_super_kmer_len: usize,
+    // `super_kmer_len_hist[l]` super-k-mers are made of `l` k-mers
+    pub super_kmer_len_hist: Vec<usize>,
+}
+
+impl MinimizerStats {
+    // the fraction of k-mers whose minimizer is a new position
+    pub fn density(&self) -> f64 {
+        if self.n_kmers == 0 {
+            return 0.0;
+        }
+        self.n_minimizers as f64 / self.n_kmers as f64
+    }
+
+    // the mean super-k-mer length, in k-mers
+    pub fn mean_super_kmer_len(&self) -> f64 {
+        if self.n_super_kmers == 0 {
+            return 0.0;
+        }
+        self.n_kmers as f64 / self.n_super_kmers as f64
+    }
+
+    fn from_super_kmers<I: Iterator<Item = SuperKmerOcc>>(occs: I) -> Self {
+        let mut stats = Self::default();
+        let mut last_pos = None;
+
+        for occ in occs {
+            let len = occ.n_kmers() as usize;
+            stats.n_kmers += len;
+            stats.n_super_kmers += 1;
+            stats.max_super_kmer_len = stats.max_super_kmer_len.max(len);
+            if stats.super_kmer_len_hist.len() <= len {
+                stats.super_kmer_len_hist.resize(len + 1, 0);
+            }
+            stats.super_kmer_len_hist[len] += 1;
+
+            // minimizer positions never decrease along the sequence, but
+            // long runs are split into super-k-mers sharing their minimizer
+            if last_pos != Some(occ.mmer_pos()) {
+                stats.n_minimizers += 1;
+                last_pos = Some(occ.mmer_pos());
+            }
+        }
+
+        stats
+    }
+}
+
+// Statistics of the canonical minimizers and super-k-mers of `sv`, computed
+// while streaming over them.
+pub fn minimizer_stats<T>(sv: &SeqVectorSlice, k: usize, w: usize, hash_seed: T) -> MinimizerStats
+where
+    T: BuildHasher,
+{
+    MinimizerStats::from_super_kmers(CanonicalSuperKmerIterator::new(sv.clone(), k, w, hash_seed))
+}
+
+// Same as `minimizer_stats`, for forward strand minimizers.
+pub fn minimizer_stats_fw<T>(
+    sv: &SeqVectorSlice,
+    k: usize,
+    w: usize,
+    hash_seed: T,
+) -> MinimizerStats
+where
+    T: BuildHasher,
+{
+    MinimizerStats::from_super_kmers(SuperKmerIterator::new(sv.clone(), k, w, hash_seed))
+}
+
+// Canonical minimizers of every valid k-mer of an ASCII sequence that may
+// contain bases other than `ACGTacgt` (e.g. `N`s). The sequence is split at
+// such bases, segments shorter than `k` are skipped, and positions are those
+// in `seq`. Each segment is packed into its own `SeqVector` in turn.
+pub fn canonical_minimizers_from_ascii<'a, T>(
+    seq: &'a [u8],
+    k: usize,
+    w: usize,
+    hash_seed: T,
+) -> impl Iterator<Item = MappedMinimizer> + 'a
+where
+    T: BuildHasher + Clone + 'a,
+{
+    let segments = seq
+        .split(|&c| !is_valid_nuc(encode_binary_u8(c)))
+        .scan(0, |start, segment| {
+            let seg_start = *start;
+            *start += segment.len() + 1; // skip the invalid base
+            Some((seg_start, segment))
+        });
+
+    segments
+        .filter(move |(_, segment)| segment.len() >= k)
+        .flat_map(move |(start, segment)| {
+            let sv = SeqVector::from(segment);
+            let mmers: Vec<MappedMinimizer> = sv
+                .iter_canonical_minimizers(k, w, hash_seed.clone())
+                .map(|mmer| MappedMinimizer {
+                    pos: start + mmer.pos,
+                    ..mmer
+                })
+                .collect();
+            mmers
+        })
+}
+
+// A super-k-mer: a run of `n_kmers` consecutive k-mers, the first of which
+// starts at `start_pos`, that all share the same minimizer occurrence.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuperKmerOcc {
+    mmer: MappedMinimizer,
+    start_pos: usize,
+    n_kmers: u8,
+}
+
+impl SuperKmerOcc {
+    pub fn new(mmer: MappedMinimizer, start_pos: usize, n_kmers: u8) -> Self {
+        Self {
+            mmer,
+            start_pos,
+            n_kmers,
+        }
+    }
+
+    pub fn mmer(&self) -> &MappedMinimizer {
+        &self.mmer
+    }
+
+    pub fn mmer_word(&self) -> u64 {
+        self.mmer.as_u64()
+    }
+
+    pub fn mmer_pos(&self) -> usize {
+        self.mmer.pos
+    }
+
+    pub fn mmer_is_fw(&self) -> bool {
+        self.mmer.is_fw()
+    }
+
+    pub fn start_pos(&self) -> usize {
+        self.start_pos
+    }
+
+    pub fn n_kmers(&self) -> u8 {
+        self.n_kmers
+    }
+
+    // one past the last base of the super-k-mer, for k-mers of length `k`
+    pub fn end_pos(&self, k: usize) -> usize {
+        self.start_pos + self.n_kmers as usize + k - 1
+    }
+
+    // Shifts the super-k-mer and its minimizer `offset` bases to the right,
+    // e.g. from the coordinates of a slice to those of the whole sequence.
+    pub fn inc_pos(&mut self, offset: usize) {
+        self.start_pos += offset;
+        self.mmer.pos += offset;
+    }
+}
+
+// Super-k-mers of canonical minimizers are represented the same way.
+pub type CanonicalSuperKmerOcc = SuperKmerOcc;
+
+// Group the k-mers yielded by a minimizer iterator, the first of which is the
+// `start_pos`-th k-mer, into a super-k-mer. Runs longer than `u8::MAX` k-mers
+// are split into several occurrences of the same minimizer.
+#[inline]
+fn next_super_kmer<I>(mmers: &mut Peekable<I>, start_pos: usize) -> Option<SuperKmerOcc>
+where
+    I: Iterator<Item = MappedMinimizer>,
+{
+    let mmer = mmers.next()?;
+    let mut n_kmers = 1;
+    while n_kmers < u8::MAX && mmers.peek() == Some(&mmer) {
+        mmers.next();
+        n_kmers += 1;
+    }
+
+    Some(SuperKmerOcc::new(mmer, start_pos, n_kmers))
+}
+
+// Super-k-mers with respect to forward strand minimizers, with the same
+// (leftmost) tie-breaking as `SeqVecMinimizerIter`.
+pub struct SuperKmerIterator<'a, T: BuildHasher, S: MinimizerScheme = LeftMin> {
+    mmers: Peekable<SeqVecMinimizerIter<'a, T, S>>,
+    sv: SeqVectorSlice<'a>,
+    k: usize,
+    curr_km_i: usize,
+}
+
+impl<'a, T: BuildHasher> SuperKmerIterator<'a, T> {
+    pub fn new(sv: SeqVectorSlice<'a>, k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(sv, k, w, hash_seed, LeftMin)
+    }
+}
+
+impl<'a> SuperKmerIterator<'a, NtHashState> {
+    // Super-k-mers of `SeqVecMinimizerIter::with_nthash`
+    pub fn with_nthash(sv: SeqVectorSlice<'a>, k: usize, w: usize) -> Self {
+        Self {
+            mmers: SeqVecMinimizerIter::with_nthash(sv.clone(), k, w).peekable(),
+            sv,
+            k,
+            curr_km_i: 0,
+        }
+    }
+}
+
+impl<'a, T: BuildHasher, S: MinimizerScheme> SuperKmerIterator<'a, T, S> {
+    pub fn with_scheme(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        scheme: S,
+    ) -> Self {
+        Self {
+            mmers: SeqVecMinimizerIter::with_scheme(sv.clone(), k, w, hash_seed, scheme).peekable(),
+            sv,
+            k,
+            curr_km_i: 0,
+        }
+    }
+
+    // the bases spanned by the k-mers of `occ`
+    pub fn seq_of(&self, occ: &SuperKmerOcc) -> SeqVectorSlice<'a> {
+        self.sv.slice(occ.start_pos(), occ.end_pos(self.k))
+    }
+
+    // the k-mers of `occ`
+    pub fn as_kmer_iter(&self, occ: &SuperKmerOcc) -> SeqVecKmerIterator<'a> {
+        SeqVecKmerIterator::new(self.seq_of(occ), self.k)
+    }
+}
+
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for SuperKmerIterator<'_, T, S> {
+    type Item = SuperKmerOcc;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let occ = next_super_kmer(&mut self.mmers, self.curr_km_i)?;
+        self.curr_km_i += occ.n_kmers() as usize;
+        Some(occ)
+    }
+}
+
+// Super-k-mers with respect to canonical minimizers, with the same
+// tie-breaking as `CanonicalMinimizerIter`.
+pub struct CanonicalSuperKmerIterator<'a, T: BuildHasher, S: MinimizerScheme = LeftMin> {
+    mmers: Peekable<CanonicalMinimizerIter<'a, T, S>>,
+    sv: SeqVectorSlice<'a>,
+    k: usize,
+    curr_km_i: usize,
+}
+
+impl<'a, T: BuildHasher> CanonicalSuperKmerIterator<'a, T> {
+    pub fn new(sv: SeqVectorSlice<'a>, k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(sv, k, w, hash_seed, LeftMin)
+    }
+}
+
+impl<'a> CanonicalSuperKmerIterator<'a, NtHashState> {
+    // Super-k-mers of `CanonicalMinimizerIter::with_nthash`
+    pub fn with_nthash(sv: SeqVectorSlice<'a>, k: usize, w: usize) -> Self {
+        Self {
+            mmers: CanonicalMinimizerIter::with_nthash(sv.clone(), k, w).peekable(),
+            sv,
+            k,
+            curr_km_i: 0,
+        }
+    }
+}
+
+impl<'a, T: BuildHasher, S: MinimizerScheme> CanonicalSuperKmerIterator<'a, T, S> {
+    pub fn with_scheme(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        scheme: S,
+    ) -> Self {
+        Self {
+            mmers: CanonicalMinimizerIter::with_scheme(sv.clone(), k, w, hash_seed, scheme)
+                .peekable(),
+            sv,
+            k,
+            curr_km_i: 0,
+        }
+    }
+
+    // the bases spanned by the k-mers of `occ`
+    pub fn seq_of(&self, occ: &CanonicalSuperKmerOcc) -> SeqVectorSlice<'a> {
+        self.sv.slice(occ.start_pos(), occ.end_pos(self.k))
+    }
+
+    // the k-mers of `occ`, as they appear in the sequence (not canonicalized)
+    pub fn as_kmer_iter(&self, occ: &CanonicalSuperKmerOcc) -> SeqVecKmerIterator<'a> {
+        SeqVecKmerIterator::new(self.seq_of(occ), self.k)
+    }
+}
+
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for CanonicalSuperKmerIterator<'_, T, S> {
+    type Item = CanonicalSuperKmerOcc;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let occ = next_super_kmer(&mut self.mmers, self.curr_km_i)?;
+        self.curr_km_i += occ.n_kmers() as usize;
+        Some(occ)
+    }
+}
+
+// Super-k-mers with respect to canonical minimizers of an ASCII sequence
+// that may contain bases other than `ACGTacgt` (e.g. `N`s in reads), with
+// the same tie-breaking as `CanonicalSuperKmerIterator`. An invalid base
+// ends the current super-k-mer and the next one starts at the first k-mer
+// after it. Positions are those in `seq`.
+pub struct CanonicalSuperKmerFromBytesIter<'a, T: BuildHasher, S: MinimizerScheme = LeftMin> {
+    kmers: Peekable<CanonicalKmerMinimizerIter<'a, T, S>>,
+    seq: &'a [u8],
+    k: usize,
+}
+
+impl<'a, T: BuildHasher> CanonicalSuperKmerFromBytesIter<'a, T> {
+    pub fn new(seq: &'a [u8], k: usize, w: usize, hash_seed: T) -> Self {
+        Self::with_scheme(seq, k, w, hash_seed, LeftMin)
+    }
+}
+
+impl<'a, T: BuildHasher, S: MinimizerScheme> CanonicalSuperKmerFromBytesIter<'a, T, S> {
+    pub fn with_scheme(seq: &'a [u8], k: usize, w: usize, hash_seed: T, scheme: S) -> Self {
+        Self {
+            kmers: CanonicalKmerMinimizerIter::with_scheme(seq, k, w, hash_seed, scheme).peekable(),
+            seq,
+            k,
+        }
+    }
+
+    // the bases spanned by the k-mers of `occ`
+    pub fn seq_of(&self, occ: &CanonicalSuperKmerOcc) -> &'a [u8] {
+        &self.seq[occ.start_pos()..occ.end_pos(self.k)]
+    }
+}
+
+impl<T: BuildHasher, S: MinimizerScheme> Iterator for CanonicalSuperKmerFromBytesIter<'_, T, S> {
+    type Item = CanonicalSuperKmerOcc;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.kmers.next()?;
+        let mut n_kmers = 1;
+        // the k-mers after an invalid base do not follow the previous ones
+        while n_kmers < u8::MAX
+            && self.kmers.peek().map_or(false, |next| {
+                next.mmer == first.mmer && next.pos == first.pos + n_kmers as usize
+            })
+        {
+            self.kmers.next();
+            n_kmers += 1;
+        }
+
+        Some(SuperKmerOcc::new(first.mmer, first.pos, n_kmers))
+    }
+}
+
+// Syncmers (Edgar 2021): a k-mer is selected if the minimal s-mer among its
+// k - s + 1 s-mers (leftmost on ties) starts at one of the accepted
+// `offsets`, e.g. `[0]` for open syncmers and `[0, k - s]` for closed ones.
+// Unlike minimizers, whether a k-mer is selected only depends on the k-mer
+// itself. Yields the selected k-mers and their positions in the sequence.
+pub struct SyncmerIter<'a, T: BuildHasher> {
+    smers: SeqVecMinimizerIter<'a, T>,
+    sv: SeqVectorSlice<'a>,
+    k: usize,
+    offsets: Vec<usize>,
+    curr_km_i: usize,
+}
+
+impl<'a, T: BuildHasher> SyncmerIter<'a, T> {
+    pub fn new(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        s: usize,
+        offsets: &[usize],
+        hash_seed: T,
+    ) -> Self {
+        assert!(s < k, "s must be smaller than k");
+        assert!(offsets.iter().all(|&o| o <= k - s));
+        Self {
+            smers: SeqVecMinimizerIter::new(sv.clone(), k, s, hash_seed),
+            sv,
+            k,
+            offsets: offsets.to_vec(),
+            curr_km_i: 0,
+        }
+    }
+
+    pub fn open(sv: SeqVectorSlice<'a>, k: usize, s: usize, hash_seed: T) -> Self {
+        Self::new(sv, k, s, &[0], hash_seed)
+    }
+
+    pub fn closed(sv: SeqVectorSlice<'a>, k: usize, s: usize, hash_seed: T) -> Self {
+        Self::new(sv, k, s, &[0, k - s], hash_seed)
+    }
+}
+
+impl<T: BuildHasher> Iterator for SyncmerIter<'_, T> {
+    type Item = (Kmer, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for smer in self.smers.by_ref() {
+            let pos = self.curr_km_i;
+            self.curr_km_i += 1;
+            if self.offsets.contains(&(smer.pos - pos)) {
+                return Some((self.sv.get_kmer(pos, self.k), pos));
+            }
+        }
+        None
+    }
+}
+
+// Syncmers decided on the canonical form of each k-mer, so that a k-mer and
+// its reverse complement are both selected or both skipped: s-mers are
+// ranked by min(h(S), h(rc(S))) and offsets are taken on the canonical
+// k-mer, as in `Kmer::canonical_minimizer`. Yields the canonical k-mers
+// selected and their positions in the sequence.
+pub struct CanonicalSyncmerIter<'a, T: BuildHasher> {
+    smers: CanonicalMinimizerIter<'a, T>,
+    sv: SeqVectorSlice<'a>,
+    k: usize,
+    s: usize,
+    offsets: Vec<usize>,
+    curr_km_i: usize,
+}
+
+impl<'a, T: BuildHasher> CanonicalSyncmerIter<'a, T> {
+    pub fn new(
+        sv: SeqVectorSlice<'a>,
+        k: usize,
+        s: usize,
+        offsets: &[usize],
+        hash_seed: T,
+    ) -> Self {
+        assert!(s < k, "s must be smaller than k");
+        assert!(offsets.iter().all(|&o| o <= k - s));
+        Self {
+            smers: CanonicalMinimizerIter::new(sv.clone(), k, s, hash_seed),
+            sv,
+            k,
+            s,
+            offsets: offsets.to_vec(),
+            curr_km_i: 0,
+        }
+    }
+
+    pub fn open(sv: SeqVectorSlice<'a>, k: usize, s: usize, hash_seed: T) -> Self {
+        Self::new(sv, k, s, &[0], hash_seed)
+    }
+
+    pub fn closed(sv: SeqVectorSlice<'a>, k: usize, s: usize, hash_seed: T) -> Self {
+        Self::new(sv, k, s, &[0, k - s], hash_seed)
+    }
+}
+
+impl<T: BuildHasher> Iterator for CanonicalSyncmerIter<'_, T> {
+    type Item = (Kmer, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for smer in self.smers.by_ref() {
+            let pos = self.curr_km_i;
+            self.curr_km_i += 1;
+
+            let km = self.sv.get_kmer(pos, self.k);
+            let canon = km.to_canonical();
+            // offset of the s-mer on the canonical k-mer
+            let offset = if canon == km {
+                smer.pos - pos
+            } else {
+                self.k - self.s - (smer.pos - pos)
+            };
+            if self.offsets.contains(&offset) {
+                return Some((canon, pos));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::naive_impl::canonical_kmer_iterator::{CanonicalKmerIterator, CanonicalKmerPos};
+    use crate::naive_impl::hash::{LexHasherState, NtHashState, SeededState};
+    use crate::naive_impl::minimizer_queue::ModMinimizer;
+    use crate::test_utils::{random_seq, rc};
+
+    use super::*;
+
+    fn dqmers_from_hashes(hashes: &[u64]) -> Vec<DQMer> {
+        hashes
+            .iter()
+            .enumerate()
+            .map(|(i, h)| DQMer::new(0, i, *h))
+            .collect()
+    }
+
+    fn iter_dq_hashes<T: BuildHasher>(iter: &SeqVecMinimizerIter<T>) -> Vec<u64> {
+        iter.dq.iter().map(|x| x.hash).collect()
+    }
+
+    #[test]
+    fn enqueue_dqmer() {
+        let sv = SeqVector::from(b"");
+        let sv = sv.as_slice();
+
+        let dq = HashedMinimizerQueue::new();
+
+        let (k, w) = (4, 2);
+        let hashes = vec![2, 1, 0, 0, 3, 4, 2];
+
+        let mut iter = SeqVecMinimizerIter {
+            sv,
+            dq,
+            k,
+            w,
+            curr_km_i: 0,
+            hash_seed: SeededState(7),
+            rolling: None,
+        };
+
+        let dqmers = dqmers_from_hashes(&hashes);
+
+        iter.enqueue_dqmer(dqmers[0].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![2]);
+
+        iter.enqueue_dqmer(dqmers[1].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![1]);
+
+        iter.enqueue_dqmer(dqmers[2].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![0]);
+        iter.curr_km_i = 1;
+
+        iter.enqueue_dqmer(dqmers[3].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![0, 0]);
+        iter.curr_km_i = 2;
+
+        iter.enqueue_dqmer(dqmers[4].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![0, 0, 3]);
+        iter.curr_km_i = 3;
+
+        iter.enqueue_dqmer(dqmers[5].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![0, 3, 4]);
+        iter.curr_km_i = 4;
+
+        iter.enqueue_dqmer(dqmers[6].clone());
+        assert_eq!(iter_dq_hashes(&iter), vec![2]);
+        iter.curr_km_i = 5;
+    }
+
+    #[test]
+    fn leftmost_mmer() {
+        let sv = SeqVector::from(b"AAAAAAA");
+        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 5, 3, SeededState(7));
+
+        let mmers: Vec<MappedMinimizer> = iter.collect();
+
+        assert_eq!(
+            mmers,
+            vec![
+                MappedMinimizer::new(0, 0),
+                MappedMinimizer::new(0, 1),
+                MappedMinimizer::new(0, 2),
+            ]
+        )
+    }
+
+    #[test]
+    fn mmers0() {
+        let sv = SeqVector::from(b"AAACAAA");
+        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 6, 3, LexHasherState::new(6));
+
+        let mmers: Vec<MappedMinimizer> = iter.collect();
+
+        assert_eq!(
+            mmers,
+            vec![MappedMinimizer::new(0, 0), MappedMinimizer::new(0, 4),]
+        )
+    }
+
+    #[test]
+    fn mmers1() {
+        let sv = SeqVector::from(b"AACCAAA");
+        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 5, 3, LexHasherState::new(5));
+
+        let mmers: Vec<MappedMinimizer> = iter.collect();
+
+        let aac = 0b010000;
+        let acc = 0b010100;
+        let aaa = 0b000000;
+        assert_eq!(
+            mmers,
+            vec![
+                MappedMinimizer::new(aac, 0),
+                MappedMinimizer::new(acc, 1),
+                MappedMinimizer::new(aaa, 4),
+            ]
+        )
+    }
+
+    #[test]
+    fn mmers2() {
+        let sv = SeqVector::from(b"CACACACCAC");
+        // let bh = RandomState::new();
+        let bh = LexHasherState::new(3);
+        let iter = SeqVecMinimizerIter::new(sv.as_slice(), 7, 3, bh);
+
+        let mmers: Vec<MappedMinimizer> = iter.collect();
+
+        // let aac = 0b010000;
+        let aca = 0b000100;
+        assert_eq!(
+            mmers,
+            vec![
+                MappedMinimizer::new(aca, 1),
+                MappedMinimizer::new(aca, 1),
+                MappedMinimizer::new(aca, 3),
+                MappedMinimizer::new(aca, 3),
+            ]
+        )
+    }
+
+    #[test]
+    fn break_ties_on_canonical() {
+        let (k, w) = (5, 3);
+        let aaa = 0b000000;
+
+        // every k-mer is canonical: leftmost AAA on the forward strand
+        let sv = SeqVector::from(b"AAAAAAA");
+        let iter = CanonicalMinimizerIter::new(sv.as_slice(), k, w, LexHasherState::new(w));
+        let mmers: Vec<MappedMinimizer> = iter.collect();
+        assert_eq!(
+            mmers,
+            vec![
+                MappedMinimizer::new_stranded(aaa, 0, true),
+                MappedMinimizer::new_stranded(aaa, 1, true),
+                MappedMinimizer::new_stranded(aaa, 2, true),
+            ]
+        );
+
+        // no k-mer is canonical: the leftmost AAA on the reverse complement
+        // is the rightmost TTT on the sequence
+        let sv = SeqVector::from(b"TTTTTTT");
+        let iter = CanonicalMinimizerIter::new(sv.as_slice(), k, w, LexHasherState::new(w));
+        let mmers: Vec<MappedMinimizer> = iter.collect();
+        assert_eq!(
+            mmers,
+            vec![
+                MappedMinimizer::new_stranded(aaa, 2, false),
+                MappedMinimizer::new_stranded(aaa, 3, false),
+                MappedMinimizer::new_stranded(aaa, 4, false),
+            ]
+        );
+        assert!(mmers.iter().all(|mm| !mm.is_fw()));
+    }
+
+    #[test]
+    fn canonical_mmers_match_rc() {
+        let s = b"CACACACCACGTGTGGTGATTACA";
+        let (k, w) = (7, 3);
+        let sv = SeqVector::from(s);
+        let rc = rc(s);
+        let sv_rc = SeqVector::from(rc.as_slice());
+
+        let mmers: Vec<MappedMinimizer> = sv
+            .iter_canonical_minimizers(k, w, LexHasherState::new(w))
+            .collect();
+        let mut mmers_rc: Vec<MappedMinimizer> = sv_rc
+            .iter_canonical_minimizers(k, w, LexHasherState::new(w))
+            .map(|mm| MappedMinimizer::new_stranded(mm.as_u64(), s.len() - w - mm.pos, !mm.is_fw()))
+            .collect();
+        mmers_rc.reverse();
+
+        assert_eq!(mmers, mmers_rc);
+    }
+
+    // a hasher borrowing its parameters
+    struct BorrowedState<'a>(&'a SeededState);
+
+    impl BuildHasher for BorrowedState<'_> {
+        type Hasher = <SeededState as BuildHasher>::Hasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            self.0.build_hasher()
+        }
+    }
+
+    #[test]
+    fn borrowed_hasher() {
+        let s = random_seq(200, 5);
+        let sv = SeqVector::from(s.as_slice());
+        let state = SeededState(11);
+        let (k, w) = (21, 9);
+        assert!(sv
+            .iter_canonical_minimizers(k, w, BorrowedState(&state))
+            .eq(sv.iter_canonical_minimizers(k, w, state)));
+        assert!(sv
+            .as_slice()
+            .iter_minimizers_with_global_pos(k, w, BorrowedState(&state))
+            .eq(sv.iter_minimizers(k, w, state)));
+        assert!(sv
+            .iter_canonical_super_kmers(k, w, BorrowedState(&state))
+            .eq(sv.iter_canonical_super_kmers(k, w, state)));
+    }
+
+    #[test]
+    fn nthash_mmers_match_brute_force() {
+        let s = b"ACGTTGCAAGGCTTACAGATTTAGGACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+        let sv = SeqVector::from(s);
+
+        for (k, w) in [(5, 3), (11, 5), (31, 15), (31, 31)] {
+            let state = NtHashState::new(w);
+            let kmers = sv.iter_kmers(k);
+            let mmers = sv.iter_minimizers(k, w, state.clone());
+            for (i, (km, mmer)) in kmers.zip(mmers).enumerate() {
+                let (mm, o) = km.minimizer(w, &state);
+                assert_eq!(mmer, MappedMinimizer::new(mm.into_u64(), i + o));
+            }
+            // rolling the hashes gives the same minimizers
+            assert!(sv
+                .iter_minimizers(k, w, state.clone())
+                .eq(SeqVecMinimizerIter::with_nthash(sv.as_slice(), k, w)));
+            assert!(sv
+                .iter_canonical_minimizers(k, w, state.clone())
+                .eq(CanonicalMinimizerIter::with_nthash(sv.as_slice(), k, w)));
+            assert!(sv
+                .iter_super_kmers(k, w, state.clone())
+                .eq(SuperKmerIterator::with_nthash(sv.as_slice(), k, w)));
+            assert!(sv
+                .iter_canonical_super_kmers(k, w, state.clone())
+                .eq(CanonicalSuperKmerIterator::with_nthash(sv.as_slice(), k, w)));
+
+            let kmers = sv.iter_kmers(k);
+            let mmers = sv.iter_canonical_minimizers(k, w, state.clone());
+            for (i, (km, mmer)) in kmers.zip(mmers).enumerate() {
+                let (mm, o, is_fw) = km.canonical_minimizer(w, &state);
+                let pos = if km.is_canonical() {
+                    i + o
+                } else {
+                    i + k - w - o
+                };
+                let is_fw = is_fw == km.is_canonical();
+                assert_eq!(
+                    mmer,
+                    MappedMinimizer::new_stranded(mm.into_u64(), pos, is_fw)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn super_kmers() {
+        let sv = SeqVector::from(b"AACCAAA");
+        let (k, w) = (5, 3);
+        let iter = SuperKmerIterator::new(sv.as_slice(), k, w, LexHasherState::new(w));
+        let occs: Vec<SuperKmerOcc> = iter.collect();
+
+        let aac = 0b010000;
+        let acc = 0b010100;
+        let aaa = 0b000000;
+        assert_eq!(
+            occs,
+            vec![
+                SuperKmerOcc::new(MappedMinimizer::new(aac, 0), 0, 1),
+                SuperKmerOcc::new(MappedMinimizer::new(acc, 1), 1, 1),
+                SuperKmerOcc::new(MappedMinimizer::new(aaa, 4), 2, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn super_kmers2() {
+        let sv = SeqVector::from(b"CACACACCAC");
+        let (k, w) = (7, 3);
+        let iter = SuperKmerIterator::new(sv.as_slice(), k, w, LexHasherState::new(w));
+        let occs: Vec<SuperKmerOcc> = iter.collect();
+
+        let aca = 0b000100;
+        assert_eq!(
+            occs,
+            vec![
+                SuperKmerOcc::new(MappedMinimizer::new(aca, 1), 0, 2),
+                SuperKmerOcc::new(MappedMinimizer::new(aca, 3), 2, 2),
+            ]
+        );
+
+        assert!(occs.iter().all(|occ| occ.mmer_is_fw()));
+
+        // the super-k-mers cover every k-mer exactly once
+        let n: usize = occs.iter().map(|occ| occ.n_kmers() as usize).sum();
+        assert_eq!(n, sv.len() - k + 1);
+
+        // on the reverse complement, the same minimizers are read off the
+        // reverse strand
+        let sv = SeqVector::from(b"GTGGTGTGTG");
+        let iter = CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, LexHasherState::new(w));
+        let occs: Vec<CanonicalSuperKmerOcc> = iter.collect();
+        assert_eq!(
+            occs,
+            vec![
+                SuperKmerOcc::new(MappedMinimizer::new_stranded(aca, 4, false), 0, 2),
+                SuperKmerOcc::new(MappedMinimizer::new_stranded(aca, 6, false), 2, 2),
+            ]
+        );
+        assert!(occs.iter().all(|occ| !occ.mmer_is_fw()));
+    }
+
+    #[test]
+    fn stats() {
+        let (k, w) = (7, 3);
+        let sv = SeqVector::from(b"CACACACCAC");
+        let stats = minimizer_stats_fw(&sv.as_slice(), k, w, LexHasherState::new(w));
+        assert_eq!(
+            stats,
+            MinimizerStats {
+                n_kmers: 4,
+                n_minimizers: 2,
+                n_super_kmers: 2,
+                max_super_kmer_len: 2,
+                super_kmer_len_hist: vec![0, 0, 2],
+            }
+        );
+        assert_eq!(stats.density(), 0.5);
+        assert_eq!(stats.mean_super_kmer_len(), 2.0);
+
+        // the canonical super-k-mers of the reverse complement are the same
+        let rc = SeqVector::from(b"GTGGTGTGTG");
+        assert_eq!(
+            minimizer_stats(&rc.as_slice(), k, w, LexHasherState::new(w)),
+            stats
+        );
+
+        let sv = SeqVector::from(b"AACCAAA");
+        let stats = minimizer_stats_fw(&sv.as_slice(), 5, 3, LexHasherState::new(3));
+        assert_eq!(stats.n_kmers, 3);
+        assert_eq!(stats.n_minimizers, 3);
+        assert_eq!(stats.super_kmer_len_hist, vec![0, 3]);
+        assert_eq!(stats.density(), 1.0);
+
+        // the homopolymer's split super-k-mers share their minimizer
+        let sv = SeqVector::from(vec![b'A'; 600].as_slice());
+        let stats = minimizer_stats_fw(&sv.as_slice(), 31, 15, LexHasherState::new(15));
+        assert_eq!(stats.n_kmers, 570);
+        assert_eq!(stats.n_minimizers, 570);
+    }
+
+    #[test]
+    fn seeded_minimizers_are_reproducible() {
+        let sv = SeqVector::from(random_seq(5000, 15).as_slice());
+        let (k, w) = (31, 15);
+
+        let run = |seed| {
+            let mmers: Vec<MappedMinimizer> =
+                CanonicalMinimizerIter::new(sv.as_slice(), k, w, SeededState(seed)).collect();
+            let occs: Vec<CanonicalSuperKmerOcc> =
+                CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, SeededState(seed)).collect();
+            (mmers, occs)
+        };
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1).0, run(2).0);
+        assert_ne!(run(1).1, run(2).1);
+
+        let fw = |seed| -> Vec<MappedMinimizer> {
+            SeqVecMinimizerIter::new(sv.as_slice(), k, w, SeededState(seed)).collect()
+        };
+        assert_eq!(fw(1), fw(1));
+        assert_ne!(fw(1), fw(2));
+    }
+
+    #[test]
+    fn window_equals_k() {
+        let s = random_seq(300, 12);
+        let sv = SeqVector::from(s.as_slice());
+        for k in [1, 7, 31, 32] {
+            // the minimizer of a k-mer is the k-mer itself, on one of its strands
+            let mmers: Vec<MappedMinimizer> =
+                SeqVecMinimizerIter::new(sv.as_slice(), k, k, SeededState(7)).collect();
+            assert_eq!(mmers.len(), s.len() - k + 1);
+            for (i, mmer) in mmers.iter().enumerate() {
+                assert_eq!(*mmer, MappedMinimizer::new(sv.get_kmer_u64(i, k), i));
+            }
+
+            let mmers: Vec<MappedMinimizer> =
+                CanonicalMinimizerIter::with_nthash(sv.as_slice(), k, k).collect();
+            assert_eq!(mmers.len(), s.len() - k + 1);
+            for (i, mmer) in mmers.iter().enumerate() {
+                let km = sv.get_kmer_u64(i, k);
+                let rc = Kmer::get_reverse_complement_word(km, k as u8);
+                assert_eq!(mmer.pos, i);
+                assert_eq!(mmer.as_u64(), if mmer.is_fw() { km } else { rc });
+            }
+        }
+    }
+
+    #[test]
+    fn single_kmer() {
+        let s = random_seq(31, 13);
+        let sv = SeqVector::from(s.as_slice());
+        let km = Kmer::from(s.as_slice());
+        let state = SeededState(7);
+        for w in [1, 5, 30, 31] {
+            let mmers: Vec<MappedMinimizer> =
+                SeqVecMinimizerIter::new(sv.as_slice(), 31, w, state).collect();
+            let (mm, o) = km.minimizer(w, &state);
+            assert_eq!(mmers, vec![MappedMinimizer::new(mm.into_u64(), o)]);
+
+            let mmers: Vec<MappedMinimizer> =
+                CanonicalMinimizerIter::new(sv.as_slice(), 31, w, state).collect();
+            assert_eq!(mmers.len(), 1);
+
+            let occs: Vec<SuperKmerOcc> =
+                SuperKmerIterator::new(sv.as_slice(), 31, w, state).collect();
+            assert_eq!(occs.len(), 1);
+            assert_eq!((occs[0].start_pos(), occs[0].n_kmers()), (0, 1));
+
+            let occs: Vec<CanonicalSuperKmerOcc> =
+                CanonicalSuperKmerIterator::new(sv.as_slice(), 31, w, state).collect();
+            assert_eq!(occs.len(), 1);
+            assert_eq!((occs[0].start_pos(), occs[0].n_kmers()), (0, 1));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "got k = 11 and w = 12")]
+    fn window_longer_than_k() {
+        let sv = SeqVector::from(b"ACGTTGCAAGGTCA");
+        SeqVecMinimizerIter::new(sv.as_slice(), 11, 12, SeededState(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "got k = 11 and w = 12")]
+    fn canonical_window_longer_than_k() {
+        let sv = SeqVector::from(b"ACGTTGCAAGGTCA");
+        CanonicalMinimizerIter::new(sv.as_slice(), 11, 12, SeededState(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "got k = 11 and w = 0")]
+    fn empty_window() {
+        let sv = SeqVector::from(b"ACGTTGCAAGGTCA");
+        SuperKmerIterator::new(sv.as_slice(), 11, 0, SeededState(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "got k = 33 and w = 5")]
+    fn k_longer_than_a_word() {
+        let sv = SeqVector::from(random_seq(40, 14).as_slice());
+        CanonicalSuperKmerIterator::new(sv.as_slice(), 33, 5, SeededState(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "sequence of length 14 holds no 15-mer")]
+    fn sequence_shorter_than_k() {
+        let sv = SeqVector::from(b"ACGTTGCAAGGTCA");
+        SeqVecMinimizerIter::new(sv.as_slice(), 15, 5, SeededState(7));
+    }
+
+    #[test]
+    fn stats_density_w_equals_k() {
+        let s = random_seq(2000, 11);
+        let sv = SeqVector::from(s.as_slice());
+        for k in [5, 15, 31] {
+            let stats = minimizer_stats(&sv.as_slice(), k, k, NtHashState::new(k));
+            assert_eq!(stats.n_kmers, 2000 - k + 1);
+            assert_eq!(stats.density(), 1.0);
+            assert_eq!(stats.max_super_kmer_len, 1);
+
+            let stats = minimizer_stats_fw(&sv.as_slice(), k, k, SeededState(7));
+            assert_eq!(stats.density(), 1.0);
+        }
+
+        // otherwise about 2 / (k - w + 2) of the k-mers start a new minimizer
+        let stats = minimizer_stats(&sv.as_slice(), 31, 21, NtHashState::new(21));
+        let expected = 2.0 / 12.0;
+        assert!((stats.density() - expected).abs() < 0.05, "{:?}", stats);
+        let n: usize = stats
+            .super_kmer_len_hist
+            .iter()
+            .enumerate()
+            .map(|(l, c)| l * c)
+            .sum();
+        assert_eq!(n, stats.n_kmers);
+    }
+
+    #[test]
+    fn super_kmers_homopolymer() {
+        // all lmers tie, so the leftmost one changes with every k-mer
+        let sv = SeqVector::from(vec![b'A'; 600].as_slice());
+        let (k, w) = (31, 15);
+        let iter = SuperKmerIterator::new(sv.as_slice(), k, w, LexHasherState::new(w));
+
+        for (i, occ) in iter.enumerate() {
+            assert_eq!(occ, SuperKmerOcc::new(MappedMinimizer::new(0, i), i, 1));
+        }
+
+        // a run of more than u8::MAX k-mers sharing a minimizer is split
+        let mmer = MappedMinimizer::new(0, 7);
+        let mut mmers = std::iter::repeat(mmer.clone()).take(600).peekable();
+        let mut occs = Vec::new();
+        let mut start = 0;
+        while let Some(occ) = next_super_kmer(&mut mmers, start) {
+            start += occ.n_kmers() as usize;
+            occs.push(occ);
+        }
+        assert_eq!(
+            occs,
+            vec![
+                SuperKmerOcc::new(mmer.clone(), 0, 255),
+                SuperKmerOcc::new(mmer.clone(), 255, 255),
+                SuperKmerOcc::new(mmer, 510, 90),
+            ]
+        );
+    }
+
+    #[test]
+    fn canonical_super_kmers_tile_seq() {
+        let s = b"ACGTTGCAAGGCTTACAGATTTAGGACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+        let sv = SeqVector::from(s);
+
+        for (k, w) in [(5, 3), (11, 5), (21, 7), (31, 15)] {
+            let state = LexHasherState::new(w);
+            let mut iter = CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, state.clone());
+            let mut seqs = Vec::new();
+            while let Some(occ) = iter.next() {
+                let kmers: Vec<Kmer> = iter.as_kmer_iter(&occ).collect();
+                assert_eq!(kmers.len(), occ.n_kmers() as usize);
+                for km in kmers {
+                    let (mm, _, _) = km.canonical_minimizer(w, &state);
+                    assert_eq!(mm.into_u64(), occ.mmer_word());
+                }
+
+                let seq = iter.seq_of(&occ).to_string();
+                seqs.push((occ, seq));
+            }
+
+            // consecutive super-k-mers overlap by exactly k - 1 bases
+            let mut tiled = seqs[0].1.clone();
+            for ((prev, _), (occ, seq)) in seqs.iter().zip(seqs.iter().skip(1)) {
+                assert_eq!(prev.end_pos(k) - occ.start_pos(), k - 1);
+                assert_eq!(&tiled[occ.start_pos()..], &seq[..k - 1]);
+                tiled.push_str(&seq[k - 1..]);
+            }
+            assert_eq!(tiled.as_bytes(), s);
+        }
+    }
+
+    #[test]
+    fn super_kmers_tile_seq() {
+        let s = b"ACGTTGCAAGGCTTACAGATTTAGGACCAATGCACGTAGGCAATTCGACTAGCTTGACCAGTA";
+        let sv = SeqVector::from(s);
+
+        for (k, w) in [(5, 3), (11, 5), (21, 7), (31, 15), (7, 7), (31, 31)] {
+            let state = LexHasherState::new(w);
+            let mut iter = sv.iter_super_kmers(k, w, state.clone());
+            let mut seqs = Vec::new();
+            while let Some(occ) = iter.next() {
+                assert!(occ.mmer_is_fw());
+                let seq = iter.seq_of(&occ);
+                for i in 0..occ.n_kmers() as usize {
+                    let km = seq.get_kmer(i, k);
+                    let (mm, o) = km.minimizer(w, &state);
+                    assert_eq!(mm.into_u64(), occ.mmer_word());
+                    assert_eq!(occ.start_pos() + i + o, occ.mmer_pos());
+                }
+                // with w == k, every k-mer is its own minimizer
+                if w == k {
+                    assert_eq!(occ.n_kmers(), 1);
+                    assert_eq!(occ.mmer_pos(), occ.start_pos());
+                }
+                seqs.push((occ, seq.to_string()));
+            }
+
+            let mut tiled = seqs[0].1.clone();
+            for ((prev, _), (occ, seq)) in seqs.iter().zip(seqs.iter().skip(1)) {
+                assert_eq!(prev.end_pos(k) - occ.start_pos(), k - 1);
+                tiled.push_str(&seq[k - 1..]);
+            }
+            assert_eq!(tiled.as_bytes(), s);
+
+            // the convenience methods on slices and vectors agree
+            let occs: Vec<SuperKmerOcc> = seqs.into_iter().map(|(occ, _)| occ).collect();
+            let from_slice: Vec<SuperKmerOcc> = sv
+                .as_slice()
+                .iter_super_kmers(k, w, state.clone())
+                .collect();
+            assert_eq!(from_slice, occs);
+            let canonical: Vec<CanonicalSuperKmerOcc> =
+                CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, state.clone()).collect();
+            let from_vec: Vec<CanonicalSuperKmerOcc> =
+                sv.iter_canonical_super_kmers(k, w, state.clone()).collect();
+            assert_eq!(from_vec, canonical);
+            let from_slice: Vec<CanonicalSuperKmerOcc> = sv
+                .as_slice()
+                .iter_canonical_super_kmers(k, w, state)
+                .collect();
+            assert_eq!(from_slice, canonical);
+        }
+    }
+
+    fn brute_force_canonical_mmers(seq: &[u8], k: usize, w: usize) -> Vec<MappedMinimizer> {
+        let state = LexHasherState::new(w);
+        let mut mmers = Vec::new();
+        for (i, window) in seq.windows(k).enumerate() {
+            if Kmer::from_bytes_checked(window).is_err() {
+                continue;
+            }
+            let km = Kmer::from(window);
+            let (mm, o, is_fw) = km.canonical_minimizer(w, &state);
+            let pos = if km.is_canonical() {
+                i + o
+            } else {
+                i + k - w - o
+            };
+            let is_fw = is_fw == km.is_canonical();
+            mmers.push(MappedMinimizer::new_stranded(mm.into_u64(), pos, is_fw));
+        }
+        mmers
+    }
+
+    // the streaming canonical minimizers agree with `Kmer::canonical_minimizer`,
+    // strand included, on random sequences and (k, w)
+    #[quickcheck]
+    fn canonical_mmers_match_brute_force(seq: Vec<u8>, k: u8, w: u8, seed: u64) -> bool {
+        let seq: Vec<u8> = seq.iter().map(|b| b"ACGT"[(b % 4) as usize]).collect();
+        let k = (k % 32) as usize + 1;
+        let w = (w as usize % k) + 1;
+        if seq.len() < k {
+            return true;
+        }
+        let state = SeededState(seed);
+        let expected: Vec<MappedMinimizer> = seq
+            .windows(k)
+            .enumerate()
+            .map(|(i, window)| {
+                let km = Kmer::from(window);
+                let (mm, o, is_fw) = km.canonical_minimizer(w, &state);
+                let pos = if km.is_canonical() {
+                    i + o
+                } else {
+                    i + k - w - o
+                };
+                MappedMinimizer::new_stranded(mm.into_u64(), pos, is_fw == km.is_canonical())
+            })
+            .collect();
+
+        let sv = SeqVector::from(seq.as_slice());
+        let mmers: Vec<MappedMinimizer> = sv.iter_canonical_minimizers(k, w, state).collect();
+        let occs = CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, state);
+        let occs_agree = occs.into_iter().all(|occ| {
+            let start = occ.start_pos();
+            (start..start + occ.n_kmers() as usize).all(|i| {
+                let mm = &expected[i];
+                (occ.mmer_word(), occ.mmer_pos(), occ.mmer_is_fw())
+                    == (mm.as_u64(), mm.pos, mm.is_fw())
+            })
+        });
+        mmers == expected && occs_agree
+    }
+
+    #[test]
+    fn canonical_mmers_from_ascii() {
+        let (k, w) = (7, 3);
+        let seqs: [&[u8]; 7] = [
+            b"NNACGTTGCAAGGCTTACAG",
+            b"ACGTTGCAAGGCTTACAGNN",
+            b"ACGTTGCNNNNNNNNNNNAAGGCTTACAG",
+            b"ACGTTGCNNAAGGCTTNACAGATTTAGGA",
+            b"NACGTNNACGTTGCANACGTTGNNNN",
+            b"acgtnACGTTGCAAGGxTTACAGrA",
+            b"NNNNNNNNNN",
+        ];
+
+        for seq in seqs {
+            let mmers: Vec<MappedMinimizer> =
+                canonical_minimizers_from_ascii(seq, k, w, LexHasherState::new(w)).collect();
+            assert_eq!(mmers, brute_force_canonical_mmers(seq, k, w), "{seq:?}");
+        }
+    }
+
+    #[test]
+    fn minimizers_with_kmers() {
+        let mut seq = random_seq(400, 29);
+        let sv = SeqVector::from(seq.as_slice());
+        for (k, w) in [(4, 2), (11, 5), (21, 21), (31, 15), (32, 1)] {
+            let n_kmers = seq.len() - k + 1;
+
+            let mut iter = sv.iter_minimizers(k, w, SeededState(3));
+            assert_eq!(iter.len(), n_kmers);
+            let expected: Vec<MappedMinimizer> = sv.iter_minimizers(k, w, SeededState(3)).collect();
+            for (pos, mmer) in expected.iter().enumerate() {
+                let next = iter.next_with_kmer().unwrap();
+                assert_eq!(next.km, sv.get_kmer(pos, k));
+                assert_eq!(next.pos, pos);
+                assert_eq!(next.mmer, *mmer);
+                assert_eq!(iter.len(), n_kmers - pos - 1);
+            }
+            assert_eq!(iter.next_with_kmer(), None);
+
+            let mut iter = sv.iter_canonical_minimizers(k, w, SeededState(3));
+            assert_eq!(iter.size_hint(), (n_kmers, Some(n_kmers)));
+            let expected: Vec<MappedMinimizer> =
+                sv.iter_canonical_minimizers(k, w, SeededState(3)).collect();
+            for (pos, mmer) in expected.iter().enumerate() {
+                // `next` and `next_with_kmer` can be interleaved
+                if pos % 3 == 0 {
+                    assert_eq!(iter.next().as_ref(), Some(mmer));
+                    continue;
+                }
+                let next = iter.next_with_kmer().unwrap();
+                assert_eq!(next.km, CanonicalKmer::from(sv.get_kmer(pos, k)));
+                assert_eq!(next.pos, pos);
+                assert_eq!(next.mmer, *mmer);
+                assert_eq!(iter.len(), n_kmers - pos - 1);
+            }
+            assert_eq!(iter.next_with_kmer(), None);
+            assert_eq!(iter.len(), 0);
+        }
+
+        // from bytes, with the k-mers over an N skipped
+        seq[50] = b'N';
+        let (k, w) = (11, 5);
+        let sv = SeqVector::from(&seq[51..]);
+        let mut iter = MinimizerIter::from_bytes(&seq, k, w, SeededState(3));
+        for pos in (0..=50 - k).chain(51..=seq.len() - k) {
+            let next = iter.next_with_kmer().unwrap();
+            assert_eq!(next.pos, pos);
+            assert_eq!(next.km, Kmer::from(&seq[pos..pos + k]));
+            if pos > 50 {
+                let mmer = sv
+                    .iter_minimizers(k, w, SeededState(3))
+                    .nth(pos - 51)
+                    .unwrap();
+                assert_eq!(
+                    next.mmer,
+                    MappedMinimizer::new(mmer.as_u64(), mmer.pos + 51)
+                );
+            }
+        }
+        assert_eq!(iter.next_with_kmer(), None);
+    }
+
+    #[test]
+    fn minimizers_from_bytes() {
+        let seq = random_seq(500, 23);
+        let sv = SeqVector::from(seq.as_slice());
+        for (k, w) in [(4, 2), (11, 5), (21, 21), (31, 15), (32, 1)] {
+            let packed: Vec<MappedMinimizer> = sv.iter_minimizers(k, w, SeededState(5)).collect();
+            let bytes: Vec<MappedMinimizer> =
+                MinimizerIter::from_bytes(&seq, k, w, SeededState(5)).collect();
+            assert_eq!(bytes, packed);
+
+            let packed: Vec<MappedMinimizer> =
+                sv.iter_minimizers(k, w, NtHashState::new(w)).collect();
+            let bytes: Vec<MappedMinimizer> =
+                MinimizerIter::from_bytes(&seq, k, w, NtHashState::new(w)).collect();
+            assert_eq!(bytes, packed);
+
+            let scheme = ModMinimizer::new(2);
+            let packed: Vec<MappedMinimizer> =
+                SeqVecMinimizerIter::with_scheme(sv.as_slice(), k, w, SeededState(5), scheme)
+                    .collect();
+            let bytes: Vec<MappedMinimizer> =
+                MinimizerIter::with_scheme(&seq, k, w, SeededState(5), scheme).collect();
+            assert_eq!(bytes, packed);
+        }
+
+        // N runs restart the window, the same as minimizing each segment
+        let mut seq = seq;
+        for i in [3, 40, 41, 42, 100, 103, 250] {
+            seq[i] = b'N';
+        }
+        seq[300..310].make_ascii_lowercase();
+        let (k, w) = (11, 5);
+        let mut expected = Vec::new();
+        let mut start = 0;
+        for segment in seq.split(|&c| c == b'N') {
+            if segment.len() >= k {
+                let sv = SeqVector::from(segment);
+                expected.extend(
+                    sv.iter_minimizers(k, w, SeededState(5))
+                        .map(|mmer| MappedMinimizer::new(mmer.as_u64(), start + mmer.pos)),
+                );
+            }
+            start += segment.len() + 1;
+        }
+        let bytes: Vec<MappedMinimizer> =
+            MinimizerIter::from_bytes(&seq, k, w, SeededState(5)).collect();
+        assert_eq!(bytes, expected);
+
+        // too short or all N
+        assert_eq!(
+            MinimizerIter::from_bytes(b"ACGTN", 4, 2, SeededState(5)).count(),
+            1
+        );
+        assert_eq!(
+            MinimizerIter::from_bytes(b"ACG", 4, 2, SeededState(5)).count(),
+            0
+        );
+        assert_eq!(
+            MinimizerIter::from_bytes(b"NNNNNN", 4, 2, SeededState(5)).count(),
+            0
+        );
+    }
+
+    // the super-k-mers of the segments between Ns, in the coordinates of `seq`
+    fn super_kmers_by_segment(seq: &[u8], k: usize, w: usize) -> Vec<CanonicalSuperKmerOcc> {
+        let mut occs = Vec::new();
+        let mut start = 0;
+        for segment in seq.split(|&c| c == b'N') {
+            if segment.len() >= k {
+                let sv = SeqVector::from(segment);
+                for mut occ in CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, SeededState(9))
+                {
+                    occ.inc_pos(start);
+                    occs.push(occ);
+                }
+            }
+            start += segment.len() + 1;
+        }
+        occs
+    }
+
+    #[test]
+    fn canonical_super_kmers_from_bytes() {
+        let (k, w) = (11, 5);
+        let clean = random_seq(60, 41);
+        let bytes = |seq: &[u8]| -> Vec<CanonicalSuperKmerOcc> {
+            CanonicalSuperKmerFromBytesIter::new(seq, k, w, SeededState(9)).collect()
+        };
+
+        // without N, the same as packing the read first
+        let sv = SeqVector::from(clean.as_slice());
+        let packed: Vec<CanonicalSuperKmerOcc> =
+            CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, SeededState(9)).collect();
+        assert_eq!(bytes(&clean), packed);
+
+        let mut leading = b"NNN".to_vec();
+        leading.extend_from_slice(&clean);
+        let mut trailing = clean.clone();
+        trailing.extend_from_slice(b"NN");
+        let mut in_first_window = clean.clone();
+        in_first_window[4] = b'N';
+        for seq in [&leading, &trailing, &in_first_window] {
+            let occs = bytes(seq);
+            assert_eq!(occs, super_kmers_by_segment(seq, k, w));
+            for occ in &occs {
+                let it = CanonicalSuperKmerFromBytesIter::new(seq, k, w, SeededState(9));
+                assert!(!it.seq_of(occ).contains(&b'N'));
+            }
+        }
+        assert_eq!(bytes(&leading)[0].start_pos(), 3);
+        assert_eq!(bytes(&in_first_window)[0].start_pos(), 5);
+        let n: usize = bytes(&trailing).iter().map(|o| o.n_kmers() as usize).sum();
+        assert_eq!(n, clean.len() - k + 1);
+
+        // an N inside a super-k-mer, outside of its minimizer, splits it in two
+        let occ = packed.iter().find(|occ| occ.n_kmers() >= 3).unwrap();
+        let n_pos = if occ.mmer_pos() + w < occ.end_pos(k) {
+            occ.end_pos(k) - 1
+        } else {
+            occ.start_pos()
+        };
+        let mut split = clean.clone();
+        split[n_pos] = b'N';
+        let occs = bytes(&split);
+        assert_eq!(occs, super_kmers_by_segment(&split, k, w));
+        assert!(!occs.contains(occ));
+        assert!(occs.iter().any(|o| o.end_pos(k) == n_pos));
+        assert!(occs.iter().any(|o| o.start_pos() == n_pos + 1));
+
+        // random N runs, longer reads
+        let mut seq = random_seq(3000, 43);
+        for (i, c) in seq.iter_mut().enumerate() {
+            if i % 89 < i % 5 {
+                *c = b'N';
+            }
+        }
+        for (k, w) in [(5, 3), (11, 5), (31, 15), (32, 32)] {
+            let occs: Vec<CanonicalSuperKmerOcc> =
+                CanonicalSuperKmerFromBytesIter::new(&seq, k, w, SeededState(9)).collect();
+            assert_eq!(occs, super_kmers_by_segment(&seq, k, w), "k = {k}, w = {w}");
+        }
+        assert_eq!(bytes(b"NNNNNNNNNNNNNNNN").len(), 0);
+    }
+
+    #[test]
+    fn kmer_minimizers_in_one_pass() {
+        let mut seq = random_seq(2000, 17);
+        for (i, c) in seq.iter_mut().enumerate() {
+            // N runs of all lengths, and some lowercase bases
+            if i % 97 < i % 7 {
+                *c = b'N';
+            } else if i % 13 == 0 {
+                c.make_ascii_lowercase();
+            }
+        }
+
+        for (k, w) in [(4, 2), (11, 5), (21, 21), (31, 15), (32, 1)] {
+            let kmers: Vec<CanonicalKmerPos> =
+                CanonicalKmerIterator::from_u8_slice(&seq, k as u8).collect();
+
+            let states: [Box<dyn Fn() -> Vec<KmerMinimizer>>; 3] = [
+                Box::new(|| CanonicalKmerMinimizerIter::new(&seq, k, w, SeededState(3)).collect()),
+                Box::new(|| {
+                    CanonicalKmerMinimizerIter::new(&seq, k, w, LexHasherState::new(w)).collect()
+                }),
+                Box::new(|| {
+                    CanonicalKmerMinimizerIter::new(&seq, k, w, NtHashState::new(w)).collect()
+                }),
+            ];
+            let expected_mmers: [Vec<MappedMinimizer>; 3] = [
+                canonical_minimizers_from_ascii(&seq, k, w, SeededState(3)).collect(),
+                canonical_minimizers_from_ascii(&seq, k, w, LexHasherState::new(w)).collect(),
+                canonical_minimizers_from_ascii(&seq, k, w, NtHashState::new(w)).collect(),
+            ];
+
+            for (fused, expected) in states.iter().zip(expected_mmers) {
+                let fused = fused();
+                assert_eq!(fused.len(), kmers.len());
+                for (km_mmer, ckp) in fused.iter().zip(&kmers) {
+                    assert_eq!(km_mmer.pos, ckp.pos as usize);
+                    assert_eq!(km_mmer.km, ckp.km);
+                }
+                let mmers: Vec<MappedMinimizer> = fused.into_iter().map(|km| km.mmer).collect();
+                assert_eq!(mmers, expected, "k = {k}, w = {w}");
+            }
+        }
+
+        // other schemes than LeftMin
+        let seq = random_seq(500, 3);
+        let sv = SeqVector::from(seq.as_slice());
+        let scheme = ModMinimizer::new(4);
+        let fused: Vec<MappedMinimizer> =
+            CanonicalKmerMinimizerIter::with_scheme(&seq, 21, 9, SeededState(5), scheme)
+                .map(|km| km.mmer)
+                .collect();
+        let expected: Vec<MappedMinimizer> =
+            CanonicalMinimizerIter::with_scheme(sv.as_slice(), 21, 9, SeededState(5), scheme)
+                .collect();
+        assert_eq!(fused, expected);
+
+        assert_eq!(
+            CanonicalKmerMinimizerIter::new(b"ACGTNACG", 4, 2, SeededState(5)).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn syncmers_match_brute_force() {
+        for (seed, (k, s)) in [(5, 2), (11, 5), (21, 11), (31, 15), (31, 30)]
+            .into_iter()
+            .enumerate()
+        {
+            let seq = random_seq(500, seed as u64 + 1);
+            let sv = SeqVector::from(seq.as_slice());
+            let state = NtHashState::new(s);
+
+            for offsets in [vec![0], vec![0, k - s], vec![(k - s) / 2]] {
+                let expected: Vec<(Kmer, usize)> = sv
+                    .iter_kmers(k)
+                    .enumerate()
+                    .filter(|(_, km)| offsets.contains(&km.minimizer(s, &state).1))
+                    .map(|(i, km)| (km, i))
+                    .collect();
+                let syncmers: Vec<(Kmer, usize)> =
+                    SyncmerIter::new(sv.as_slice(), k, s, &offsets, state.clone()).collect();
+                assert_eq!(syncmers, expected);
+
+                let expected: Vec<(Kmer, usize)> = sv
+                    .iter_kmers(k)
+                    .enumerate()
+                    .filter(|(_, km)| offsets.contains(&km.canonical_minimizer(s, &state).1))
+                    .map(|(i, km)| (km.to_canonical(), i))
+                    .collect();
+                let syncmers: Vec<(Kmer, usize)> =
+                    CanonicalSyncmerIter::new(sv.as_slice(), k, s, &offsets, state.clone())
+                        .collect();
+                assert_eq!(syncmers, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn closed_syncmer_density() {
+        let seq = random_seq(100_000, 42);
+        let sv = SeqVector::from(seq.as_slice());
+        let n_kmers = |k: usize| (seq.len() - k + 1) as f64;
+
+        for (k, s) in [(15, 5), (21, 11), (31, 15)] {
+            let state = NtHashState::new(s);
+            let expected = 2.0 / (k - s + 1) as f64;
+
+            let n = SyncmerIter::closed(sv.as_slice(), k, s, state.clone()).count();
+            let density = n as f64 / n_kmers(k);
+            assert!(
+                (density - expected).abs() < 0.2 * expected,
+                "{k} {s} {density}"
+            );
+
+            let n = CanonicalSyncmerIter::closed(sv.as_slice(), k, s, state).count();
+            let density = n as f64 / n_kmers(k);
+            assert!(
+                (density - expected).abs() < 0.2 * expected,
+                "{k} {s} {density}"
+            );
+        }
+    }
+
+    // lexicographically smallest (leftmost) w-mer of `kmer`, by string
+    fn lex_min(kmer: &[u8], w: usize) -> (Vec<u8>, usize) {
+        let (o, lmer) = kmer
+            .windows(w)
+            .enumerate()
+            .min_by(|(i, a), (j, b)| a.cmp(b).then(i.cmp(j)))
+            .unwrap();
+        (lmer.to_vec(), o)
+    }
+
+    #[test]
+    fn lex_minimizers_are_lexicographic() {
+        let s = random_seq(300, 7);
+        let sv = SeqVector::from(s.as_slice());
+
+        for (k, w) in [(5, 3), (11, 5), (31, 15), (32, 32)] {
+            let state = LexHasherState::new(w);
+            let word = |lmer: &[u8]| Kmer::from(lmer).into_u64();
+
+            let mmers = sv.iter_minimizers(k, w, state.clone());
+            for (i, mmer) in mmers.enumerate() {
+                let (lmer, o) = lex_min(&s[i..i + k], w);
+                assert_eq!(mmer, MappedMinimizer::new(word(&lmer), i + o));
+            }
+
+            let mmers = sv.iter_canonical_minimizers(k, w, state.clone());
+            let ascii = canonical_minimizers_from_ascii(&s, k, w, state.clone());
+            for (i, (mmer, ascii)) in mmers.zip(ascii).enumerate() {
+                // smallest w-mer of either strand
+                let fw = lex_min(&s[i..i + k], w);
+                let rc = lex_min(&rc(&s[i..i + k]), w);
+                assert_eq!(mmer.as_u64(), word(fw.0.min(rc.0).as_slice()));
+                assert_eq!(mmer, ascii);
+            }
+
+            let occs = SuperKmerIterator::new(sv.as_slice(), k, w, state.clone());
+            for occ in occs {
+                let (lmer, o) = lex_min(&s[occ.start_pos()..occ.start_pos() + k], w);
+                assert_eq!(
+                    (occ.mmer_word(), occ.mmer_pos()),
+                    (word(&lmer), occ.start_pos() + o)
+                );
+            }
+
+            if w < k {
+                let syncmers = SyncmerIter::open(sv.as_slice(), k, w, state.clone());
+                for (km, i) in syncmers {
+                    assert_eq!(lex_min(&s[i..i + k], w).1, 0);
+                    assert_eq!(km, Kmer::from(&s[i..i + k]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mod_minimizers() {
+        let s = random_seq(400, 3);
+        let sv = SeqVector::from(s.as_slice());
+        let s_rc = rc(&s);
+        let sv_rc = SeqVector::from(s_rc.as_slice());
+
+        for (k, w, r) in [(11, 5, 4), (21, 7, 2), (31, 15, 8)] {
+            let state = NtHashState::new(w);
+            let scheme = ModMinimizer::new(r);
+            let key = |lmer: u64| {
+                let h = hash_one(&state, lmer);
+                (h % r != 0, h)
+            };
+
+            // leftmost l-mer of smallest key
+            let mmers: Vec<MappedMinimizer> =
+                SeqVecMinimizerIter::with_scheme(sv.as_slice(), k, w, state.clone(), scheme)
+                    .collect();
+            for (i, mmer) in mmers.iter().enumerate() {
+                let (lmer, o) = (0..=k - w)
+                    .map(|o| (sv.get_kmer_u64(i + o, w), o))
+                    .min_by_key(|&(lmer, o)| (key(lmer), o))
+                    .unwrap();
+                assert_eq!(mmer, &MappedMinimizer::new(lmer, i + o));
+            }
+            let default: Vec<MappedMinimizer> = sv.iter_minimizers(k, w, state.clone()).collect();
+            assert_ne!(mmers, default);
+
+            // canonical minimizers rank first among the l-mers of both
+            // strands, and are the same read from either strand
+            let mmers: Vec<MappedMinimizer> =
+                CanonicalMinimizerIter::with_scheme(sv.as_slice(), k, w, state.clone(), scheme)
+                    .collect();
+            for (i, mmer) in mmers.iter().enumerate() {
+                let best = (0..=k - w)
+                    .flat_map(|o| {
+                        let lmer = sv.get_kmer_u64(i + o, w);
+                        [lmer, Kmer::get_reverse_complement_word(lmer, w as u8)]
+                    })
+                    .map(key)
+                    .min()
+                    .unwrap();
+                assert_eq!(key(mmer.as_u64()), best);
+            }
+            let mut mmers_rc: Vec<MappedMinimizer> =
+                CanonicalMinimizerIter::with_scheme(sv_rc.as_slice(), k, w, state.clone(), scheme)
+                    .map(|mm| {
+                        MappedMinimizer::new_stranded(
+                            mm.as_u64(),
+                            s.len() - w - mm.pos,
+                            !mm.is_fw(),
+                        )
+                    })
+                    .collect();
+            mmers_rc.reverse();
+            assert_eq!(mmers, mmers_rc);
+
+            // super-k-mers group the same minimizers
+            let occs =
+                CanonicalSuperKmerIterator::with_scheme(sv.as_slice(), k, w, state.clone(), scheme);
+            let mut n = 0;
+            for occ in occs {
+                let run = &mmers[occ.start_pos()..occ.start_pos() + occ.n_kmers() as usize];
+                assert!(run.iter().all(|mmer| mmer == occ.mmer()));
+                n += occ.n_kmers() as usize;
+            }
+            assert_eq!(n, mmers.len());
+        }
     }
 }
@@ -0,0 +1,217 @@
+// Canonical super-k-mers of a long sequence, computed in parallel over
+// chunks of k-mers. The minimizer of a k-mer only depends on the k-mer, so
+// every chunk can be processed on its own; what needs care are the
+// super-k-mers spanning a chunk boundary. A super-k-mer belongs to the chunk
+// holding its first k-mer: that chunk reads past its end to complete it, and
+// the next chunk skips the k-mers that continue it. Runs longer than
+// `u8::MAX` k-mers are split from their first k-mer, as done sequentially.
+
+use rayon::prelude::*;
+
+use super::minimizers::{CanonicalMinimizerIter, CanonicalSuperKmerOcc, SuperKmerOcc};
+use super::*;
+
+// k-mers per chunk in `SeqVector::par_iter_super_kmers`
+pub const DEFAULT_CHUNK_LEN: usize = 1 << 20;
+
+// The same occurrences as `CanonicalSuperKmerIterator` on `sv`, in the same
+// order, computed on chunks of `chunk_len` k-mers in parallel.
+pub fn par_super_kmers<'a, T>(
+    sv: &SeqVectorSlice<'a>,
+    k: usize,
+    w: usize,
+    hash_seed: T,
+    chunk_len: usize,
+) -> impl ParallelIterator<Item = CanonicalSuperKmerOcc> + 'a
+where
+    T: BuildHasher + Clone + Send + Sync + 'a,
+{
+    assert!(chunk_len > 0, "chunks must hold at least one k-mer");
+    let n_kmers = (sv.len() + 1).saturating_sub(k);
+    let n_chunks = (n_kmers + chunk_len - 1) / chunk_len;
+    let sv = sv.clone();
+
+    (0..n_chunks).into_par_iter().flat_map_iter(move |c| {
+        let start = c * chunk_len;
+        let end = (start + chunk_len).min(n_kmers);
+        chunk_super_kmers(&sv, k, w, hash_seed.clone(), start, end)
+    })
+}
+
+// The super-k-mers whose first k-mer is in `start..end`, in global
+// coordinates.
+fn chunk_super_kmers<T>(
+    sv: &SeqVectorSlice,
+    k: usize,
+    w: usize,
+    hash_seed: T,
+    start: usize,
+    end: usize,
+) -> Vec<CanonicalSuperKmerOcc>
+where
+    T: BuildHasher,
+{
+    // start one k-mer early to tell whether the first k-mer continues the
+    // super-k-mer of the previous chunk
+    let from = start.saturating_sub(1);
+    let (start, end) = (start - from, end - from);
+    let mut mmers =
+        CanonicalMinimizerIter::new(sv.slice(from, sv.len()), k, w, hash_seed).peekable();
+
+    let mut km_i = start;
+    if start > 0 {
+        let prev = mmers.next().unwrap();
+        while km_i < end && mmers.peek() == Some(&prev) {
+            mmers.next();
+            km_i += 1;
+        }
+    }
+
+    let mut occs: Vec<SuperKmerOcc> = Vec::new();
+    while let Some(mmer) =
+        mmers.next_if(|mmer| km_i < end || occs.last().map_or(false, |occ| occ.mmer() == mmer))
+    {
+        let mut n_kmers = 1;
+        while n_kmers < u8::MAX && mmers.peek() == Some(&mmer) {
+            mmers.next();
+            n_kmers += 1;
+        }
+        occs.push(SuperKmerOcc::new(mmer, km_i, n_kmers));
+        km_i += n_kmers as usize;
+    }
+
+    for occ in occs.iter_mut() {
+        occ.inc_pos(from);
+    }
+    occs
+}
+
+impl SeqVector {
+    // `CanonicalSuperKmerIterator`'s occurrences, computed in parallel on
+    // chunks of `DEFAULT_CHUNK_LEN` k-mers
+    pub fn par_iter_super_kmers<'s, T>(
+        &'s self,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+    ) -> impl ParallelIterator<Item = CanonicalSuperKmerOcc> + 's
+    where
+        T: BuildHasher + Clone + Send + Sync + 's,
+    {
+        self.par_iter_canonical_super_kmers(k, w, hash_seed, DEFAULT_CHUNK_LEN)
+    }
+
+    // The same, on chunks of `chunk_len` k-mers. Collecting the iterator
+    // gives the occurrences in sequential order.
+    pub fn par_iter_canonical_super_kmers<'s, T>(
+        &'s self,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        chunk_len: usize,
+    ) -> impl ParallelIterator<Item = CanonicalSuperKmerOcc> + 's
+    where
+        T: BuildHasher + Clone + Send + Sync + 's,
+    {
+        par_super_kmers(&self.as_slice(), k, w, hash_seed, chunk_len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::hash::{NtHashState, SeededState};
+    use super::super::minimizers::CanonicalSuperKmerIterator;
+    use super::*;
+    use crate::test_utils::random_seq;
+
+    fn check_par_super_kmers<T>(
+        sv: &SeqVectorSlice,
+        k: usize,
+        w: usize,
+        hash_seed: T,
+        chunks: &[usize],
+    ) where
+        T: BuildHasher + Clone + Send + Sync,
+    {
+        let expected: Vec<CanonicalSuperKmerOcc> =
+            CanonicalSuperKmerIterator::new(sv.clone(), k, w, hash_seed.clone()).collect();
+
+        for &chunk_len in chunks {
+            let mut occs: Vec<CanonicalSuperKmerOcc> =
+                par_super_kmers(sv, k, w, hash_seed.clone(), chunk_len).collect();
+            occs.sort_by_key(|occ| occ.start_pos());
+            assert_eq!(occs, expected, "chunks of {} k-mers", chunk_len);
+        }
+    }
+
+    #[test]
+    fn same_as_sequential() {
+        let sv = SeqVector::from(random_seq(5_000_000, 1).as_slice());
+        check_par_super_kmers(
+            &sv.as_slice(),
+            31,
+            19,
+            NtHashState::new(19),
+            &[1 << 16, 1 << 20],
+        );
+
+        let mut occs: Vec<CanonicalSuperKmerOcc> = sv
+            .par_iter_super_kmers(31, 19, NtHashState::new(19))
+            .collect();
+        occs.sort_by_key(|occ| occ.start_pos());
+        let expected: Vec<CanonicalSuperKmerOcc> =
+            CanonicalSuperKmerIterator::new(sv.as_slice(), 31, 19, NtHashState::new(19)).collect();
+        assert_eq!(occs, expected);
+    }
+
+    #[test]
+    fn ordered_as_sequential() {
+        let sv = SeqVector::from(random_seq(1_000_003, 4).as_slice());
+        for (k, w) in [(31, 15), (21, 21), (15, 7)] {
+            let expected: Vec<CanonicalSuperKmerOcc> =
+                CanonicalSuperKmerIterator::new(sv.as_slice(), k, w, SeededState(11)).collect();
+            // chunk lengths that do not divide the number of k-mers
+            for chunk_len in [9_999, 77_777, 400_000] {
+                let occs: Vec<CanonicalSuperKmerOcc> = sv
+                    .par_iter_canonical_super_kmers(k, w, SeededState(11), chunk_len)
+                    .collect();
+                assert_eq!(occs, expected, "k = {k}, w = {w}, chunks of {chunk_len}");
+            }
+        }
+    }
+
+    #[test]
+    fn chunks_shorter_than_super_kmers() {
+        let sv = SeqVector::from(random_seq(100_000, 2).as_slice());
+        let chunks = [1, 2, 3, 7, 13, 1000];
+        check_par_super_kmers(&sv.as_slice(), 31, 15, NtHashState::new(15), &chunks);
+        check_par_super_kmers(&sv.slice(17, 50_000), 21, 11, SeededState(7), &chunks);
+        check_par_super_kmers(&sv.as_slice(), 5, 5, SeededState(7), &chunks);
+    }
+
+    #[test]
+    fn long_runs_across_chunks() {
+        // homopolymers, where all l-mers of a window tie
+        let mut seq = vec![b'A'; 2000];
+        seq.extend(random_seq(500, 3));
+        seq.extend(vec![b'C'; 700]);
+        let sv = SeqVector::from(seq.as_slice());
+        check_par_super_kmers(
+            &sv.as_slice(),
+            31,
+            15,
+            NtHashState::new(15),
+            &[1, 10, 254, 255, 256, 600],
+        );
+    }
+
+    #[test]
+    fn short_sequences() {
+        let sv = SeqVector::from(b"ACGTTGCA");
+        assert_eq!(
+            par_super_kmers(&sv.as_slice(), 11, 5, SeededState(7), 4).count(),
+            0
+        );
+        check_par_super_kmers(&sv.as_slice(), 8, 3, SeededState(7), &[1, 2]);
+    }
+}
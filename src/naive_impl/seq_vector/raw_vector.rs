@@ -0,0 +1,319 @@
+// A pure-Rust stand-in for the subset of `simple_sds::raw_vector::RawVector`
+// used by `SeqVector`, for builds without the `seq-vector` feature (e.g. on
+// platforms where simple-sds does not compile). It mirrors the simple-sds
+// API, bit layout, and on-disk format, so `SeqVector` behaves identically
+// whichever backend it is built on.
+
+use std::io::{Read, Write};
+
+pub trait AccessRaw {
+    // Read `width` <= 64 bits starting at `bit_offset`, lowest order bits first.
+    //
+    // # Safety
+    //
+    // Unlike simple-sds, bounds are always checked here, but the signature
+    // is kept `unsafe` so callers are identical for both backends.
+    unsafe fn int(&self, bit_offset: usize, width: usize) -> u64;
+
+    // Overwrite `width` <= 64 bits starting at `bit_offset` with the lowest
+    // `width` bits of `value`.
+    //
+    // # Safety
+    //
+    // See `int`.
+    unsafe fn set_int(&mut self, bit_offset: usize, value: u64, width: usize);
+}
+
+pub trait PushRaw {
+    // Append the lowest `width` <= 64 bits of `value`.
+    //
+    // # Safety
+    //
+    // See `AccessRaw::int`.
+    unsafe fn push_int(&mut self, value: u64, width: usize);
+}
+
+pub trait Serialize: Sized {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()>;
+    fn load<R: Read>(reader: &mut R) -> std::io::Result<Self>;
+    fn size_in_bytes(&self) -> usize;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawVector {
+    len: usize,
+    data: Vec<u64>,
+}
+
+#[inline]
+const fn low_bits(width: usize) -> u64 {
+    if width >= 64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+// the number of words needed to hold `bits` bits
+#[inline]
+const fn words_for(bits: usize) -> usize {
+    bits / 64 + (bits % 64 != 0) as usize
+}
+
+impl RawVector {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            len: 0,
+            data: Vec::with_capacity(words_for(capacity)),
+        }
+    }
+
+    pub fn from_parts(len: usize, data: Vec<u64>) -> Self {
+        assert!(len <= data.len() * 64);
+        Self { len, data }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let words = words_for(self.len + additional);
+        self.data.reserve(words.saturating_sub(self.data.len()));
+    }
+}
+
+// the words holding the bits, the unused high bits of the last one are 0
+impl AsRef<[u64]> for RawVector {
+    fn as_ref(&self) -> &[u64] {
+        &self.data
+    }
+}
+
+impl AccessRaw for RawVector {
+    #[inline]
+    unsafe fn int(&self, bit_offset: usize, width: usize) -> u64 {
+        assert!(width <= 64 && bit_offset + width <= self.len);
+        if width == 0 {
+            return 0;
+        }
+
+        let (i, o) = (bit_offset / 64, bit_offset % 64);
+        let mut v = self.data[i] >> o;
+        if o + width > 64 {
+            v |= self.data[i + 1] << (64 - o);
+        }
+        v & low_bits(width)
+    }
+
+    #[inline]
+    unsafe fn set_int(&mut self, bit_offset: usize, value: u64, width: usize) {
+        assert!(width <= 64 && bit_offset + width <= self.len);
+        if width == 0 {
+            return;
+        }
+
+        let value = value & low_bits(width);
+        let (i, o) = (bit_offset / 64, bit_offset % 64);
+        self.data[i] &= !(low_bits(width) << o);
+        self.data[i] |= value << o;
+        if o + width > 64 {
+            let hi = o + width - 64;
+            self.data[i + 1] &= !low_bits(hi);
+            self.data[i + 1] |= value >> (64 - o);
+        }
+    }
+}
+
+impl PushRaw for RawVector {
+    #[inline]
+    unsafe fn push_int(&mut self, value: u64, width: usize) {
+        assert!(width <= 64);
+        if width == 0 {
+            return;
+        }
+
+        let value = value & low_bits(width);
+        let o = self.len % 64;
+        if o == 0 {
+            self.data.push(value);
+        } else {
+            *self.data.last_mut().unwrap() |= value << o;
+            if o + width > 64 {
+                self.data.push(value >> (64 - o));
+            }
+        }
+        self.len += width;
+    }
+}
+
+// Same layout as simple-sds: the length in bits, then the number of
+// words, then the words themselves, each as a little-endian u64.
+impl Serialize for RawVector {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&(self.len as u64).to_le_bytes())?;
+        writer.write_all(&(self.data.len() as u64).to_le_bytes())?;
+        for w in self.data.iter() {
+            writer.write_all(&w.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn load<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        let len = u64::from_le_bytes(buf) as usize;
+        reader.read_exact(&mut buf)?;
+        let n = u64::from_le_bytes(buf) as usize;
+
+        if n != words_for(len) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "RawVector length does not match the number of words",
+            ));
+        }
+
+        // grown as the words are read, not to trust a corrupted length with
+        // an allocation
+        let mut data = Vec::with_capacity(n.min(1024));
+        for _ in 0..n {
+            reader.read_exact(&mut buf)?;
+            data.push(u64::from_le_bytes(buf));
+        }
+        if len % 64 != 0 && data.last().map_or(false, |w| w & !low_bits(len % 64) != 0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "RawVector has bits set past its length",
+            ));
+        }
+        Ok(Self { len, data })
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        8 * (2 + self.data.len())
+    }
+}
+
+// The serde representation of `simple_sds::serde_compat`: a `RawVector`
+// struct of the length in bits and the words. Written out rather than
+// derived, so that it stays the same as simple-sds' even if the fields here
+// change, and so that inconsistent input is rejected on deserialization.
+pub mod serde_compat {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{low_bits, words_for, RawVector};
+
+    #[derive(Serialize)]
+    #[serde(rename = "RawVector")]
+    struct Repr<'a> {
+        len: usize,
+        data: &'a [u64],
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "RawVector")]
+    struct OwnedRepr {
+        len: usize,
+        data: Vec<u64>,
+    }
+
+    pub fn serialize<S: Serializer>(v: &RawVector, s: S) -> Result<S::Ok, S::Error> {
+        Repr {
+            len: v.len,
+            data: &v.data,
+        }
+        .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<RawVector, D::Error> {
+        let OwnedRepr { len, data } = OwnedRepr::deserialize(d)?;
+        if data.len() != words_for(len) {
+            return Err(D::Error::custom(
+                "RawVector length does not match the number of words",
+            ));
+        }
+        if len % 64 != 0 && data.last().map_or(false, |w| w & !low_bits(len % 64) != 0) {
+            return Err(D::Error::custom("RawVector has bits set past its length"));
+        }
+        Ok(RawVector { len, data })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_and_read() {
+        let mut rv = RawVector::with_capacity(0);
+        let mut bits = Vec::new();
+        for (i, width) in [3, 64, 1, 60, 7, 64, 0, 2].into_iter().enumerate() {
+            let value = 0x9E3779B97F4A7C15_u64.rotate_left(i as u32) & low_bits(width);
+            unsafe { rv.push_int(value, width) };
+            bits.push((rv.len() - width, value, width));
+        }
+
+        for (offset, value, width) in bits {
+            assert_eq!(unsafe { rv.int(offset, width) }, value);
+        }
+    }
+
+    #[test]
+    fn set_int() {
+        let mut rv = RawVector::with_capacity(192);
+        unsafe {
+            for _ in 0..3 {
+                rv.push_int(0, 64);
+            }
+            rv.set_int(60, 0b1011_0110, 8);
+            rv.set_int(0, 0b11, 2);
+            rv.set_int(128, u64::MAX, 64);
+            rv.set_int(130, 0, 4);
+
+            assert_eq!(rv.int(60, 8), 0b1011_0110);
+            assert_eq!(rv.int(56, 16), 0b1011_0110 << 4);
+            assert_eq!(rv.int(0, 4), 0b11);
+            assert_eq!(rv.int(128, 64), !0b111100);
+        }
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let mut rv = RawVector::with_capacity(130);
+        unsafe {
+            rv.push_int(u64::MAX, 64);
+            rv.push_int(0b1011, 4);
+            rv.push_int(0x1234, 62);
+        }
+
+        let mut buf = Vec::new();
+        Serialize::serialize(&rv, &mut buf).unwrap();
+        assert_eq!(buf.len(), rv.size_in_bytes());
+        assert_eq!(RawVector::load(&mut buf.as_slice()).unwrap(), rv);
+    }
+
+    #[test]
+    fn load_rejects_bad_input() {
+        let header = |len: u64, n: u64| [len.to_le_bytes(), n.to_le_bytes()].concat();
+
+        // a huge length is an error, not an allocation failure
+        let buf = header(u64::MAX - 63, u64::MAX / 64);
+        let e = RawVector::load(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let buf = header(70, 1);
+        let e = RawVector::load(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+
+        let mut buf = header(70, 2);
+        buf.extend_from_slice(&u64::MAX.to_le_bytes());
+        buf.extend_from_slice(&(1u64 << 6).to_le_bytes());
+        let e = RawVector::load(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,186 @@
+// A slice of a SeqVector read on the reverse strand, e.g. a unitig mapped
+// to the minus strand, without building its reverse complement. Position i
+// of the view is the complement of base `len - 1 - i` of the forward slice,
+// and the k-mer at position `pos` is the reverse complement of the forward
+// k-mer at `len - pos - k`.
+
+use super::super::prelude::complement_base;
+use super::*;
+
+#[derive(Clone, Debug)]
+pub struct RcSeqVectorSlice<'a> {
+    fw: SeqVectorSlice<'a>,
+}
+
+impl<'a> SeqVectorSlice<'a> {
+    // The reverse complement of the slice, as a view over the same words
+    pub fn rc(&self) -> RcSeqVectorSlice<'a> {
+        RcSeqVectorSlice { fw: self.clone() }
+    }
+}
+
+impl<'a> RcSeqVectorSlice<'a> {
+    // The forward slice this view reads backwards
+    pub fn rc(&self) -> SeqVectorSlice<'a> {
+        self.fw.clone()
+    }
+}
+
+impl RcSeqVectorSlice<'_> {
+    pub fn len(&self) -> usize {
+        self.fw.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fw.is_empty()
+    }
+
+    // where the `k` bases from `pos` of the view start on the forward slice
+    #[inline]
+    fn fw_pos(&self, pos: usize, k: km_size_t) -> usize {
+        assert!(
+            pos + k <= self.len(),
+            "{} bases from {} overflow a slice of {} bases",
+            k,
+            pos,
+            self.len()
+        );
+        self.len() - pos - k
+    }
+
+    pub fn get_kmer(&self, pos: usize, k: km_size_t) -> Kmer {
+        Kmer::from_u64(self.get_kmer_u64(pos, k), k as u8)
+    }
+
+    pub fn get_kmer_u64(&self, pos: usize, k: km_size_t) -> u64 {
+        let word = self.fw.get_kmer_u64(self.fw_pos(pos, k), k);
+        Kmer::get_reverse_complement_word(word, k as u8)
+    }
+
+    pub fn get_base(&self, pos: usize) -> u64 {
+        complement_base(self.fw.get_base(self.fw_pos(pos, 1)))
+    }
+
+    // `start` and `end` are positions of the view, as for
+    // `SeqVectorSlice::slice`
+    pub fn slice(&self, start: usize, end: usize) -> Self {
+        assert!(start <= end);
+        assert!(end <= self.len());
+        Self {
+            fw: self.fw.slice(self.len() - end, self.len() - start),
+        }
+    }
+
+    // A new vector holding the bases of the view
+    pub fn to_owned(&self) -> SeqVector {
+        self.fw.reverse_complement()
+    }
+
+    pub fn iter_kmers(&self, k: km_size_t) -> RcSeqVecKmerIterator<'_> {
+        RcSeqVecKmerIterator {
+            fw: SeqVecKmerIterator::new(self.fw.clone(), k),
+        }
+    }
+}
+
+// The k-mers of a `RcSeqVectorSlice`: those of the forward slice from the
+// last one, reverse complemented.
+pub struct RcSeqVecKmerIterator<'a> {
+    fw: SeqVecKmerIterator<'a>,
+}
+
+impl Iterator for RcSeqVecKmerIterator<'_> {
+    type Item = Kmer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fw.next_back().map(|km| km.to_reverse_complement())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.fw.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for RcSeqVecKmerIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.fw.next().map(|km| km.to_reverse_complement())
+    }
+}
+
+impl ExactSizeIterator for RcSeqVecKmerIterator<'_> {}
+
+impl std::iter::FusedIterator for RcSeqVecKmerIterator<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::random_seq;
+
+    #[test]
+    fn rc_kmers() {
+        let sv = SeqVector::from(random_seq(300, 9).as_slice());
+        for (start, end) in [(0, 300), (1, 33), (31, 97), (64, 128), (5, 6), (7, 7)] {
+            let fw = sv.slice(start, end);
+            let rc = fw.rc();
+            assert_eq!(rc.len(), end - start);
+
+            for k in [1, 2, 15, 31, 32] {
+                let mut expected: Vec<Kmer> = fw
+                    .iter_kmers(k)
+                    .map(|km| km.to_reverse_complement())
+                    .collect();
+                expected.reverse();
+                assert_eq!(
+                    rc.iter_kmers(k).collect::<Vec<_>>(),
+                    expected,
+                    "{}..{}, k = {}",
+                    start,
+                    end,
+                    k
+                );
+                assert_eq!(rc.iter_kmers(k).len(), expected.len());
+
+                let mut from_back: Vec<Kmer> = rc.iter_kmers(k).rev().collect();
+                from_back.reverse();
+                assert_eq!(from_back, expected);
+
+                for (pos, km) in expected.iter().enumerate() {
+                    assert_eq!(rc.get_kmer(pos, k), *km);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rc_bases() {
+        let seq = random_seq(200, 4);
+        let sv = SeqVector::from(seq.as_slice());
+        for (start, end) in [(0, 200), (3, 70), (32, 64), (199, 200)] {
+            let rc = sv.slice(start, end).rc();
+            let owned = sv.slice(start, end).reverse_complement();
+            assert_eq!(rc.to_owned(), owned);
+            for pos in 0..rc.len() {
+                assert_eq!(rc.get_base(pos), owned.get_base(pos));
+            }
+
+            // slicing the view then going back to the forward strand
+            let len = end - start;
+            for (s, e) in [(0, len), (1, len), (0, len / 2), (len / 3, len / 2)] {
+                let sub = rc.slice(s, e);
+                assert_eq!(sub.to_owned(), owned.slice(s, e).to_owned());
+                assert_eq!(sub.rc().to_owned(), sv.slice(end - e, end - s).to_owned());
+            }
+        }
+        assert_eq!(
+            sv.slice(10, 90).rc().rc().to_owned(),
+            sv.slice(10, 90).to_owned()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "5 bases from 8 overflow a slice of 10 bases")]
+    fn rc_kmer_out_of_bounds() {
+        let sv = SeqVector::from(b"ACGTACGTAC".as_slice());
+        sv.as_slice().rc().get_kmer(8, 5);
+    }
+}
@@ -0,0 +1,205 @@
+// A SeqVector that can also hold ambiguous bases (e.g. `N`s or IUPAC codes),
+// so that a whole reference can be stored without splitting it into N-free
+// segments. Ambiguous bases are stored as `A` in the 2-bit sequence, and a
+// bitmap with one bit per position flags which bases are valid.
+
+use super::super::prelude::{decode_binary_u8, encode_binary_u8, is_valid_nuc};
+use super::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SeqVectorN {
+    seq: SeqVector,
+    // bit i is set iff base i is one of `ACGT`
+    #[serde(with = "serde_compat")]
+    valid: RawVector,
+}
+
+impl SeqVectorN {
+    pub fn with_capacity(len: usize) -> Self {
+        Self {
+            seq: SeqVector::with_capacity(len),
+            valid: RawVector::with_capacity(len),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seq.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seq.is_empty()
+    }
+
+    // The 2-bit sequence, in which ambiguous bases read as `A`
+    pub fn as_seq_vector(&self) -> &SeqVector {
+        &self.seq
+    }
+
+    pub fn is_valid(&self, pos: usize) -> bool {
+        assert!(pos < self.len());
+        unsafe { self.valid.int(pos, 1) == 1 }
+    }
+
+    // the base at `pos`, or `None` if it is ambiguous
+    pub fn get_base_checked(&self, pos: usize) -> Option<Base> {
+        if self.is_valid(pos) {
+            Some(self.seq.get_base(pos))
+        } else {
+            None
+        }
+    }
+
+    // Like `SeqVector::push_chars`, but any base other than `ACGTacgt` is
+    // accepted and flagged as invalid.
+    pub fn push_chars(&mut self, bytes: &[u8]) {
+        self.seq.push_chars_lossy(bytes);
+        for chunk in bytes.chunks(64) {
+            let mut bits = 0_u64;
+            for (i, &c) in chunk.iter().enumerate() {
+                bits |= (is_valid_nuc(encode_binary_u8(c)) as u64) << i;
+            }
+            unsafe {
+                self.valid.push_int(bits, chunk.len());
+            }
+        }
+    }
+
+    // The k-mers made of valid bases only, with their position. Like
+    // `CanonicalKmerIterator` on the ASCII sequence, windows overlapping an
+    // ambiguous base are skipped.
+    pub fn iter_kmers(&self, k: km_size_t) -> SeqVecNKmerIterator {
+        SeqVecNKmerIterator::new(self, k)
+    }
+}
+
+impl From<&[u8]> for SeqVectorN {
+    fn from(bytes: &[u8]) -> Self {
+        let mut sv = Self::with_capacity(bytes.len());
+        sv.push_chars(bytes);
+        sv
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for SeqVectorN {
+    fn from(bytes: &[u8; N]) -> Self {
+        Self::from(bytes.as_slice())
+    }
+}
+
+// `N` for the ambiguous bases
+impl std::fmt::Display for SeqVectorN {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s: String = (0..self.len())
+            .map(|i| match self.get_base_checked(i) {
+                Some(b) => decode_binary_u8(b) as char,
+                None => 'N',
+            })
+            .collect();
+        write!(f, "{}", s)
+    }
+}
+
+pub struct SeqVecNKmerIterator<'a> {
+    sv: &'a SeqVectorN,
+    k: usize,
+    // the next base to append
+    pos: usize,
+    // the number of valid bases ending at `pos - 1`
+    run: usize,
+    km: Kmer,
+}
+
+impl<'a> SeqVecNKmerIterator<'a> {
+    pub fn new(sv: &'a SeqVectorN, k: km_size_t) -> Self {
+        assert!(k > 0 && k <= 32, "k must be in 1..=32");
+        Self {
+            sv,
+            k,
+            pos: 0,
+            run: 0,
+            km: Kmer::from_u64(0, k as u8),
+        }
+    }
+}
+
+impl Iterator for SeqVecNKmerIterator<'_> {
+    type Item = (Kmer, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.sv.len() {
+            let pos = self.pos;
+            self.pos += 1;
+
+            match self.sv.get_base_checked(pos) {
+                Some(b) => {
+                    self.km.append_base(b);
+                    self.run += 1;
+                    if self.run >= self.k {
+                        return Some((self.km.clone(), pos + 1 - self.k));
+                    }
+                }
+                None => self.run = 0,
+            }
+        }
+        None
+    }
+}
+
+impl std::iter::FusedIterator for SeqVecNKmerIterator<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::CanonicalKmerIterator;
+    use super::*;
+    use crate::test_utils::random_seq_with_ns;
+
+    #[test]
+    fn get_base_checked() {
+        let sv = SeqVectorN::from(b"ACNgtRN");
+        assert_eq!(sv.len(), 7);
+        let bases: Vec<Option<Base>> = (0..sv.len()).map(|i| sv.get_base_checked(i)).collect();
+        assert_eq!(
+            bases,
+            vec![Some(0), Some(1), None, Some(2), Some(3), None, None]
+        );
+        assert_eq!(sv.to_string(), "ACNGTNN");
+        assert_eq!(sv.as_seq_vector().to_string(), "ACAGTAA");
+    }
+
+    #[test]
+    fn kmers_skip_ambiguous_bases() {
+        let sv = SeqVectorN::from(b"ACGTNACGTTNNAC");
+        let kmers: Vec<(String, usize)> = sv
+            .iter_kmers(4)
+            .map(|(km, pos)| (km.to_string(), pos))
+            .collect();
+        assert_eq!(
+            kmers,
+            vec![
+                ("acgt".to_string(), 0),
+                ("acgt".to_string(), 5),
+                ("cgtt".to_string(), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn kmers_match_canonical_kmer_iterator() {
+        for (len, seed) in [(10, 1), (100, 2), (1000, 3), (5000, 4)] {
+            let seq = random_seq_with_ns(len, seed);
+            let sv = SeqVectorN::from(seq.as_slice());
+
+            for k in [1, 3, 11, 21, 31] {
+                let expected: Vec<(CanonicalKmer, i32)> =
+                    CanonicalKmerIterator::from_u8_slice(&seq, k as u8)
+                        .map(|kp| (kp.km, kp.pos))
+                        .collect();
+                let kmers: Vec<(CanonicalKmer, i32)> = sv
+                    .iter_kmers(k)
+                    .map(|(km, pos)| (CanonicalKmer::from(km), pos as i32))
+                    .collect();
+                assert_eq!(kmers, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// Encoding of ASCII bases into packed 2-bit words for `SeqVector`.
+//
+// With the `simd` feature enabled on x86_64, words are packed with AVX2
+// when the CPU supports it (detected at runtime). Otherwise, and for any
+// chunk containing a byte that is not one of `ACGTacgt`, we fall back to
+// the scalar path, so the result (or the panic) is always identical.
+
+use crate::naive_impl::Kmer;
+
+// Pack up to 32 ASCII bases into a word, 2 bits per base, with the first
+// base in the lowest order bits (the same layout as `Kmer`).
+#[inline]
+pub(crate) fn encode_word_simd(bytes: &[u8]) -> u64 {
+    assert!(bytes.len() <= 32);
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // pad partial words with 'A', which is encoded as 0
+            let mut buf = [b'A'; 32];
+            buf[..bytes.len()].copy_from_slice(bytes);
+            if let Some(w) = unsafe { avx2::encode_word(&buf) } {
+                return w;
+            }
+        }
+    }
+
+    encode_word_scalar(bytes)
+}
+
+#[inline]
+pub(crate) fn encode_word_scalar(bytes: &[u8]) -> u64 {
+    Kmer::from(bytes).into_u64()
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    // returns None if any of the 32 bytes is not a valid base
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn encode_word(bytes: &[u8; 32]) -> Option<u64> {
+        let c = _mm256_loadu_si256(bytes.as_ptr() as *const __m256i);
+
+        // fold to uppercase and compare against each of ACGT
+        let up = _mm256_and_si256(c, _mm256_set1_epi8(0xDFu8 as i8));
+        let is_ac = _mm256_or_si256(
+            _mm256_cmpeq_epi8(up, _mm256_set1_epi8(b'A' as i8)),
+            _mm256_cmpeq_epi8(up, _mm256_set1_epi8(b'C' as i8)),
+        );
+        let is_gt = _mm256_or_si256(
+            _mm256_cmpeq_epi8(up, _mm256_set1_epi8(b'G' as i8)),
+            _mm256_cmpeq_epi8(up, _mm256_set1_epi8(b'T' as i8)),
+        );
+        if _mm256_movemask_epi8(_mm256_or_si256(is_ac, is_gt)) != -1 {
+            return None;
+        }
+
+        // (c >> 1) & 3 maps A, C, G, T to 0, 1, 3, 2 and xor-ing
+        // with (c >> 2) & 1 swaps G and T back to 2 and 3.
+        let hi = _mm256_and_si256(_mm256_srli_epi16(c, 1), _mm256_set1_epi8(3));
+        let lo = _mm256_and_si256(_mm256_srli_epi16(c, 2), _mm256_set1_epi8(1));
+        let codes = _mm256_xor_si256(hi, lo);
+
+        // combine adjacent codes: 2 bases per 16-bit lane, then 4 bases per 32-bit lane
+        let pairs = _mm256_maddubs_epi16(codes, _mm256_set1_epi16(0x0401));
+        let quads = _mm256_madd_epi16(pairs, _mm256_set1_epi32(0x0010_0001));
+
+        // gather the low byte of every 32-bit lane into the low 32 bits of each half
+        let shuf = _mm256_setr_epi8(
+            0, 4, 8, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, //
+            0, 4, 8, 12, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+        );
+        let packed = _mm256_shuffle_epi8(quads, shuf);
+
+        let lo_word = _mm256_extract_epi32(packed, 0) as u32 as u64;
+        let hi_word = _mm256_extract_epi32(packed, 4) as u32 as u64;
+        Some(lo_word | (hi_word << 32))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::SeqVector;
+    use super::*;
+    use crate::test_utils::random_bases;
+
+    fn scalar_words(bytes: &[u8]) -> Vec<u64> {
+        bytes.chunks(32).map(encode_word_scalar).collect()
+    }
+
+    #[test]
+    fn words_match_scalar() {
+        let mut seed = 0x2545F4914F6CDD1D;
+        for len in 0..=32 {
+            for _ in 0..8 {
+                let s = random_bases(len, &mut seed, b"ACGTacgt");
+                assert_eq!(encode_word_simd(&s), encode_word_scalar(&s), "{s:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn seq_vector_matches_scalar() {
+        let mut seed = 0x9E3779B97F4A7C15;
+        for len in 0..=200 {
+            let s = random_bases(len + 3, &mut seed, b"ACGTacgt");
+            for offset in 0..4 {
+                let bytes = &s[offset..offset + len];
+                let sv = SeqVector::from(bytes);
+                assert_eq!(sv.len(), len);
+                assert_eq!(sv.to_string().as_bytes(), bytes.to_ascii_uppercase());
+
+                let words: Vec<u64> = (0..len)
+                    .step_by(32)
+                    .map(|i| sv.get_kmer_u64(i, (len - i).min(32)))
+                    .collect();
+                assert_eq!(words, scalar_words(bytes));
+
+                let mut pushed = SeqVector::with_capacity(len);
+                pushed.push_chars(bytes);
+                assert_eq!(pushed, sv);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_base_panics() {
+        let mut s = vec![b'A'; 32];
+        s[17] = b'N';
+        encode_word_simd(&s);
+    }
+}
@@ -0,0 +1,63 @@
+// Helpers shared by the unit tests. Random inputs come from a small
+// xorshift generator with fixed seeds, so that they are the same on every
+// run, and reverse complements are computed on the ASCII bases, as a
+// reference independent of the packed encodings.
+
+// advances the xorshift64 generator `seed` and returns its new value
+pub(crate) fn xorshift(seed: &mut u64) -> u64 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 7;
+    *seed ^= *seed << 17;
+    *seed
+}
+
+// `len` bases drawn uniformly from `alphabet`
+pub(crate) fn random_bases(len: usize, seed: &mut u64, alphabet: &[u8]) -> Vec<u8> {
+    (0..len)
+        .map(|_| alphabet[(xorshift(seed) % alphabet.len() as u64) as usize])
+        .collect()
+}
+
+// `len` uppercase ACGT bases
+pub(crate) fn random_seq(len: usize, mut seed: u64) -> Vec<u8> {
+    random_bases(len, &mut seed, b"ACGT")
+}
+
+pub(crate) fn random_string(len: usize, seed: u64) -> String {
+    String::from_utf8(random_seq(len, seed)).unwrap()
+}
+
+// bases in either case, with about 1 in 20 of them an `N` or an `n`,
+// sometimes in runs
+pub(crate) fn random_seq_with_ns(len: usize, mut seed: u64) -> Vec<u8> {
+    (0..len)
+        .map(|_| match xorshift(&mut seed) % 40 {
+            0 => b'N',
+            1 => b'n',
+            x => b"ACGTacgt"[(x % 8) as usize],
+        })
+        .collect()
+}
+
+// the reverse complement of an ASCII sequence, keeping the case of each
+// base; other bytes (e.g. `N`s) are left as they are
+pub(crate) fn rc(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|&c| match c {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'T' => b'A',
+            b'a' => b't',
+            b'c' => b'g',
+            b'g' => b'c',
+            b't' => b'a',
+            c => c,
+        })
+        .collect()
+}
+
+pub(crate) fn rc_string(s: &str) -> String {
+    String::from_utf8(rc(s.as_bytes())).unwrap()
+}
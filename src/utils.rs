@@ -29,11 +29,12 @@ pub const fn kmer_space(k: u32) -> usize {
 }
 
 /// Compute the number of possible canonical kmer for a value of k
+/// For even k, the 4^(k/2) kmer that are their own reverse complement are counted once
 pub const fn canonical_space(k: u32) -> usize {
     if k % 2 == 1 {
         2_usize.pow(k * 2) / 2
     } else {
-        2_usize.pow(k * 2) / 2 - (k * 2) as usize
+        2_usize.pow(k * 2) / 2 + 2_usize.pow(k) / 2
     }
 }
 
@@ -61,14 +62,14 @@ mod tests {
     fn canonical_space_size() {
         assert_eq!(canonical_space(0), 0);
         assert_eq!(canonical_space(1), 2);
-        assert_eq!(canonical_space(2), 4);
+        assert_eq!(canonical_space(2), 10);
         assert_eq!(canonical_space(3), 32);
-        assert_eq!(canonical_space(4), 120);
+        assert_eq!(canonical_space(4), 136);
         assert_eq!(canonical_space(5), 512);
-        assert_eq!(canonical_space(6), 2036);
+        assert_eq!(canonical_space(6), 2080);
         assert_eq!(canonical_space(7), 8192);
-        assert_eq!(canonical_space(8), 32752);
+        assert_eq!(canonical_space(8), 32896);
         assert_eq!(canonical_space(9), 131072);
-        assert_eq!(canonical_space(10), 524268);
+        assert_eq!(canonical_space(10), 524800);
     }
 }
@@ -0,0 +1,165 @@
+//! Differential tests of the encode/decode paths: every encoding of the crate is checked against
+//! plain string manipulations on the same random sequences.
+
+/* crates use */
+use quickcheck::{Arbitrary, Gen};
+use quickcheck_macros::quickcheck;
+
+/* project use */
+use kmers::encoding::{Encoding, Naive, Xor10};
+use kmers::naive_impl::seq_vector::SeqVector;
+use kmers::naive_impl::{CanonicalKmer, Kmer};
+
+const MAX_LEN: usize = 200;
+
+/// A random sequence of `ACGT`, of at most `MAX_LEN` bases
+#[derive(Clone, Debug)]
+struct Dna(Vec<u8>);
+
+impl Arbitrary for Dna {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let len = usize::arbitrary(g) % (MAX_LEN + 1);
+        Dna((0..len).map(|_| *g.choose(b"ACGT").unwrap()).collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let seq = self.0.clone();
+        Box::new(
+            (0..seq.len())
+                .rev()
+                .map(move |len| Dna(seq[..len].to_vec())),
+        )
+    }
+}
+
+const ALL_NAIVE: [Naive; 24] = [
+    Naive::ACTG,
+    Naive::ACGT,
+    Naive::ATCG,
+    Naive::ATGC,
+    Naive::AGCT,
+    Naive::AGTC,
+    Naive::CATG,
+    Naive::CAGT,
+    Naive::CTAG,
+    Naive::CTGA,
+    Naive::CGAT,
+    Naive::CGTA,
+    Naive::TACG,
+    Naive::TAGC,
+    Naive::TCAG,
+    Naive::TCGA,
+    Naive::TGAC,
+    Naive::TGCA,
+    Naive::GACT,
+    Naive::GATC,
+    Naive::GCAT,
+    Naive::GCTA,
+    Naive::GTAC,
+    Naive::GTCA,
+];
+
+fn rev_comp(seq: &[u8]) -> Vec<u8> {
+    seq.iter()
+        .rev()
+        .map(|c| match c {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            _ => b'A',
+        })
+        .collect()
+}
+
+/// Encode then decode `seq` in `B` words of `P`, enough to hold `MAX_LEN` bases
+fn round_trip<E, P, const B: usize>(encoder: &E, seq: &[u8]) -> bool
+where
+    E: Encoding<P, B>,
+{
+    let decoded = encoder.decode(encoder.encode(seq));
+    decoded.len() >= seq.len() && decoded[..seq.len()] == *seq
+}
+
+fn round_trip_all_words<E>(encoder: &E, seq: &[u8]) -> bool
+where
+    E: Encoding<u8, 50>
+        + Encoding<u16, 25>
+        + Encoding<u32, 13>
+        + Encoding<u64, 7>
+        + Encoding<u128, 4>,
+{
+    round_trip::<_, u8, 50>(encoder, seq)
+        && round_trip::<_, u16, 25>(encoder, seq)
+        && round_trip::<_, u32, 13>(encoder, seq)
+        && round_trip::<_, u64, 7>(encoder, seq)
+        && round_trip::<_, u128, 4>(encoder, seq)
+}
+
+#[quickcheck]
+fn encodings_round_trip(seq: Dna) -> bool {
+    ALL_NAIVE
+        .iter()
+        .all(|encoder| round_trip_all_words(encoder, &seq.0))
+        && round_trip_all_words(&Xor10, &seq.0)
+}
+
+#[quickcheck]
+fn naive_impl_kmers(seq: Dna) -> bool {
+    let seq = &seq.0[..seq.0.len().min(32)];
+    let s = std::str::from_utf8(seq).unwrap();
+
+    let km = Kmer::from(seq);
+    assert_eq!(km.len(), seq.len());
+    assert_eq!(format!("{:#}", km), s);
+    assert_eq!(Kmer::from_bytes_checked(seq).unwrap(), km);
+    assert_eq!(Kmer::from_u64(km.into_u64(), km.k), km);
+
+    let rc = km.to_reverse_complement();
+    assert_eq!(format!("{:#}", rc).into_bytes(), rev_comp(seq));
+    assert_eq!(rc.to_reverse_complement(), km);
+
+    for pos in 0..seq.len() {
+        for width in 1..=seq.len() - pos {
+            assert_eq!(
+                format!("{:#}", km.sub_kmer(pos, width)),
+                &s[pos..pos + width]
+            );
+        }
+    }
+
+    let ck = CanonicalKmer::from(seq);
+    let fw = format!("{:#}", km);
+    let rc = format!("{:#}", rc);
+    assert_eq!(format!("{:#}", ck.get_fw_mer()), fw);
+    assert_eq!(format!("{:#}", ck.get_rc_mer()), rc);
+    // both orientations have the same canonical form
+    assert_eq!(
+        CanonicalKmer::from(rc.as_str()).get_canonical_word(),
+        ck.get_canonical_word()
+    );
+    true
+}
+
+#[quickcheck]
+fn naive_impl_seq_vector(seq: Dna) -> bool {
+    let seq = &seq.0;
+    let s = std::str::from_utf8(seq).unwrap();
+
+    let sv = SeqVector::from(seq.as_slice());
+    assert_eq!(sv.len(), seq.len());
+    assert_eq!(sv.to_string(), s);
+    assert_eq!(SeqVector::from_bytes_checked(seq).unwrap(), sv);
+
+    for k in [1, 2, 15, 31, 32] {
+        for pos in 0..(seq.len() + 1).saturating_sub(k) {
+            assert_eq!(sv.get_kmer(pos, k), Kmer::from(&seq[pos..pos + k]));
+        }
+    }
+
+    for start in (0..seq.len()).step_by(7) {
+        let slice = sv.slice(start, seq.len());
+        assert_eq!(slice.to_string(), &s[start..]);
+        assert_eq!(slice.to_owned(), SeqVector::from(&seq[start..]));
+    }
+    true
+}